@@ -0,0 +1,76 @@
+//! Feeds to collect from, with the tags given to their words.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// A feed and the tags added to every word collected from it.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeedConfig {
+    pub url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl FeedConfig {
+    /// An untagged feed
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FeedsFile {
+    #[serde(default, rename = "feed")]
+    feeds: Vec<FeedConfig>,
+}
+
+/// Parse a feeds file:
+///
+/// ```toml
+/// [[feed]]
+/// url = "https://blog.rust-lang.org/feed.xml"
+/// tags = ["rust", "Rust blog"]
+/// ```
+pub fn parse_feeds(content: &str) -> Result<Vec<FeedConfig>, String> {
+    let file: FeedsFile = toml::from_str(content).map_err(|e| e.to_string())?;
+    if let Some(feed) = file.feeds.iter().find(|f| f.url.trim().is_empty()) {
+        return Err(format!("Feed with tags {:?} has no url", feed.tags));
+    }
+    Ok(file.feeds)
+}
+
+pub fn load_feeds(path: &Path) -> Result<Vec<FeedConfig>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_feeds(&content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feeds() {
+        let feeds = parse_feeds(
+            r#"
+            [[feed]]
+            url = "https://blog.rust-lang.org/feed.xml"
+            tags = ["rust", "Rust blog"]
+
+            [[feed]]
+            url = "https://example.com/finance.xml"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0].tags, ["rust", "Rust blog"]);
+        assert_eq!(feeds[1], FeedConfig::new("https://example.com/finance.xml"));
+
+        assert!(parse_feeds("").unwrap().is_empty());
+        assert!(parse_feeds("[[feed]]\ntags = [\"x\"]").is_err());
+        assert!(parse_feeds("[[feed]]\nurl = \"\"").is_err());
+    }
+}
@@ -1,27 +1,54 @@
+mod feeds;
+mod review;
+mod runs;
+mod word;
 mod workflow;
 
+use std::io::{BufRead, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
+use spread_core::error::CoreError;
+use spread_core::model::{Deck, SearchHit, Vocabulary, SNIPPET_HIGHLIGHT};
+use spread_core::port::{LlmPort, StoragePort};
+use spread_core::query::{TagFilter, VocabQuery};
+use spread_core::quiz::QuestionKind;
+use spread_core::run::{self as pipeline_run, RunStats, RunTrigger};
+use spread_core::srs::Algorithm;
+use spread_core::timezone::{self, Tz};
 use spread_fetcher::RssFetcher;
-use spread_integration::{MarkdownExporter, McpServer};
-use spread_llm::MockLlmEngine;
+use spread_integration::{AnswerGrader, MarkdownExporter, McpServer, QuizGenerator};
+use spread_llm::{Dictionary, FrequencyExtractor, GeminiLlmEngine, MockLlmEngine};
 use spread_notify::Notifier;
-use spread_storage::SqliteStorage;
+use spread_storage::{
+    AnyStorage, ConflictStrategy, Dump, DumpFormat, InMemoryStorage, RetentionPolicy,
+    SqliteStorage, DEFAULT_KEEP_BACKUPS, ENV_RETENTION_DAYS,
+};
+
+use crate::feeds::FeedConfig;
 
 /// Default RSS feed URLs for testing
 const DEFAULT_FEED_URLS: &[&str] = &["https://blog.rust-lang.org/feed.xml"];
 
-/// Default SQLite database path
+/// Database used when SPREAD_DATABASE_URL is not set
 const DEFAULT_DB_URL: &str = "sqlite:spread.db?mode=rwc";
 
 /// Environment variable names
 const ENV_OBSIDIAN_VAULT_PATH: &str = "OBSIDIAN_VAULT_PATH";
 const ENV_OBSIDIAN_NOTE_PATH: &str = "OBSIDIAN_NOTE_PATH";
 const ENV_OBSIDIAN_INBOX_PATH: &str = "OBSIDIAN_INBOX_PATH";
+const ENV_DICTIONARY_PATH: &str = "SPREAD_DICTIONARY_PATH";
+const ENV_FEEDS_PATH: &str = "SPREAD_FEEDS_PATH";
+const ENV_BACKUP_DIR: &str = "SPREAD_BACKUP_DIR";
+const ENV_DATABASE_URL: &str = "SPREAD_DATABASE_URL";
+
+/// Backup directory used when neither --to nor SPREAD_BACKUP_DIR is given
+const DEFAULT_BACKUP_DIR: &str = "backups";
 
 #[derive(Parser)]
 #[command(name = "spread")]
@@ -38,6 +65,22 @@ enum Commands {
         /// Obsidian vault path for exporting vocabulary (overrides OBSIDIAN_VAULT_PATH env)
         #[arg(long)]
         obsidian_path: Option<PathBuf>,
+        /// Vocabulary extractor to use
+        #[arg(long, value_enum, default_value_t = Extractor::Mock)]
+        extractor: Extractor,
+        /// Local dictionary (JSON or word<TAB>definition) for the frequency extractor
+        /// (overrides SPREAD_DICTIONARY_PATH env)
+        #[arg(long)]
+        dictionary: Option<PathBuf>,
+        /// TOML file listing feeds and their tags (overrides SPREAD_FEEDS_PATH env)
+        #[arg(long)]
+        feeds: Option<PathBuf>,
+        /// Show what would be saved without writing to the database
+        #[arg(long)]
+        dry_run: bool,
+        /// What started the run, as recorded in `spread runs` (manual or scheduled)
+        #[arg(long, default_value_t = RunTrigger::Manual)]
+        trigger: RunTrigger,
     },
     /// Start MCP server (stdio mode)
     Mcp,
@@ -46,6 +89,11 @@ enum Commands {
         /// Obsidian vault path (overrides OBSIDIAN_VAULT_PATH env)
         #[arg(long)]
         obsidian_path: Option<PathBuf>,
+        /// Only export words collected on this local day (YYYY-MM-DD)
+        #[arg(long)]
+        date: Option<NaiveDate>,
+        #[command(flatten)]
+        tags: TagArgs,
     },
     /// Send daily vocabulary notification via Telegram
     Notify {
@@ -55,9 +103,295 @@ enum Commands {
         /// Test mode: use all vocabulary if today's is empty
         #[arg(long)]
         test: bool,
+        /// Send quiz questions about the words instead of the word list
+        #[arg(long)]
+        quiz: bool,
+        /// Send words collected on this local day (YYYY-MM-DD) instead of today
+        #[arg(long, conflicts_with = "all")]
+        date: Option<NaiveDate>,
+        #[command(flatten)]
+        tags: TagArgs,
+    },
+    /// Print quiz questions generated from stored vocabulary
+    Quiz {
+        /// Number of questions
+        #[arg(long, default_value_t = 5)]
+        count: usize,
+        /// Question types to use (repeatable; all types by default)
+        #[arg(long = "type", value_name = "TYPE")]
+        kinds: Vec<QuestionKind>,
+        /// Seed for reproducible questions
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Ask the questions one by one, grade typed answers and record them as reviews
+        #[arg(long)]
+        graded: bool,
+    },
+    /// Full-text search over words, definitions, contexts and articles
+    Search {
+        /// Terms match as prefixes; wrap text in double quotes for a phrase
+        query: String,
+        /// Maximum word and article results each
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Manage words you already know (skipped during extraction)
+    Known {
+        #[command(subcommand)]
+        action: KnownAction,
+    },
+    /// Show, correct and curate stored words
+    Word {
+        #[command(subcommand)]
+        action: WordAction,
+    },
+    /// Tag words by topic
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+    /// Manage decks: named sets of tags to study together
+    Deck {
+        #[command(subcommand)]
+        action: DeckAction,
+    },
+    /// Review due words with spaced repetition
+    Review {
+        /// Maximum number of words in this session
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Maximum number of never-reviewed words to introduce
+        #[arg(long = "new", default_value_t = 10)]
+        new_words: usize,
+        /// Type answers to quiz questions instead of grading yourself
+        #[arg(long)]
+        typed: bool,
+        /// Question types for typed reviews (repeatable; all types by default)
+        #[arg(long = "type", value_name = "TYPE", requires = "typed")]
+        kinds: Vec<QuestionKind>,
+        #[command(flatten)]
+        tags: TagArgs,
+    },
+    /// Copy the database to a timestamped file, safe while other commands run
+    Backup {
+        /// Directory to write backups to (overrides SPREAD_BACKUP_DIR env)
+        #[arg(long)]
+        to: Option<PathBuf>,
+        /// Number of backups to keep in the directory; older ones are deleted
+        #[arg(long, default_value_t = DEFAULT_KEEP_BACKUPS, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        keep: usize,
+    },
+    /// Replace the database with a backup (stop running commands first)
+    Restore {
+        /// Backup file written by `spread backup`
+        file: PathBuf,
+        /// Do not ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Write every article, word, tag and review as a portable JSON document
+    Dump {
+        /// File to write (stdout if omitted)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// json or ndjson (default: from the file extension, else json)
+        #[arg(long)]
+        format: Option<DumpFormat>,
+    },
+    /// Import a dump written by `spread dump` (`-` reads stdin)
+    Load {
+        file: PathBuf,
+        /// What to do with words and articles already stored: skip, overwrite,
+        /// or merge (keep stored fields, add missing contexts, tags and reviews)
+        #[arg(long, default_value = "merge")]
+        strategy: ConflictStrategy,
+    },
+    /// Inspect and migrate the database schema
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Show recorded pipeline runs and failing feeds
+    Runs {
+        #[command(subcommand)]
+        action: RunsAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbAction {
+    /// Apply pending schema migrations (a backup copy is taken first)
+    Migrate {
+        /// List pending migrations without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show the current schema version
+    Version,
+    /// Cut old article bodies down to the sentences their words were seen in
+    Compact {
+        /// Keep full bodies for this many days (overrides SPREAD_RETENTION_DAYS env)
+        #[arg(long)]
+        days: Option<u32>,
+        /// Also delete old articles that gave no words, remembering only a URL
+        /// fingerprint so they are not collected again
+        #[arg(long)]
+        prune_empty: bool,
+    },
+    /// Rebuild the database file and report the space reclaimed
+    Vacuum,
+}
+
+#[derive(Subcommand)]
+enum RunsAction {
+    /// List recent runs, newest first, and feeds whose latest run failed
+    List {
+        /// Number of runs to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show a run with its per-feed results and errors
+    Show { id: i64 },
+}
+
+#[derive(Subcommand)]
+enum KnownAction {
+    /// Add words to the known list
+    Add {
+        #[arg(required = true)]
+        words: Vec<String>,
+    },
+    /// Remove words from the known list
+    Remove {
+        #[arg(required = true)]
+        words: Vec<String>,
+    },
+    /// Import words from a plain text file (one word per line, `#` starts a comment)
+    /// or from mastered review items
+    #[command(group(ArgGroup::new("source").required(true).args(["file", "mastered"])))]
+    Import {
+        file: Option<PathBuf>,
+        /// Import words whose review interval reached the mastery threshold
+        #[arg(long)]
+        mastered: bool,
+    },
+    /// List known words
+    List,
+}
+
+/// Tag and deck filters shared by review, export and notify
+#[derive(Args)]
+struct TagArgs {
+    /// Only words with this tag
+    #[arg(long)]
+    tag: Option<String>,
+    /// Only words with any tag of this deck
+    #[arg(long)]
+    deck: Option<String>,
+}
+
+impl From<TagArgs> for TagFilter {
+    fn from(args: TagArgs) -> Self {
+        Self {
+            tag: args.tag,
+            deck: args.deck,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum TagAction {
+    /// Add tags to a word
+    Add {
+        /// Word or id
+        word: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Remove tags from a word
+    Remove {
+        /// Word or id
+        word: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// List all tags with their word counts, or the tags of one word
+    List {
+        /// Word or id
+        word: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DeckAction {
+    /// Create a deck, or replace the tags of an existing one
+    Create {
+        name: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Delete a deck (its words and tags are kept)
+    Delete { name: String },
+    /// List decks and their tags
+    List,
+}
+
+#[derive(Subcommand)]
+enum WordAction {
+    /// Show a word with all its contexts
+    Show {
+        /// Word or id
+        word: String,
+    },
+    /// Edit a word in $EDITOR; edited fields are kept when the word is extracted again
+    Edit {
+        /// Word or id
+        word: String,
+    },
+    /// Delete a word with its contexts and review history
+    Delete {
+        /// Word or id
+        word: String,
+        /// Do not ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Merge one word into another, keeping all contexts (e.g. analyse into analyze)
+    Merge {
+        /// Word or id to merge away; its spelling becomes an alias
+        from: String,
+        /// Word or id to keep
+        into: String,
+    },
+    /// Exclude a word from reviews
+    Suspend {
+        /// Word or id
+        word: String,
+        /// Include the word in reviews again
+        #[arg(long)]
+        resume: bool,
     },
 }
 
+/// Vocabulary extraction backends
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Extractor {
+    /// Gemini API (requires GEMINI_API_KEY)
+    Gemini,
+    /// Offline extraction from the embedded frequency/CEFR word list
+    Frequency,
+    /// Placeholder words for testing
+    Mock,
+}
+
+/// Timezone from `SPREAD_TIMEZONE`, UTC when unset or invalid
+fn load_timezone() -> Tz {
+    timezone::timezone_from_env().unwrap_or_else(|e| {
+        warn!(error = %e, "Invalid {}, using UTC", timezone::ENV_TIMEZONE);
+        Tz::UTC
+    })
+}
+
 /// Get Obsidian export path from CLI arg or environment variables
 fn get_obsidian_path(cli_path: Option<PathBuf>) -> Option<PathBuf> {
     // CLI arg takes precedence
@@ -115,30 +449,212 @@ async fn main() {
 
     match cli.command {
         Some(Commands::Mcp) => run_mcp_server().await,
-        Some(Commands::Export { obsidian_path }) => {
+        Some(Commands::Export {
+            obsidian_path,
+            date,
+            tags,
+        }) => {
             let path = get_obsidian_path(obsidian_path);
             if let Some(p) = path {
-                run_export(p).await;
+                run_export(p, date, tags.into()).await;
             } else {
                 error!("No Obsidian path provided. Use --obsidian-path or set OBSIDIAN_VAULT_PATH/OBSIDIAN_NOTE_PATH in .env");
             }
         }
-        Some(Commands::Run { obsidian_path }) => {
-            run_pipeline(get_obsidian_path(obsidian_path)).await
+        Some(Commands::Run {
+            obsidian_path,
+            extractor,
+            dictionary,
+            feeds,
+            dry_run,
+            trigger,
+        }) => {
+            run_pipeline(
+                get_obsidian_path(obsidian_path),
+                extractor,
+                dictionary,
+                feeds,
+                dry_run,
+                trigger,
+            )
+            .await
+        }
+        Some(Commands::Notify {
+            all,
+            test,
+            quiz,
+            date,
+            tags,
+        }) => run_notify(all, test, quiz, date, tags.into()).await,
+        Some(Commands::Quiz {
+            count,
+            kinds,
+            seed,
+            graded,
+        }) => run_quiz(count, kinds, seed, graded).await,
+        Some(Commands::Search { query, limit }) => run_search(&query, limit).await,
+        Some(Commands::Known { action }) => run_known(action).await,
+        Some(Commands::Word { action }) => run_word(action).await,
+        Some(Commands::Tag { action }) => run_tag(action).await,
+        Some(Commands::Deck { action }) => run_deck(action).await,
+        Some(Commands::Review {
+            limit,
+            new_words,
+            typed,
+            kinds,
+            tags,
+        }) => {
+            let session = review::Session::new(limit, new_words).with_filter(tags.into());
+            run_review(session, typed, kinds).await
+        }
+        Some(Commands::Backup { to, keep }) => run_backup(to, keep).await,
+        Some(Commands::Restore { file, yes }) => run_restore(&file, yes).await,
+        Some(Commands::Dump { output, format }) => run_dump(output.as_deref(), format).await,
+        Some(Commands::Load { file, strategy }) => run_load(&file, strategy).await,
+        Some(Commands::Db { action }) => run_db(action).await,
+        Some(Commands::Runs { action }) => run_runs(action).await,
+        None => {
+            run_pipeline(
+                get_obsidian_path(None),
+                Extractor::Mock,
+                None,
+                None,
+                false,
+                RunTrigger::Manual,
+            )
+            .await
         }
-        Some(Commands::Notify { all, test }) => run_notify(all, test).await,
-        None => run_pipeline(get_obsidian_path(None)).await,
     }
 }
 
-async fn run_pipeline(obsidian_path: Option<PathBuf>) {
-    info!("Starting spread pipeline");
+/// Build the configured vocabulary extractor
+fn build_extractor(
+    extractor: Extractor,
+    dictionary_path: Option<PathBuf>,
+    known_words: Vec<String>,
+) -> Option<Box<dyn LlmPort>> {
+    match extractor {
+        Extractor::Gemini => match GeminiLlmEngine::new() {
+            Ok(engine) => Some(Box::new(engine.with_known_words(known_words))),
+            Err(e) => {
+                error!(error = %e, "Failed to initialize Gemini");
+                None
+            }
+        },
+        Extractor::Frequency => {
+            let mut engine = FrequencyExtractor::new().with_known_words(known_words);
+            let dictionary_path = dictionary_path.or_else(|| {
+                std::env::var(ENV_DICTIONARY_PATH)
+                    .ok()
+                    .filter(|s| !s.is_empty())
+                    .map(PathBuf::from)
+            });
+            if let Some(path) = dictionary_path {
+                match Dictionary::load(&path) {
+                    Ok(dictionary) => {
+                        info!(path = %path.display(), entries = dictionary.len(), "Loaded dictionary");
+                        engine = engine.with_dictionary(dictionary);
+                    }
+                    Err(e) => {
+                        warn!(path = %path.display(), error = %e, "Failed to load dictionary, continuing without definitions");
+                    }
+                }
+            }
+            Some(Box::new(engine))
+        }
+        Extractor::Mock => Some(Box::new(MockLlmEngine::new())),
+    }
+}
+
+/// Feeds from the feeds file given on the command line or in the environment,
+/// or the untagged default feeds. `None` if the file cannot be loaded.
+fn load_feed_configs(cli_path: Option<PathBuf>) -> Option<Vec<FeedConfig>> {
+    let path = cli_path.or_else(|| {
+        std::env::var(ENV_FEEDS_PATH)
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+    });
+    let Some(path) = path else {
+        return Some(
+            DEFAULT_FEED_URLS
+                .iter()
+                .copied()
+                .map(FeedConfig::new)
+                .collect(),
+        );
+    };
+
+    match feeds::load_feeds(&path) {
+        Ok(feeds) => {
+            info!(path = %path.display(), feeds = feeds.len(), "Loaded feeds");
+            Some(feeds)
+        }
+        Err(e) => {
+            error!(path = %path.display(), error = %e, "Failed to load feeds");
+            None
+        }
+    }
+}
+
+/// Database URL from SPREAD_DATABASE_URL; `sqlite:` or, with the `postgres`
+/// feature, `postgres://`
+fn database_url() -> String {
+    std::env::var(ENV_DATABASE_URL).unwrap_or_else(|_| DEFAULT_DB_URL.to_string())
+}
+
+/// Database URL for commands that work on the SQLite file itself
+fn sqlite_url(command: &str) -> Option<String> {
+    let url = database_url();
+    if url.starts_with("sqlite:") {
+        Some(url)
+    } else {
+        error!("`spread {}` only supports SQLite databases", command);
+        None
+    }
+}
+
+async fn run_pipeline(
+    obsidian_path: Option<PathBuf>,
+    extractor: Extractor,
+    dictionary_path: Option<PathBuf>,
+    feeds_path: Option<PathBuf>,
+    dry_run: bool,
+    trigger: RunTrigger,
+) {
+    info!(extractor = ?extractor, dry_run, %trigger, "Starting spread pipeline");
+
+    let Some(feeds) = load_feed_configs(feeds_path) else {
+        return;
+    };
 
     // Initialize dependencies
     let fetcher = RssFetcher::new();
-    let llm = MockLlmEngine::new();
 
-    let storage = match SqliteStorage::new(DEFAULT_DB_URL).await {
+    if dry_run {
+        // Read-only, so the database is neither created nor migrated
+        match AnyStorage::open_read_only(&database_url()).await {
+            Ok(storage) => match storage.pending_migrations().await {
+                Ok(0) => {
+                    dry_run_pipeline(&feeds, &fetcher, &storage, extractor, dictionary_path).await
+                }
+                Ok(pending) => error!(
+                    pending,
+                    "Database has pending migrations; run `spread db migrate` before a dry run"
+                ),
+                Err(e) => error!(error = %e, "Failed to check migrations"),
+            },
+            Err(CoreError::NotFound(_)) => {
+                info!("No database yet, previewing as if it were empty");
+                let empty = InMemoryStorage::new();
+                dry_run_pipeline(&feeds, &fetcher, &empty, extractor, dictionary_path).await;
+            }
+            Err(e) => error!(error = %e, "Failed to open database"),
+        }
+        return;
+    }
+
+    let storage = match AnyStorage::new(&database_url()).await {
         Ok(s) => s,
         Err(e) => {
             error!(error = %e, "Failed to initialize storage");
@@ -146,22 +662,71 @@ async fn run_pipeline(obsidian_path: Option<PathBuf>) {
         }
     };
 
+    let known_words = load_known_words(&storage).await;
+    let Some(llm) = build_extractor(extractor, dictionary_path, known_words.clone()) else {
+        return;
+    };
+
     info!("Initialized all dependencies");
 
     // Run the pipeline
-    match workflow::run_pipeline(DEFAULT_FEED_URLS, &fetcher, &storage, &llm).await {
-        Ok(stats) => {
+    let cancel = cancel_on_signal();
+    match workflow::run_pipeline(
+        &feeds,
+        &fetcher,
+        &storage,
+        llm.as_ref(),
+        &known_words,
+        trigger,
+        &cancel,
+    )
+    .await
+    {
+        Ok(run) => {
+            let stats = &run.stats;
             info!(
+                run_id = run.id,
                 articles_saved = stats.articles_saved,
                 vocabularies_saved = stats.vocabularies_saved,
+                vocabularies_rejected = stats.vocabularies_rejected,
                 skipped = stats.skipped_duplicates,
+                resumed = stats.resumed_items,
                 "Pipeline completed successfully"
             );
 
+            flag_broken_feeds(&storage, &feeds).await;
+
+            if run.cancelled {
+                let queued = storage
+                    .get_queued_items()
+                    .await
+                    .map_or(0, |items| items.len());
+                println!(
+                    "Run cancelled after {} article(s); {} item(s) stay queued for the next run",
+                    stats.articles_saved, queued
+                );
+                return;
+            }
+
+            if let Some(policy) = RetentionPolicy::from_env() {
+                match storage.as_sqlite() {
+                    Some(sqlite) => match sqlite.compact_articles(&policy).await {
+                        Ok(report) => info!(
+                            compacted = report.compacted,
+                            pruned = report.pruned,
+                            bytes_removed = report.content_bytes_removed,
+                            "Applied article retention"
+                        ),
+                        Err(e) => error!(error = %e, "Failed to compact articles"),
+                    },
+                    None => warn!("Article retention is only applied to SQLite databases"),
+                }
+            }
+
             // Export to Obsidian if path is provided
             if let Some(path) = obsidian_path {
                 info!(path = %path.display(), "Exporting vocabulary to Obsidian");
-                export_to_obsidian(&storage, &path).await;
+                export_to_obsidian(&storage, &path, load_timezone(), None, &TagFilter::new()).await;
             } else {
                 warn!("No Obsidian path configured. Set OBSIDIAN_VAULT_PATH or OBSIDIAN_NOTE_PATH in .env to auto-export");
             }
@@ -172,27 +737,964 @@ async fn run_pipeline(obsidian_path: Option<PathBuf>) {
     }
 }
 
+async fn load_known_words<S: StoragePort>(storage: &S) -> Vec<String> {
+    match storage.get_known_words().await {
+        Ok(words) => words,
+        Err(e) => {
+            warn!(error = %e, "Failed to load known words");
+            Vec::new()
+        }
+    }
+}
+
+/// Run the pipeline against `storage` without writing to it and print the result.
+async fn dry_run_pipeline<S: StoragePort>(
+    feeds: &[FeedConfig],
+    fetcher: &RssFetcher,
+    storage: &S,
+    extractor: Extractor,
+    dictionary_path: Option<PathBuf>,
+) {
+    let known_words = load_known_words(storage).await;
+    let Some(llm) = build_extractor(extractor, dictionary_path, known_words.clone()) else {
+        return;
+    };
+
+    let cancel = cancel_on_signal();
+    match workflow::dry_run_pipeline(feeds, fetcher, storage, llm.as_ref(), &known_words, &cancel)
+        .await
+    {
+        Ok((stats, preview)) => print_dry_run(storage, &preview, &stats).await,
+        Err(e) => error!(error = %e, "Pipeline failed"),
+    }
+}
+
+/// Print the articles and words a dry run would have saved. Words not in
+/// `storage` yet are marked `+`, words that would gain a new context `~`.
+async fn print_dry_run<S: StoragePort>(storage: &S, preview: &InMemoryStorage, stats: &RunStats) {
+    let words = match preview.query_vocab(&VocabQuery::new()).await {
+        Ok(page) => page.vocabulary,
+        Err(e) => {
+            error!(error = %e, "Failed to read dry run results");
+            return;
+        }
+    };
+
+    let mut new_words = 0;
+    for article in preview.articles() {
+        println!("{} <{}>", article.title, article.url);
+        for vocab in words
+            .iter()
+            .filter(|v| v.occurrences.iter().any(|o| o.source_url == article.url))
+        {
+            let is_new = matches!(storage.get_vocab_by_word(&vocab.word).await, Ok(None));
+            new_words += usize::from(is_new);
+            let tags = if vocab.tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", vocab.tags.join(" "))
+            };
+            println!(
+                "  {} {} — {}{}",
+                if is_new { '+' } else { '~' },
+                vocab.word,
+                vocab.definition,
+                tags
+            );
+        }
+    }
+
+    println!(
+        "\nDry run: would save {} article(s) and {} word(s) ({} new); {} rejected, {} already saved. Nothing was written.",
+        stats.articles_saved,
+        stats.vocabularies_saved,
+        new_words,
+        stats.vocabularies_rejected,
+        stats.skipped_duplicates
+    );
+}
+
+/// A token cancelled on the first Ctrl-C (or SIGTERM), so the pipeline stops
+/// after the current item. A second Ctrl-C exits at once.
+fn cancel_on_signal() -> CancellationToken {
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        if !shutdown_signal().await {
+            return;
+        }
+        println!("Stopping after the current item; press Ctrl-C again to quit now");
+        token.cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(130);
+        }
+    });
+    cancel
+}
+
+/// Wait for Ctrl-C or, on Unix, SIGTERM. `false` if no handler could be installed.
+async fn shutdown_signal() -> bool {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            return tokio::select! {
+                result = tokio::signal::ctrl_c() => result.is_ok() || terminate.recv().await.is_some(),
+                _ = terminate.recv() => true,
+            };
+        }
+    }
+    tokio::signal::ctrl_c().await.is_ok()
+}
+
+/// Report configured feeds that have failed too many runs in a row.
+async fn flag_broken_feeds(storage: &AnyStorage, feeds: &[FeedConfig]) {
+    let runs = match storage.get_runs(Some(pipeline_run::FEED_HEALTH_RUNS)).await {
+        Ok(runs) => runs,
+        Err(e) => {
+            warn!(error = %e, "Failed to load runs for feed health");
+            return;
+        }
+    };
+    for health in pipeline_run::feed_health(&runs) {
+        if health.is_broken() && feeds.iter().any(|feed| feed.url == health.feed_url) {
+            error!(
+                feed_url = %health.feed_url,
+                consecutive_failures = health.consecutive_failures,
+                last_success = ?health.last_success,
+                last_error = health.last_error.as_deref().unwrap_or_default(),
+                "Feed is broken; fix or remove it from the feeds file"
+            );
+        }
+    }
+}
+
+async fn run_runs(action: RunsAction) {
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+    let tz = load_timezone();
+
+    match action {
+        RunsAction::List { limit } => {
+            let recent = match storage.get_runs(Some(limit)).await {
+                Ok(runs) if runs.is_empty() => {
+                    println!("No runs recorded yet");
+                    return;
+                }
+                Ok(runs) => runs,
+                Err(e) => {
+                    error!(error = %e, "Failed to list runs");
+                    return;
+                }
+            };
+            for run in &recent {
+                println!("{}", runs::format_run_line(run, tz));
+            }
+
+            // Health looks further back than the listed runs
+            match storage.get_runs(Some(pipeline_run::FEED_HEALTH_RUNS)).await {
+                Ok(latest) => {
+                    let failing: Vec<_> = pipeline_run::feed_health(&latest)
+                        .into_iter()
+                        .filter(|health| health.consecutive_failures > 0)
+                        .collect();
+                    if !failing.is_empty() {
+                        println!("\nFailing feeds:");
+                        for health in &failing {
+                            println!("  {}", runs::format_feed_health(health, tz));
+                        }
+                    }
+                }
+                Err(e) => error!(error = %e, "Failed to load runs for feed health"),
+            }
+        }
+        RunsAction::Show { id } => match storage.get_run(id).await {
+            Ok(Some(run)) => println!("{}", runs::format_run(&run, tz)),
+            Ok(None) => println!("No run #{}", id),
+            Err(e) => error!(error = %e, "Failed to load run"),
+        },
+    }
+}
+
+async fn run_known(action: KnownAction) {
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+
+    match action {
+        KnownAction::Add { words } => match storage.add_known_words(&words).await {
+            Ok(added) => println!("Added {} known word(s)", added),
+            Err(e) => error!(error = %e, "Failed to add known words"),
+        },
+        KnownAction::Remove { words } => {
+            for word in words {
+                match storage.remove_known_word(&word).await {
+                    Ok(true) => println!("Removed '{}'", word),
+                    Ok(false) => println!("'{}' is not in the known list", word),
+                    Err(e) => error!(word = %word, error = %e, "Failed to remove known word"),
+                }
+            }
+        }
+        KnownAction::Import { mastered: true, .. } => {
+            let words = match storage.get_mastered_words().await {
+                Ok(w) => w,
+                Err(e) => {
+                    error!(error = %e, "Failed to load mastered words");
+                    return;
+                }
+            };
+            match storage.add_known_words(&words).await {
+                Ok(added) => println!(
+                    "Imported {} new known word(s) ({} mastered)",
+                    added,
+                    words.len()
+                ),
+                Err(e) => error!(error = %e, "Failed to import known words"),
+            }
+        }
+        KnownAction::Import {
+            file: Some(file), ..
+        } => {
+            let content = match std::fs::read_to_string(&file) {
+                Ok(c) => c,
+                Err(e) => {
+                    error!(path = %file.display(), error = %e, "Failed to read word list");
+                    return;
+                }
+            };
+            let words = parse_word_list(&content);
+            match storage.add_known_words(&words).await {
+                Ok(added) => println!(
+                    "Imported {} new known word(s) ({} in file)",
+                    added,
+                    words.len()
+                ),
+                Err(e) => error!(error = %e, "Failed to import known words"),
+            }
+        }
+        KnownAction::Import { file: None, .. } => {
+            unreachable!("clap requires a file or --mastered")
+        }
+        KnownAction::List => match storage.get_known_words().await {
+            Ok(words) if words.is_empty() => println!("No known words yet"),
+            Ok(words) => {
+                for word in &words {
+                    println!("{}", word);
+                }
+            }
+            Err(e) => error!(error = %e, "Failed to list known words"),
+        },
+    }
+}
+
+async fn run_word(action: WordAction) {
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+
+    let key = match &action {
+        WordAction::Show { word }
+        | WordAction::Edit { word }
+        | WordAction::Delete { word, .. }
+        | WordAction::Suspend { word, .. } => word,
+        WordAction::Merge { from, .. } => from,
+    };
+    let vocab = match word::find_word(&storage, key).await {
+        Ok(Some(v)) => v,
+        Ok(None) => {
+            println!("No word '{}'", key);
+            return;
+        }
+        Err(e) => {
+            error!(word = %key, error = %e, "Failed to load word");
+            return;
+        }
+    };
+    let id = vocab.id.unwrap_or_default();
+
+    match action {
+        WordAction::Show { .. } => println!("{}", word::format_word(&vocab)),
+        WordAction::Edit { .. } => {
+            let mut text = match word::render_document(&vocab) {
+                Ok(t) => t,
+                Err(e) => {
+                    error!(error = %e, "Failed to render word");
+                    return;
+                }
+            };
+            // Reopen the editor on parse errors so the changes are not lost
+            let edited = loop {
+                text = match word::edit_text(&text) {
+                    Ok(t) => t,
+                    Err(e) => {
+                        error!(error = %e, "Failed to run editor");
+                        return;
+                    }
+                };
+                match word::apply_document(&vocab, &text) {
+                    Ok(edited) => break edited,
+                    Err(e) => {
+                        println!("Invalid word: {}", e);
+                        if !confirm("Edit again?") {
+                            return;
+                        }
+                    }
+                }
+            };
+            match edited {
+                None => println!("No changes"),
+                Some(edited) => match storage.update_vocab(&edited).await {
+                    Ok(()) => println!("Updated '{}'", edited.word),
+                    Err(e) => error!(error = %e, "Failed to update word"),
+                },
+            }
+        }
+        WordAction::Delete { yes, .. } => {
+            let prompt = format!(
+                "Delete '{}' with {} context(s)?",
+                vocab.word,
+                vocab.occurrences.len()
+            );
+            if !yes && !confirm(&prompt) {
+                return;
+            }
+            match storage.delete_vocab(id).await {
+                Ok(_) => println!("Deleted '{}'", vocab.word),
+                Err(e) => error!(error = %e, "Failed to delete word"),
+            }
+        }
+        WordAction::Merge { into, .. } => {
+            let target = match word::find_word(&storage, &into).await {
+                Ok(Some(v)) => v,
+                Ok(None) => {
+                    println!("No word '{}'", into);
+                    return;
+                }
+                Err(e) => {
+                    error!(word = %into, error = %e, "Failed to load word");
+                    return;
+                }
+            };
+            match storage.merge_vocab(id, target.id.unwrap_or_default()).await {
+                Ok(merged) => println!(
+                    "Merged '{}' into '{}' ({} context(s))",
+                    vocab.word,
+                    merged.word,
+                    merged.occurrences.len()
+                ),
+                Err(e) => error!(error = %e, "Failed to merge words"),
+            }
+        }
+        WordAction::Suspend { resume, .. } => {
+            let updated = Vocabulary {
+                suspended: !resume,
+                ..vocab
+            };
+            match storage.update_vocab(&updated).await {
+                Ok(()) if resume => println!("Resumed '{}'", updated.word),
+                Ok(()) => println!("Suspended '{}'", updated.word),
+                Err(e) => error!(error = %e, "Failed to update word"),
+            }
+        }
+    }
+}
+
+async fn run_tag(action: TagAction) {
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+
+    let key = match &action {
+        TagAction::Add { word, .. } | TagAction::Remove { word, .. } => word,
+        TagAction::List { word: Some(word) } => word,
+        TagAction::List { word: None } => {
+            match storage.get_tags().await {
+                Ok(tags) if tags.is_empty() => println!("No tags yet"),
+                Ok(tags) => {
+                    for (tag, count) in &tags {
+                        println!("{:<24} {}", tag, count);
+                    }
+                }
+                Err(e) => error!(error = %e, "Failed to list tags"),
+            }
+            return;
+        }
+    };
+    let vocab = match word::find_word(&storage, key).await {
+        Ok(Some(v)) => v,
+        Ok(None) => {
+            println!("No word '{}'", key);
+            return;
+        }
+        Err(e) => {
+            error!(word = %key, error = %e, "Failed to load word");
+            return;
+        }
+    };
+    let id = vocab.id.unwrap_or_default();
+
+    match action {
+        TagAction::Add { tags, .. } => match storage.add_tags(id, &tags).await {
+            Ok(added) => println!("Added {} tag(s) to '{}'", added, vocab.word),
+            Err(e) => error!(error = %e, "Failed to add tags"),
+        },
+        TagAction::Remove { tags, .. } => match storage.remove_tags(id, &tags).await {
+            Ok(removed) => println!("Removed {} tag(s) from '{}'", removed, vocab.word),
+            Err(e) => error!(error = %e, "Failed to remove tags"),
+        },
+        TagAction::List { .. } if vocab.tags.is_empty() => {
+            println!("'{}' has no tags", vocab.word)
+        }
+        TagAction::List { .. } => println!("{}", vocab.tags.join(" ")),
+    }
+}
+
+async fn run_deck(action: DeckAction) {
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+
+    match action {
+        DeckAction::Create { name, tags } => {
+            let deck = Deck { name, tags };
+            match storage.save_deck(&deck).await {
+                Ok(()) => println!("Saved deck '{}'", deck.name.trim()),
+                Err(e) => error!(error = %e, "Failed to save deck"),
+            }
+        }
+        DeckAction::Delete { name } => match storage.delete_deck(&name).await {
+            Ok(true) => println!("Deleted deck '{}'", name),
+            Ok(false) => println!("No deck '{}'", name),
+            Err(e) => error!(error = %e, "Failed to delete deck"),
+        },
+        DeckAction::List => match storage.get_decks().await {
+            Ok(decks) if decks.is_empty() => println!("No decks yet"),
+            Ok(decks) => {
+                for deck in &decks {
+                    println!("{:<24} {}", deck.name, deck.tags.join(" "));
+                }
+            }
+            Err(e) => error!(error = %e, "Failed to list decks"),
+        },
+    }
+}
+
+/// Ask a yes/no question on the terminal, defaulting to no
+fn confirm(prompt: &str) -> bool {
+    print!("{} [y/N] ", prompt);
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Parse a plain word list: one word per line, blank lines and `#` comments ignored
+fn parse_word_list(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+async fn run_quiz(count: usize, kinds: Vec<QuestionKind>, seed: Option<u64>, graded: bool) {
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s.with_algorithm(Algorithm::from_env()),
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+
+    let pool = match storage.query_vocab(&VocabQuery::new()).await {
+        Ok(page) => page.vocabulary,
+        Err(e) => {
+            error!(error = %e, "Failed to get vocabulary");
+            return;
+        }
+    };
+
+    let kinds = if kinds.is_empty() {
+        QuestionKind::ALL.to_vec()
+    } else {
+        kinds
+    };
+    let seed = seed.unwrap_or_else(QuizGenerator::random_seed);
+    let questions = QuizGenerator::with_seed(seed).quiz(&pool, count, &kinds);
+    if questions.is_empty() {
+        println!("Not enough vocabulary for a quiz. Please collect some words first.");
+        return;
+    }
+
+    if graded {
+        let mut terminal = match review::CrosstermTerminal::new() {
+            Ok(t) => t,
+            Err(e) => {
+                error!(error = %e, "Failed to set up terminal");
+                return;
+            }
+        };
+        let result =
+            review::run_graded_quiz(&storage, &mut terminal, &answer_grader(), &questions).await;
+        // Restore the terminal before printing the summary
+        drop(terminal);
+
+        match result {
+            Ok(summary) => println!("\n{}", review::format_summary(&summary, load_timezone())),
+            Err(e) => error!(error = %e, "Quiz failed"),
+        }
+        return;
+    }
+
+    for (i, question) in questions.iter().enumerate() {
+        println!("{}. [{}] {}", i + 1, question.kind, question.prompt);
+        for (j, choice) in question.choices.iter().enumerate() {
+            println!("   {}) {}", (b'A' + j as u8) as char, choice);
+        }
+    }
+
+    println!("\nAnswers (seed {}):", seed);
+    for (i, question) in questions.iter().enumerate() {
+        match question.answer_index {
+            Some(j) => println!(
+                "{}. {}) {}",
+                i + 1,
+                (b'A' + j as u8) as char,
+                question.answer
+            ),
+            None => println!("{}. {}", i + 1, question.answer),
+        }
+    }
+}
+
+async fn run_search(query: &str, limit: usize) {
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+
+    let hits = match storage.search(query, limit).await {
+        Ok(h) => h,
+        Err(e) => {
+            error!(error = %e, "Search failed");
+            return;
+        }
+    };
+    if hits.is_empty() {
+        println!("No matches for '{}'", query);
+        return;
+    }
+
+    let bold = std::io::stdout().is_terminal();
+    for hit in &hits {
+        match hit {
+            SearchHit::Word {
+                vocabulary,
+                snippet,
+                ..
+            } => {
+                println!("{} — {}", vocabulary.word, vocabulary.definition);
+                println!("    {}", highlight(snippet, bold));
+            }
+            SearchHit::Article {
+                url,
+                title,
+                snippet,
+                ..
+            } => {
+                println!("[article] {} <{}>", title, url);
+                println!("    {}", highlight(snippet, bold));
+            }
+        }
+    }
+}
+
+/// Render snippet markers as bold on a terminal, or leave them as `**`
+fn highlight(snippet: &str, bold: bool) -> String {
+    if !bold {
+        return snippet.to_string();
+    }
+    snippet
+        .split(SNIPPET_HIGHLIGHT)
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                format!("\x1b[1m{}\x1b[0m", part)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect()
+}
+
+async fn run_review(session: review::Session, typed: bool, kinds: Vec<QuestionKind>) {
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s.with_algorithm(Algorithm::from_env()),
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+
+    let mut terminal = match review::CrosstermTerminal::new() {
+        Ok(t) => t,
+        Err(e) => {
+            error!(error = %e, "Failed to set up terminal");
+            return;
+        }
+    };
+    let result = if typed {
+        let kinds = if kinds.is_empty() {
+            QuestionKind::ALL.to_vec()
+        } else {
+            kinds
+        };
+        review::run_typed_review(
+            &storage,
+            &mut terminal,
+            &answer_grader(),
+            &mut QuizGenerator::new(),
+            &kinds,
+            &session,
+        )
+        .await
+    } else {
+        review::run_review(&storage, &mut terminal, &session).await
+    };
+    // Restore the terminal before printing the summary
+    drop(terminal);
+
+    match result {
+        Ok(summary) => println!("\n{}", review::format_summary(&summary, load_timezone())),
+        Err(e) => error!(error = %e, "Review session failed"),
+    }
+}
+
+/// Definitions are graded semantically when Gemini is configured
+fn answer_grader() -> AnswerGrader {
+    match GeminiLlmEngine::new() {
+        Ok(engine) => AnswerGrader::new().with_llm(Box::new(engine)),
+        Err(_) => AnswerGrader::new(),
+    }
+}
+
+async fn run_db(action: DbAction) {
+    // Open without migrating so pending migrations can be inspected
+    let Some(url) = sqlite_url("db") else {
+        return;
+    };
+    let storage = match SqliteStorage::connect(&url).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to open database");
+            return;
+        }
+    };
+
+    match action {
+        DbAction::Version => match storage.schema_version().await {
+            Ok(version) => println!(
+                "Schema version {} (latest {})",
+                version,
+                spread_storage::latest_version()
+            ),
+            Err(e) => error!(error = %e, "Failed to read schema version"),
+        },
+        DbAction::Migrate { dry_run: true } => match storage.pending_migrations().await {
+            Ok(pending) if pending.is_empty() => println!("Database is up to date"),
+            Ok(pending) => {
+                println!("{} pending migration(s):", pending.len());
+                for migration in pending {
+                    println!("  {:>3}  {}", migration.version, migration.name);
+                }
+            }
+            Err(e) => error!(error = %e, "Failed to check migrations"),
+        },
+        DbAction::Compact { days, prune_empty } => {
+            let from_env = RetentionPolicy::from_env();
+            let Some(days) = days.or(from_env.map(|p| p.days)) else {
+                error!(
+                    "No retention period given. Use --days or set {}",
+                    ENV_RETENTION_DAYS
+                );
+                return;
+            };
+            let policy = RetentionPolicy::new(days)
+                .with_prune_empty(prune_empty || from_env.is_some_and(|p| p.prune_empty));
+            if let Err(e) = storage.migrate().await {
+                error!(error = %e, "Migration failed");
+                return;
+            }
+            match storage.compact_articles(&policy).await {
+                Ok(report) => println!(
+                    "Compacted {} article(s), pruned {} ({} of text removed). Run `spread db vacuum` to shrink the file.",
+                    report.compacted,
+                    report.pruned,
+                    format_size(report.content_bytes_removed)
+                ),
+                Err(e) => error!(error = %e, "Failed to compact articles"),
+            }
+        }
+        DbAction::Vacuum => match storage.vacuum().await {
+            Ok(report) => println!(
+                "Database size {} -> {} ({} reclaimed)",
+                format_size(report.size_before),
+                format_size(report.size_after),
+                format_size(report.reclaimed())
+            ),
+            Err(e) => error!(error = %e, "Vacuum failed"),
+        },
+        DbAction::Migrate { dry_run: false } => match storage.migrate().await {
+            Ok(report) if report.applied.is_empty() => println!("Database is up to date"),
+            Ok(report) => {
+                if let Some(backup) = &report.backup {
+                    println!("Backup written to {}", backup.display());
+                }
+                for (version, name) in &report.applied {
+                    println!("Applied {:>3}  {}", version, name);
+                }
+                println!(
+                    "Migrated schema {} -> {}",
+                    report.from_version, report.to_version
+                );
+            }
+            Err(e) => error!(error = %e, "Migration failed"),
+        },
+    }
+}
+
+async fn run_backup(to: Option<PathBuf>, keep: usize) {
+    let dir = to
+        .or_else(|| std::env::var(ENV_BACKUP_DIR).ok().map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_BACKUP_DIR));
+
+    let Some(url) = sqlite_url("backup") else {
+        return;
+    };
+    let storage = match SqliteStorage::new(&url).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+    match storage.backup(&dir, keep).await {
+        Ok(report) => {
+            println!(
+                "Backup written to {} ({})",
+                report.path.display(),
+                format_size(report.size)
+            );
+            for path in &report.removed {
+                println!("Removed old backup {}", path.display());
+            }
+        }
+        Err(e) => error!(error = %e, "Backup failed"),
+    }
+}
+
+async fn run_restore(file: &Path, yes: bool) {
+    // Check the file before asking, so a bad backup is reported straight away
+    let version = match spread_storage::backup_schema_version(file).await {
+        Ok(v) => v,
+        Err(e) => {
+            error!(error = %e, "Cannot restore");
+            return;
+        }
+    };
+    let prompt = format!(
+        "Replace the database with {} (schema version {})?",
+        file.display(),
+        version
+    );
+    if !yes && !confirm(&prompt) {
+        return;
+    }
+
+    let Some(url) = sqlite_url("restore") else {
+        return;
+    };
+    let storage = match SqliteStorage::connect(&url).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to open database");
+            return;
+        }
+    };
+    match storage.restore(file).await {
+        Ok(report) => {
+            if let Some(previous) = &report.previous {
+                println!("Previous database saved to {}", previous.display());
+            }
+            println!("Restored {}", file.display());
+            if report.schema_version < spread_storage::latest_version() {
+                println!(
+                    "Schema version {} will be migrated to {} on next use",
+                    report.schema_version,
+                    spread_storage::latest_version()
+                );
+            }
+        }
+        Err(e) => error!(error = %e, "Restore failed"),
+    }
+}
+
+async fn run_dump(output: Option<&Path>, format: Option<DumpFormat>) {
+    let format = format.unwrap_or_else(|| {
+        match output
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str())
+        {
+            Some("ndjson" | "jsonl") => DumpFormat::Ndjson,
+            _ => DumpFormat::Json,
+        }
+    });
+
+    let Some(url) = sqlite_url("dump") else {
+        return;
+    };
+    let storage = match SqliteStorage::new(&url).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+    let dump = match storage.dump().await {
+        Ok(d) => d,
+        Err(e) => {
+            error!(error = %e, "Failed to read database");
+            return;
+        }
+    };
+    let text = match dump.to_string(format) {
+        Ok(t) => t,
+        Err(e) => {
+            error!(error = %e, "Failed to serialize dump");
+            return;
+        }
+    };
+
+    match output {
+        Some(path) => match std::fs::write(path, text) {
+            Ok(()) => println!(
+                "Dumped {} word(s) and {} article(s) to {}",
+                dump.words.len(),
+                dump.articles.len(),
+                path.display()
+            ),
+            Err(e) => error!(path = %path.display(), error = %e, "Failed to write dump"),
+        },
+        None => print!("{}", text),
+    }
+}
+
+async fn run_load(file: &Path, strategy: ConflictStrategy) {
+    let text = if file == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(file)
+    };
+    let dump = match text
+        .map_err(|e| e.to_string())
+        .and_then(|text| Dump::parse(&text).map_err(|e| e.to_string()))
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!(path = %file.display(), error = %e, "Failed to read dump");
+            return;
+        }
+    };
+
+    let Some(url) = sqlite_url("load") else {
+        return;
+    };
+    let storage = match SqliteStorage::new(&url).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!(error = %e, "Failed to initialize storage");
+            return;
+        }
+    };
+    match storage.load(&dump, strategy).await {
+        Ok(report) => println!(
+            "Loaded {} new word(s), updated {}, skipped {}; {} new context(s), {} new article(s)",
+            report.words_added,
+            report.words_updated,
+            report.words_skipped,
+            report.occurrences_added,
+            report.articles_added
+        ),
+        Err(e) => error!(error = %e, "Load failed; nothing was imported"),
+    }
+}
+
+/// Human-readable byte count, e.g. `1.5 MB`
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
 async fn run_mcp_server() {
     info!("Starting MCP server");
 
-    let storage = match SqliteStorage::new(DEFAULT_DB_URL).await {
-        Ok(s) => s,
+    let storage = match AnyStorage::new(&database_url()).await {
+        Ok(s) => s.with_algorithm(Algorithm::from_env()),
         Err(e) => {
             error!(error = %e, "Failed to initialize storage");
             return;
         }
     };
 
-    let server = McpServer::new(storage);
+    let server = McpServer::new(storage)
+        .with_timezone(load_timezone())
+        .with_grader(answer_grader());
     if let Err(e) = server.run().await {
         error!(error = %e, "MCP server error");
     }
 }
 
-async fn run_export(obsidian_path: PathBuf) {
+async fn run_export(obsidian_path: PathBuf, date: Option<NaiveDate>, filter: TagFilter) {
     info!(path = %obsidian_path.display(), "Exporting vocabulary to Obsidian");
 
-    let storage = match SqliteStorage::new(DEFAULT_DB_URL).await {
+    let storage = match AnyStorage::new(&database_url()).await {
         Ok(s) => s,
         Err(e) => {
             error!(error = %e, "Failed to initialize storage");
@@ -200,12 +1702,17 @@ async fn run_export(obsidian_path: PathBuf) {
         }
     };
 
-    export_to_obsidian(&storage, &obsidian_path).await;
+    export_to_obsidian(&storage, &obsidian_path, load_timezone(), date, &filter).await;
 }
 
-async fn export_to_obsidian(storage: &SqliteStorage, path: &Path) {
-    use spread_core::port::StoragePort;
-
+/// Export every word matching `filter`, or only those collected on the local day `date`
+async fn export_to_obsidian(
+    storage: &AnyStorage,
+    path: &Path,
+    tz: Tz,
+    date: Option<NaiveDate>,
+    filter: &TagFilter,
+) {
     // Verify path exists
     if !path.exists() {
         error!(path = %path.display(), "Obsidian path does not exist");
@@ -217,8 +1724,13 @@ async fn export_to_obsidian(storage: &SqliteStorage, path: &Path) {
         return;
     }
 
-    let vocabs = match storage.get_all_vocab().await {
-        Ok(v) => v,
+    let query = VocabQuery::new().with_tag_filter(filter);
+    let query = match date {
+        Some(date) => query.with_collected_on(date, tz),
+        None => query,
+    };
+    let vocabs = match storage.query_vocab(&query).await {
+        Ok(page) => page.vocabulary,
         Err(e) => {
             error!(error = %e, "Failed to get vocabulary");
             return;
@@ -231,7 +1743,13 @@ async fn export_to_obsidian(storage: &SqliteStorage, path: &Path) {
     }
 
     let exporter = match MarkdownExporter::new(path) {
-        Ok(e) => e,
+        Ok(e) => {
+            let e = e.with_timezone(tz);
+            match date {
+                Some(date) => e.with_date(date),
+                None => e,
+            }
+        }
         Err(e) => {
             error!(error = %e, "Failed to create exporter");
             return;
@@ -248,21 +1766,28 @@ async fn export_to_obsidian(storage: &SqliteStorage, path: &Path) {
     }
 }
 
-async fn run_notify(use_all: bool, test_mode: bool) {
-    use spread_core::port::StoragePort;
-
+async fn run_notify(
+    use_all: bool,
+    test_mode: bool,
+    quiz: bool,
+    date: Option<NaiveDate>,
+    filter: TagFilter,
+) {
     info!("Starting Telegram notification");
 
+    let tz = load_timezone();
+    let day = date.unwrap_or_else(|| timezone::today(tz));
+
     // Check for Telegram configuration
     let notifier = match Notifier::from_env() {
-        Some(n) => n,
+        Some(n) => n.with_timezone(tz).with_date(day),
         None => {
             warn!("Telegram not configured. Set TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID in .env");
             return;
         }
     };
 
-    let storage = match SqliteStorage::new(DEFAULT_DB_URL).await {
+    let storage = match AnyStorage::new(&database_url()).await {
         Ok(s) => s,
         Err(e) => {
             error!(error = %e, "Failed to initialize storage");
@@ -271,37 +1796,41 @@ async fn run_notify(use_all: bool, test_mode: bool) {
     };
 
     // Get vocabulary based on mode
+    let all_words = VocabQuery::new().with_tag_filter(&filter);
     let vocabs = if use_all {
         info!("Using all vocabulary");
-        match storage.get_all_vocab().await {
-            Ok(v) => v,
+        match storage.query_vocab(&all_words).await {
+            Ok(page) => page.vocabulary,
             Err(e) => {
                 error!(error = %e, "Failed to get vocabulary");
                 return;
             }
         }
     } else {
-        info!("Using today's vocabulary");
-        let today = match storage.get_today_vocab().await {
-            Ok(v) => v,
+        info!(date = %day, timezone = %tz, "Using vocabulary collected on one day");
+        let on_day = match storage
+            .query_vocab(&all_words.clone().with_collected_on(day, tz))
+            .await
+        {
+            Ok(page) => page.vocabulary,
             Err(e) => {
-                error!(error = %e, "Failed to get today's vocabulary");
+                error!(error = %e, "Failed to get the day's vocabulary");
                 return;
             }
         };
 
-        // In test mode, fall back to all vocabulary if today's is empty
-        if today.is_empty() && test_mode {
-            info!("No vocabulary for today, using all vocabulary (test mode)");
-            match storage.get_all_vocab().await {
-                Ok(v) => v,
+        // In test mode, fall back to all vocabulary if the day's is empty
+        if on_day.is_empty() && test_mode {
+            info!("No vocabulary for the day, using all vocabulary (test mode)");
+            match storage.query_vocab(&all_words).await {
+                Ok(page) => page.vocabulary,
                 Err(e) => {
                     error!(error = %e, "Failed to get vocabulary");
                     return;
                 }
             }
         } else {
-            today
+            on_day
         }
     };
 
@@ -310,7 +1839,27 @@ async fn run_notify(use_all: bool, test_mode: bool) {
         return;
     }
 
-    match notifier.notify(&vocabs).await {
+    let result = if quiz {
+        // Distractors may come from any stored word
+        let pool = match storage.query_vocab(&VocabQuery::new()).await {
+            Ok(page) => page.vocabulary,
+            Err(e) => {
+                error!(error = %e, "Failed to get vocabulary");
+                return;
+            }
+        };
+        let questions = QuizGenerator::new().quiz_for(
+            &vocabs,
+            &pool,
+            notifier.word_count(),
+            &QuestionKind::ALL,
+        );
+        notifier.send_quiz(&questions).await
+    } else {
+        notifier.notify(&vocabs).await
+    };
+
+    match result {
         Ok(result) => {
             if result.skipped {
                 warn!("Notification skipped (no words)");
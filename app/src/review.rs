@@ -0,0 +1,561 @@
+//! Interactive flashcard review in the terminal.
+
+use std::io::{self, Write};
+
+use chrono::{DateTime, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+
+use spread_core::quiz::{Question, QuestionKind};
+use spread_core::timezone::Tz;
+use spread_core::{CoreError, Grade, ReviewCard, StoragePort, TagFilter, VocabQuery};
+use spread_integration::grading::{AnswerGrader, GradeResult, MatchKind};
+use spread_integration::quiz::mask_word;
+use spread_integration::QuizGenerator;
+
+/// Keys understood by the session: space/enter reveal, `1`-`4` grade, `q` quits.
+pub trait ReviewTerminal {
+    fn print(&mut self, text: &str) -> io::Result<()>;
+    fn read_key(&mut self) -> io::Result<char>;
+    /// Read a typed answer; `None` means the learner quit.
+    fn read_line(&mut self) -> io::Result<Option<String>>;
+}
+
+/// Terminal backed by crossterm raw mode, reading single keypresses.
+pub struct CrosstermTerminal;
+
+impl CrosstermTerminal {
+    pub fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for CrosstermTerminal {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl ReviewTerminal for CrosstermTerminal {
+    fn print(&mut self, text: &str) -> io::Result<()> {
+        // Raw mode does not translate newlines
+        let mut stdout = io::stdout();
+        write!(stdout, "{}", text.replace('\n', "\r\n"))?;
+        stdout.flush()
+    }
+
+    fn read_key(&mut self) -> io::Result<char> {
+        loop {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok('q')
+                    }
+                    KeyCode::Char(c) => return Ok(c),
+                    KeyCode::Enter => return Ok('\n'),
+                    KeyCode::Esc => return Ok('q'),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        loop {
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(None)
+                }
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter => {
+                    self.print("\n")?;
+                    return Ok(Some(line));
+                }
+                KeyCode::Backspace if line.pop().is_some() => {
+                    self.print("\u{8} \u{8}")?;
+                }
+                KeyCode::Char(c) => {
+                    line.push(c);
+                    self.print(&c.to_string())?;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Result of a review session.
+#[derive(Debug, Default)]
+pub struct ReviewSummary {
+    pub reviewed: usize,
+    /// Reviews graded Hard or better
+    pub passed: usize,
+    /// Earliest next due date among the reviewed words
+    pub next_due: Option<DateTime<Utc>>,
+}
+
+impl ReviewSummary {
+    pub fn accuracy(&self) -> f64 {
+        if self.reviewed == 0 {
+            0.0
+        } else {
+            self.passed as f64 / self.reviewed as f64 * 100.0
+        }
+    }
+}
+
+/// Which words a review session covers.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    /// Maximum number of words
+    pub limit: usize,
+    /// Maximum number of never-reviewed words, after the due ones
+    pub new_words: usize,
+    pub filter: TagFilter,
+}
+
+impl Session {
+    pub fn new(limit: usize, new_words: usize) -> Self {
+        Self {
+            limit,
+            new_words,
+            filter: TagFilter::new(),
+        }
+    }
+
+    /// Only review words matching `filter`
+    pub fn with_filter(mut self, filter: TagFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// Review the due words of `session` followed by unseen ones.
+pub async fn run_review<S, T>(
+    storage: &S,
+    terminal: &mut T,
+    session: &Session,
+) -> Result<ReviewSummary, CoreError>
+where
+    S: StoragePort + ?Sized,
+    T: ReviewTerminal,
+{
+    let cards = session_cards(storage, session).await?;
+
+    let mut summary = ReviewSummary::default();
+    let total = cards.len();
+    for (i, card) in cards.iter().enumerate() {
+        let Some(grade) = review_card(terminal, card, i + 1, total).map_err(terminal_error)? else {
+            break;
+        };
+        record(storage, &mut summary, card.word_id, grade).await?;
+    }
+
+    Ok(summary)
+}
+
+/// Like [`run_review`], but the learner types answers to quiz questions and
+/// the grader decides the grade.
+pub async fn run_typed_review<S, T>(
+    storage: &S,
+    terminal: &mut T,
+    grader: &AnswerGrader,
+    generator: &mut QuizGenerator,
+    kinds: &[QuestionKind],
+    session: &Session,
+) -> Result<ReviewSummary, CoreError>
+where
+    S: StoragePort + ?Sized,
+    T: ReviewTerminal,
+{
+    let cards = session_cards(storage, session).await?;
+    // Distractors for multiple-choice questions
+    let pool = if kinds.contains(&QuestionKind::MultipleChoice) {
+        storage.query_vocab(&VocabQuery::new()).await?.vocabulary
+    } else {
+        Vec::new()
+    };
+
+    let mut summary = ReviewSummary::default();
+    let total = cards.len();
+    for (i, card) in cards.iter().enumerate() {
+        // Every word can be asked in reverse
+        let Some(question) = generator
+            .random_question(&card.vocabulary, &pool, kinds)
+            .or_else(|| generator.question(&card.vocabulary, &pool, QuestionKind::Reverse))
+        else {
+            continue;
+        };
+
+        print_question(terminal, &question, i + 1, total).map_err(terminal_error)?;
+        let Some(answer) = terminal.read_line().map_err(terminal_error)? else {
+            break;
+        };
+
+        let result = grader.grade(&question, &answer).await;
+        terminal
+            .print(&format_feedback(
+                &question,
+                &result,
+                &card.vocabulary.definition,
+            ))
+            .map_err(terminal_error)?;
+        record(storage, &mut summary, card.word_id, result.grade).await?;
+    }
+
+    Ok(summary)
+}
+
+/// Ask prepared quiz questions, grading each typed answer and recording it as
+/// a review of the question's word.
+pub async fn run_graded_quiz<S, T>(
+    storage: &S,
+    terminal: &mut T,
+    grader: &AnswerGrader,
+    questions: &[Question],
+) -> Result<ReviewSummary, CoreError>
+where
+    S: StoragePort + ?Sized,
+    T: ReviewTerminal,
+{
+    let mut summary = ReviewSummary::default();
+    for (i, question) in questions.iter().enumerate() {
+        print_question(terminal, question, i + 1, questions.len()).map_err(terminal_error)?;
+        let Some(answer) = terminal.read_line().map_err(terminal_error)? else {
+            break;
+        };
+
+        let result = grader.grade(question, &answer).await;
+        let vocab = storage
+            .get_vocab_by_word(&question.word)
+            .await?
+            .ok_or_else(|| CoreError::NotFound(format!("Word '{}'", question.word)))?;
+        terminal
+            .print(&format_feedback(question, &result, &vocab.definition))
+            .map_err(terminal_error)?;
+        if let Some(word_id) = vocab.id {
+            record(storage, &mut summary, word_id, result.grade).await?;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Due cards first, then up to `new_words` unseen ones, `limit` in total.
+async fn session_cards<S: StoragePort + ?Sized>(
+    storage: &S,
+    session: &Session,
+) -> Result<Vec<ReviewCard>, CoreError> {
+    let mut cards = storage
+        .get_due_vocab(session.limit, &session.filter)
+        .await?;
+    let new_limit = session
+        .new_words
+        .min(session.limit.saturating_sub(cards.len()));
+    if new_limit > 0 {
+        cards.extend(storage.get_new_vocab(new_limit, &session.filter).await?);
+    }
+    Ok(cards)
+}
+
+async fn record<S: StoragePort + ?Sized>(
+    storage: &S,
+    summary: &mut ReviewSummary,
+    word_id: i64,
+    grade: Grade,
+) -> Result<(), CoreError> {
+    let state = storage.record_review(word_id, grade).await?;
+    summary.reviewed += 1;
+    if grade.is_pass() {
+        summary.passed += 1;
+    }
+    summary.next_due = Some(match summary.next_due {
+        Some(due) => due.min(state.due_at),
+        None => state.due_at,
+    });
+    Ok(())
+}
+
+fn print_question<T: ReviewTerminal>(
+    terminal: &mut T,
+    question: &Question,
+    position: usize,
+    total: usize,
+) -> io::Result<()> {
+    let mut text = format!(
+        "\n[{}/{}] ({}) {}\n",
+        position, total, question.kind, question.prompt
+    );
+    for (i, choice) in question.choices.iter().enumerate() {
+        text.push_str(&format!("  {}) {}\n", (b'A' + i as u8) as char, choice));
+    }
+    text.push_str("> ");
+    terminal.print(&text)
+}
+
+fn format_feedback(question: &Question, result: &GradeResult, definition: &str) -> String {
+    let verdict = match result.kind {
+        MatchKind::Typo { .. } => format!("Almost: {}", question.answer),
+        _ if result.correct => "Correct".to_string(),
+        _ if result.grade.is_pass() => format!("Partly right: {}", question.answer),
+        _ => format!("Answer: {}", question.answer),
+    };
+    let mut text = format!("{} [{:?}]\n", verdict, result.grade);
+    if question.kind.expects_word() {
+        text.push_str(&format!("Definition: {}\n", definition));
+    }
+    text
+}
+
+/// Show one card; `None` means the learner quit.
+fn review_card<T: ReviewTerminal>(
+    terminal: &mut T,
+    card: &ReviewCard,
+    position: usize,
+    total: usize,
+) -> io::Result<Option<Grade>> {
+    let vocab = &card.vocabulary;
+    let mut header = format!("\n[{}/{}] {}", position, total, vocab.word);
+    if let Some(pos) = &vocab.part_of_speech {
+        header.push_str(&format!(" ({})", pos));
+    }
+    if card.state.last_reviewed_at.is_none() {
+        header.push_str("  · new");
+    }
+    terminal.print(&format!(
+        "{}\n> {}\n[space] reveal  [q] quit\n",
+        header,
+        mask_word(&vocab.context_sentence, &vocab.word)
+    ))?;
+
+    loop {
+        match terminal.read_key()? {
+            ' ' | '\n' => break,
+            'q' => return Ok(None),
+            _ => {}
+        }
+    }
+
+    terminal.print(&format!(
+        "Definition: {}\n> {}\n[1] Again  [2] Hard  [3] Good  [4] Easy\n",
+        vocab.definition, vocab.context_sentence
+    ))?;
+
+    loop {
+        let key = terminal.read_key()?;
+        if key == 'q' {
+            return Ok(None);
+        }
+        if let Some(grade) = key.to_digit(10).and_then(|d| Grade::from_u8(d as u8)) {
+            return Ok(Some(grade));
+        }
+    }
+}
+
+fn terminal_error(e: io::Error) -> CoreError {
+    CoreError::Unknown(format!("Terminal error: {}", e))
+}
+
+/// Human-readable session summary
+pub fn format_summary(summary: &ReviewSummary, tz: Tz) -> String {
+    if summary.reviewed == 0 {
+        return "Nothing reviewed".to_string();
+    }
+
+    let mut text = format!(
+        "Reviewed {} word(s), accuracy {:.0}%",
+        summary.reviewed,
+        summary.accuracy()
+    );
+    if let Some(due) = summary.next_due {
+        text.push_str(&format!(
+            ", next due {}",
+            due.with_timezone(&tz).format("%Y-%m-%d %H:%M")
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spread_storage::testing::save_word;
+    use spread_storage::SqliteStorage;
+    use std::collections::VecDeque;
+
+    struct ScriptedTerminal {
+        keys: VecDeque<char>,
+        lines: VecDeque<String>,
+        output: String,
+    }
+
+    impl ScriptedTerminal {
+        fn new(keys: &str) -> Self {
+            Self {
+                keys: keys.chars().collect(),
+                lines: VecDeque::new(),
+                output: String::new(),
+            }
+        }
+
+        fn with_lines(lines: &[&str]) -> Self {
+            Self {
+                keys: VecDeque::new(),
+                lines: lines.iter().map(|l| l.to_string()).collect(),
+                output: String::new(),
+            }
+        }
+    }
+
+    impl ReviewTerminal for ScriptedTerminal {
+        fn print(&mut self, text: &str) -> io::Result<()> {
+            self.output.push_str(text);
+            Ok(())
+        }
+
+        fn read_key(&mut self) -> io::Result<char> {
+            self.keys
+                .pop_front()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "no more keys"))
+        }
+
+        fn read_line(&mut self) -> io::Result<Option<String>> {
+            Ok(self.lines.pop_front())
+        }
+    }
+
+    async fn storage_with_words(words: &[&str]) -> SqliteStorage {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        for word in words {
+            save_word(&storage, word).await;
+        }
+        storage
+    }
+
+    #[tokio::test]
+    async fn test_review_session_records_grades() {
+        let storage = storage_with_words(&["ephemeral", "tenuous"]).await;
+        // Reveal and grade Good, then reveal with enter and grade Again
+        let mut terminal = ScriptedTerminal::new(" x3\n1");
+
+        let summary = run_review(&storage, &mut terminal, &Session::new(10, 5))
+            .await
+            .unwrap();
+        assert_eq!(summary.reviewed, 2);
+        assert_eq!(summary.passed, 1);
+        assert_eq!(summary.accuracy(), 50.0);
+        assert!(summary.next_due.is_some());
+
+        assert!(terminal.output.contains("[1/2] ephemeral"));
+        assert!(terminal.output.contains("> The _____ essay."));
+        assert!(terminal
+            .output
+            .contains("Definition: Definition of ephemeral"));
+        assert!(storage
+            .get_new_vocab(10, &TagFilter::new())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_review_session_limits_and_quit() {
+        let storage = storage_with_words(&["ephemeral", "tenuous", "candid"]).await;
+
+        // Only one new word per session
+        let mut terminal = ScriptedTerminal::new(" 3");
+        let summary = run_review(&storage, &mut terminal, &Session::new(10, 1))
+            .await
+            .unwrap();
+        assert_eq!(summary.reviewed, 1);
+
+        // Quitting before grading records nothing
+        let mut terminal = ScriptedTerminal::new(" q");
+        let summary = run_review(&storage, &mut terminal, &Session::new(10, 5))
+            .await
+            .unwrap();
+        assert_eq!(summary.reviewed, 0);
+        assert_eq!(format_summary(&summary, Tz::UTC), "Nothing reviewed");
+        assert_eq!(
+            storage
+                .get_new_vocab(10, &TagFilter::new())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_typed_review_grades_answers() {
+        let storage = storage_with_words(&["ephemeral", "tenuous", "candid"]).await;
+        // Words come back in id order; a typo still passes, a wrong word fails,
+        // and running out of input ends the session
+        let mut terminal = ScriptedTerminal::with_lines(&["Ephemerel", "candid"]);
+        let mut generator = QuizGenerator::with_seed(1);
+
+        let summary = run_typed_review(
+            &storage,
+            &mut terminal,
+            &AnswerGrader::new(),
+            &mut generator,
+            &[QuestionKind::Reverse],
+            &Session::new(10, 5),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.reviewed, 2);
+        assert_eq!(summary.passed, 1);
+        assert!(terminal
+            .output
+            .contains("[1/3] (reverse) Which word means: Definition of ephemeral"));
+        assert!(terminal.output.contains("Almost: ephemeral [Hard]"));
+        assert!(terminal.output.contains("Answer: tenuous [Again]"));
+        assert_eq!(
+            storage
+                .get_new_vocab(10, &TagFilter::new())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_graded_quiz_records_reviews() {
+        let storage = storage_with_words(&["ephemeral", "tenuous"]).await;
+        let pool = storage
+            .query_vocab(&VocabQuery::new())
+            .await
+            .unwrap()
+            .vocabulary;
+        let questions = QuizGenerator::with_seed(1).quiz(&pool, 2, &[QuestionKind::Reverse]);
+        let mut terminal = ScriptedTerminal::with_lines(&[&questions[0].answer, "candid"]);
+
+        let summary = run_graded_quiz(&storage, &mut terminal, &AnswerGrader::new(), &questions)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reviewed, 2);
+        assert_eq!(summary.passed, 1);
+        assert!(terminal.output.contains("[1/2] (reverse)"));
+        assert!(terminal.output.contains("Correct [Good]"));
+        assert!(storage
+            .get_new_vocab(10, &TagFilter::new())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! Listing recorded pipeline runs and the health of their feeds.
+
+use chrono::{DateTime, Utc};
+use spread_core::timezone::Tz;
+use spread_core::{FeedHealth, PipelineRun};
+
+/// One line per run: id, start, trigger, duration and counts
+pub fn format_run_line(run: &PipelineRun, tz: Tz) -> String {
+    let stats = &run.stats;
+    format!(
+        "#{:<5} {}  {:<9}  {:>8}  {} article(s), {} word(s), {} error(s){}",
+        run.id.unwrap_or_default(),
+        format_time(run.started_at, tz),
+        run.trigger.to_string(),
+        format_duration(run),
+        stats.articles_saved,
+        stats.vocabularies_saved,
+        stats.errors(),
+        if run.cancelled { ", cancelled" } else { "" }
+    )
+}
+
+/// A run with its counts, feed results and errors
+pub fn format_run(run: &PipelineRun, tz: Tz) -> String {
+    let stats = &run.stats;
+    let mut text = format!(
+        "Run #{} ({})\nStarted   {}\n",
+        run.id.unwrap_or_default(),
+        run.trigger,
+        format_time(run.started_at, tz)
+    );
+    match run.finished_at {
+        Some(finished_at) => text.push_str(&format!(
+            "Finished  {} ({}){}\n",
+            format_time(finished_at, tz),
+            format_duration(run),
+            if run.cancelled {
+                ", cancelled with the remaining items queued"
+            } else {
+                ""
+            }
+        )),
+        None => text.push_str("Finished  never (still running or interrupted)\n"),
+    }
+    text.push_str(&format!(
+        "Items     {}, {} already saved, {} resumed from the queue\nSaved     {} article(s), {} word(s); {} rejected, {} context(s) snapped\n",
+        stats.total_items,
+        stats.skipped_duplicates,
+        stats.resumed_items,
+        stats.articles_saved,
+        stats.vocabularies_saved,
+        stats.vocabularies_rejected,
+        stats.contexts_snapped
+    ));
+    text.push_str(&format!(
+        "Errors    {} feed, {} fetch, {} extract, {} storage\n",
+        stats.feed_errors, stats.fetch_errors, stats.llm_errors, stats.storage_errors
+    ));
+
+    if !run.feeds.is_empty() {
+        text.push_str("\nFeeds:\n");
+        for feed in &run.feeds {
+            match &feed.error {
+                Some(error) => text.push_str(&format!("  FAILED {} — {}\n", feed.feed_url, error)),
+                None => text.push_str(&format!(
+                    "  ok     {} — {} item(s), {} article(s), {} word(s)\n",
+                    feed.feed_url, feed.items, feed.articles_saved, feed.vocabularies_saved
+                )),
+            }
+        }
+    }
+
+    if !run.errors.is_empty() {
+        text.push_str("\nErrors:\n");
+        for error in &run.errors {
+            text.push_str(&format!(
+                "  [{}] {} — {}\n",
+                error.stage, error.url, error.message
+            ));
+        }
+    }
+
+    text.trim_end().to_string()
+}
+
+/// A feed that failed its latest run, flagged `BROKEN` once it keeps failing
+pub fn format_feed_health(health: &FeedHealth, tz: Tz) -> String {
+    let last_success = health
+        .last_success
+        .map_or("none recent".to_string(), |at| format_time(at, tz));
+    format!(
+        "{:<7} {} — failed {} run(s) in a row, last success {}: {}",
+        if health.is_broken() {
+            "BROKEN"
+        } else {
+            "failing"
+        },
+        health.feed_url,
+        health.consecutive_failures,
+        last_success,
+        health.last_error.as_deref().unwrap_or_default()
+    )
+}
+
+fn format_time(at: DateTime<Utc>, tz: Tz) -> String {
+    at.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// How long the run took, `-` while unfinished
+fn format_duration(run: &PipelineRun) -> String {
+    let Some(finished_at) = run.finished_at else {
+        return "-".to_string();
+    };
+    let seconds = (finished_at - run.started_at).num_seconds().max(0);
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use spread_core::run::{FeedRun, RunStage};
+    use spread_core::RunTrigger;
+
+    fn sample() -> PipelineRun {
+        let started_at = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+        let mut run = PipelineRun::new(RunTrigger::Scheduled, started_at);
+        run.id = Some(12);
+        run.finished_at = Some(started_at + Duration::seconds(185));
+        run.stats.articles_saved = 2;
+        run.stats.vocabularies_saved = 7;
+        run.stats.feed_errors = 1;
+        run.feeds = vec![
+            FeedRun {
+                feed_url: "https://b.example/rss".to_string(),
+                items: 2,
+                articles_saved: 2,
+                vocabularies_saved: 7,
+                error: None,
+            },
+            FeedRun {
+                feed_url: "https://a.example/rss".to_string(),
+                error: Some("HTTP 404".to_string()),
+                ..Default::default()
+            },
+        ];
+        run.record_error(RunStage::Feed, "https://a.example/rss", "HTTP 404");
+        run
+    }
+
+    #[test]
+    fn test_format_run_line() {
+        let tz: Tz = "Asia/Seoul".parse().unwrap();
+        assert_eq!(
+            format_run_line(&sample(), tz),
+            "#12    2026-03-01 17:00  scheduled    3m 05s  2 article(s), 7 word(s), 1 error(s)"
+        );
+
+        let running = PipelineRun {
+            finished_at: None,
+            ..sample()
+        };
+        assert!(format_run_line(&running, Tz::UTC).contains("08:00  scheduled         -  "));
+
+        let cancelled = PipelineRun {
+            cancelled: true,
+            ..sample()
+        };
+        assert!(format_run_line(&cancelled, Tz::UTC).ends_with("1 error(s), cancelled"));
+    }
+
+    #[test]
+    fn test_format_run() {
+        let text = format_run(&sample(), Tz::UTC);
+        assert!(text.starts_with("Run #12 (scheduled)\nStarted   2026-03-01 08:00\n"));
+        assert!(text.contains("Finished  2026-03-01 08:03 (3m 05s)"));
+        assert!(text.contains(
+            "  ok     https://b.example/rss — 2 item(s), 2 article(s), 7 word(s)\n  FAILED https://a.example/rss — HTTP 404"
+        ));
+        assert!(text.ends_with("Errors:\n  [feed] https://a.example/rss — HTTP 404"));
+
+        let cancelled = PipelineRun {
+            cancelled: true,
+            ..sample()
+        };
+        assert!(format_run(&cancelled, Tz::UTC)
+            .contains("(3m 05s), cancelled with the remaining items queued\n"));
+    }
+
+    #[test]
+    fn test_format_feed_health() {
+        let mut health = FeedHealth {
+            feed_url: "https://a.example/rss".to_string(),
+            consecutive_failures: 1,
+            last_success: None,
+            last_error: Some("HTTP 404".to_string()),
+        };
+        assert_eq!(
+            format_feed_health(&health, Tz::UTC),
+            "failing https://a.example/rss — failed 1 run(s) in a row, last success none recent: HTTP 404"
+        );
+
+        health.consecutive_failures = 3;
+        health.last_success = Some(Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap());
+        assert!(format_feed_health(&health, Tz::UTC)
+            .starts_with("BROKEN  https://a.example/rss — failed 3 run(s) in a row, last success 2026-03-01 08:00"));
+    }
+}
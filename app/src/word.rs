@@ -0,0 +1,298 @@
+//! Viewing and hand-editing stored words.
+
+use std::io::{self, Write};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use spread_core::{CoreError, StoragePort, Vocabulary};
+
+/// Editor used when neither `VISUAL` nor `EDITOR` is set
+const DEFAULT_EDITOR: &str = "vi";
+
+/// The editable fields of a word, as written to the editor. Blank strings and
+/// empty lists clear a field.
+#[derive(Debug, Serialize, Deserialize)]
+struct WordDocument {
+    word: String,
+    definition: String,
+    #[serde(default)]
+    part_of_speech: String,
+    #[serde(default)]
+    ipa: String,
+    #[serde(default)]
+    cefr_level: String,
+    #[serde(default)]
+    synonyms: Vec<String>,
+    #[serde(default)]
+    antonyms: Vec<String>,
+    #[serde(default)]
+    collocations: Vec<String>,
+    #[serde(default)]
+    example_sentence: String,
+}
+
+impl From<&Vocabulary> for WordDocument {
+    fn from(vocab: &Vocabulary) -> Self {
+        Self {
+            word: vocab.word.clone(),
+            definition: vocab.definition.clone(),
+            part_of_speech: vocab.part_of_speech.clone().unwrap_or_default(),
+            ipa: vocab.ipa.clone().unwrap_or_default(),
+            cefr_level: vocab.cefr_level.clone().unwrap_or_default(),
+            synonyms: vocab.synonyms.clone(),
+            antonyms: vocab.antonyms.clone(),
+            collocations: vocab.collocations.clone(),
+            example_sentence: vocab.example_sentence.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Look a word up by id, or by spelling when `key` is not a number.
+pub async fn find_word<S: StoragePort>(
+    storage: &S,
+    key: &str,
+) -> Result<Option<Vocabulary>, CoreError> {
+    match key.trim().parse::<i64>() {
+        Ok(id) => storage.get_vocab_by_id(id).await,
+        Err(_) => storage.get_vocab_by_word(key).await,
+    }
+}
+
+/// Everything stored about a word, for `spread word show`
+pub fn format_word(vocab: &Vocabulary) -> String {
+    let mut text = format!("#{} {}", vocab.id.unwrap_or_default(), vocab.word);
+    let details: Vec<&str> = [&vocab.part_of_speech, &vocab.ipa, &vocab.cefr_level]
+        .into_iter()
+        .filter_map(|d| d.as_deref())
+        .collect();
+    if !details.is_empty() {
+        text.push_str(&format!(" ({})", details.join(", ")));
+    }
+    if vocab.suspended {
+        text.push_str(" [suspended]");
+    }
+    text.push_str(&format!("\n  {}", vocab.definition));
+
+    let lists = [
+        ("Synonyms", &vocab.synonyms),
+        ("Antonyms", &vocab.antonyms),
+        ("Collocations", &vocab.collocations),
+    ];
+    for (label, items) in lists {
+        if !items.is_empty() {
+            text.push_str(&format!("\n  {}: {}", label, items.join(", ")));
+        }
+    }
+    if let Some(example) = &vocab.example_sentence {
+        text.push_str(&format!("\n  Example: {}", example));
+    }
+    if !vocab.edited_fields.is_empty() {
+        text.push_str(&format!(
+            "\n  Edited by hand: {}",
+            vocab.edited_fields.join(", ")
+        ));
+    }
+
+    text.push_str(&format!("\n\nContexts ({}):", vocab.occurrences.len()));
+    for occurrence in &vocab.occurrences {
+        text.push_str(&format!(
+            "\n  > {}\n    {} ({})",
+            occurrence.context_sentence,
+            occurrence.source_url,
+            occurrence.extracted_at.format("%Y-%m-%d")
+        ));
+    }
+    text
+}
+
+/// TOML rendering of a word for editing, with its contexts as comments.
+pub fn render_document(vocab: &Vocabulary) -> Result<String, String> {
+    let body = toml::to_string(&WordDocument::from(vocab)).map_err(|e| e.to_string())?;
+
+    let mut header = format!(
+        "# Editing '{}'. Save and quit to apply; blank fields are cleared.\n\
+         # Fields you change are kept when the word is extracted again.\n#\n# Contexts:\n",
+        vocab.word
+    );
+    for occurrence in &vocab.occurrences {
+        header.push_str(&format!(
+            "#   {} ({})\n",
+            occurrence.context_sentence, occurrence.source_url
+        ));
+    }
+    Ok(format!("{}\n{}", header, body))
+}
+
+/// Apply an edited document to `vocab`, adding changed fields to
+/// `edited_fields`. Returns `None` when nothing changed.
+pub fn apply_document(vocab: &Vocabulary, text: &str) -> Result<Option<Vocabulary>, String> {
+    let doc: WordDocument = toml::from_str(text).map_err(|e| e.to_string())?;
+    if doc.word.trim().is_empty() {
+        return Err("word must not be empty".to_string());
+    }
+    if doc.definition.trim().is_empty() {
+        return Err("definition must not be empty".to_string());
+    }
+
+    let optional = |s: String| {
+        let s = s.trim().to_string();
+        (!s.is_empty()).then_some(s)
+    };
+    let list = |items: Vec<String>| -> Vec<String> {
+        items
+            .into_iter()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    };
+    let edited = Vocabulary {
+        word: doc.word.trim().to_string(),
+        definition: doc.definition.trim().to_string(),
+        part_of_speech: optional(doc.part_of_speech),
+        ipa: optional(doc.ipa),
+        cefr_level: optional(doc.cefr_level),
+        synonyms: list(doc.synonyms),
+        antonyms: list(doc.antonyms),
+        collocations: list(doc.collocations),
+        example_sentence: optional(doc.example_sentence),
+        ..vocab.clone()
+    };
+
+    let changes = [
+        ("word", edited.word != vocab.word),
+        ("definition", edited.definition != vocab.definition),
+        (
+            "part_of_speech",
+            edited.part_of_speech != vocab.part_of_speech,
+        ),
+        ("ipa", edited.ipa != vocab.ipa),
+        ("cefr_level", edited.cefr_level != vocab.cefr_level),
+        ("synonyms", edited.synonyms != vocab.synonyms),
+        ("antonyms", edited.antonyms != vocab.antonyms),
+        ("collocations", edited.collocations != vocab.collocations),
+        (
+            "example_sentence",
+            edited.example_sentence != vocab.example_sentence,
+        ),
+    ];
+    let changed: Vec<&str> = changes
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(field, _)| field)
+        .collect();
+    if changed.is_empty() {
+        return Ok(None);
+    }
+
+    let mut edited = edited;
+    for field in changed {
+        if !edited.edited_fields.iter().any(|f| f == field) {
+            edited.edited_fields.push(field.to_string());
+        }
+    }
+    Ok(Some(edited))
+}
+
+/// Open `text` in `$VISUAL` or `$EDITOR` and return what was saved.
+pub fn edit_text(text: &str) -> io::Result<String> {
+    let mut file = tempfile::Builder::new()
+        .prefix("spread-word-")
+        .suffix(".toml")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    // Through the shell so editors with arguments such as "code --wait" work
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(file.path())
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "{} exited with {}",
+            editor, status
+        )));
+    }
+
+    std::fs::read_to_string(file.path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use spread_core::Occurrence;
+
+    fn sample() -> Vocabulary {
+        Vocabulary {
+            id: Some(3),
+            word: "analyse".to_string(),
+            definition: "Examine in detail".to_string(),
+            context_sentence: "We analyse data.".to_string(),
+            source_url: "https://example.com".to_string(),
+            ipa: Some("/ˈæn.əl.aɪz/".to_string()),
+            synonyms: vec!["examine".to_string()],
+            occurrences: vec![Occurrence {
+                source_url: "https://example.com".to_string(),
+                context_sentence: "We analyse data.".to_string(),
+                definition: "Examine in detail".to_string(),
+                extracted_at: Utc::now(),
+            }],
+            edited_fields: vec!["ipa".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unchanged_document_is_a_no_op() {
+        let vocab = sample();
+        let text = render_document(&vocab).unwrap();
+        assert!(text.contains("#   We analyse data. (https://example.com)"));
+        assert!(apply_document(&vocab, &text).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_apply_document_marks_changed_fields() {
+        let vocab = sample();
+        let text = render_document(&vocab)
+            .unwrap()
+            .replace("Examine in detail", "Study closely")
+            .replace("cefr_level = \"\"", "cefr_level = \"B2\"")
+            .replace("ipa = \"/ˈæn.əl.aɪz/\"", "ipa = \"\"");
+
+        let edited = apply_document(&vocab, &text).unwrap().unwrap();
+        assert_eq!(edited.id, Some(3));
+        assert_eq!(edited.definition, "Study closely");
+        assert_eq!(edited.cefr_level.as_deref(), Some("B2"));
+        assert!(edited.ipa.is_none());
+        assert_eq!(edited.synonyms, ["examine"]);
+        assert_eq!(edited.occurrences.len(), 1);
+        assert_eq!(edited.edited_fields, ["ipa", "definition", "cefr_level"]);
+    }
+
+    #[test]
+    fn test_apply_document_rejects_invalid_input() {
+        let vocab = sample();
+        assert!(apply_document(&vocab, "word = ").is_err());
+        assert!(apply_document(&vocab, "word = \"\"\ndefinition = \"x\"").is_err());
+    }
+
+    #[test]
+    fn test_format_word() {
+        let text = format_word(&Vocabulary {
+            suspended: true,
+            ..sample()
+        });
+        assert!(text.starts_with("#3 analyse (/ˈæn.əl.aɪz/) [suspended]"));
+        assert!(text.contains("Synonyms: examine"));
+        assert!(text.contains("Edited by hand: ipa"));
+        assert!(text.contains("Contexts (1):\n  > We analyse data."));
+    }
+}
@@ -1,43 +1,241 @@
 use chrono::Utc;
 use std::time::Duration;
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
-use spread_core::{Article, LlmPort, SourceType, StoragePort, Vocabulary};
+use spread_core::run::{FeedRun, RunStage};
+use spread_core::{
+    Article, LlmPort, PipelineRun, QueuedItem, RunStats, RunTrigger, SourceType, StoragePort,
+    Vocabulary,
+};
 use spread_fetcher::RssFetcher;
+use spread_llm::VocabularyValidator;
+use spread_storage::InMemoryStorage;
+
+use crate::feeds::FeedConfig;
 
 /// Rate limiting delay between LLM API calls (in seconds)
 const LLM_RATE_LIMIT_DELAY_SECS: u64 = 2;
 
-/// Execute the vocabulary extraction pipeline for a list of RSS feed URLs.
+/// Execute the vocabulary extraction pipeline for a list of RSS feeds.
 ///
 /// Pipeline flow:
-/// 1. Fetch Feed: Get all items from RSS feed
+/// 1. Fetch Feed: Get all items from RSS feed and queue them in storage; feeds
+///    with items left queued by an interrupted run are resumed first instead
 /// 2. Deduplication: Check if URL already exists in storage
 /// 3. Fetch Body: Get article body content
-/// 4. AI Extract: Extract vocabularies using LLM
-/// 5. Persist: Save article and vocabularies to storage
+/// 4. AI Extract: Extract vocabularies using LLM, then validate them against the body
+/// 5. Persist: Save article and vocabularies to storage, tagged with the feed's tags,
+///    and take the item off the queue
+///
+/// Words in `known_words` are dropped from the extracted ones. Cancelling
+/// `cancel` stops the run after the current item; the rest stay queued for the
+/// next run. The run, with per-feed results and errors, is recorded in storage
+/// as well.
 pub async fn run_pipeline<S, L>(
-    feed_urls: &[&str],
+    feeds: &[FeedConfig],
+    fetcher: &RssFetcher,
+    storage: &S,
+    llm: &L,
+    known_words: &[String],
+    trigger: RunTrigger,
+    cancel: &CancellationToken,
+) -> Result<PipelineRun, PipelineError>
+where
+    S: StoragePort,
+    L: LlmPort + ?Sized,
+{
+    let mut run = PipelineRun::new(trigger, Utc::now());
+    match storage.start_run(&run).await {
+        Ok(id) => run.id = Some(id),
+        Err(e) => warn!(error = %e, "Failed to record run start, the run will not be recorded"),
+    }
+
+    let collector = Collector::new(fetcher, storage, None, llm, known_words, cancel);
+    collect(feeds, &collector, &mut run).await?;
+
+    run.finished_at = Some(Utc::now());
+    if run.id.is_some() {
+        if let Err(e) = storage.update_run(&run).await {
+            warn!(error = %e, "Failed to record run results");
+        }
+    }
+
+    Ok(run)
+}
+
+/// Run the pipeline without writing to `storage`: articles and words that
+/// would be saved go into the returned in-memory storage instead. The run is
+/// not recorded and the work queue is read but not changed.
+pub async fn dry_run_pipeline<S, L>(
+    feeds: &[FeedConfig],
     fetcher: &RssFetcher,
     storage: &S,
     llm: &L,
-) -> Result<PipelineStats, PipelineError>
+    known_words: &[String],
+    cancel: &CancellationToken,
+) -> Result<(RunStats, InMemoryStorage), PipelineError>
+where
+    S: StoragePort,
+    L: LlmPort + ?Sized,
+{
+    let preview = InMemoryStorage::new();
+    let mut run = PipelineRun::new(RunTrigger::Manual, Utc::now());
+    let collector = Collector::new(fetcher, storage, Some(&preview), llm, known_words, cancel);
+    collect(feeds, &collector, &mut run).await?;
+    Ok((run.stats, preview))
+}
+
+/// The pipeline over `feeds`. Counts, feed results and errors are added to `run`.
+async fn collect<S, L>(
+    feeds: &[FeedConfig],
+    collector: &Collector<'_, S, L>,
+    run: &mut PipelineRun,
+) -> Result<(), PipelineError>
+where
+    S: StoragePort,
+    L: LlmPort + ?Sized,
+{
+    let cancel = collector.cancel;
+    let queued = collector.queued_items(feeds, run).await;
+    // Feeds with queued items go first, and are not fetched again until their queue is empty
+    let (resumed, fresh): (Vec<&FeedConfig>, Vec<&FeedConfig>) = feeds
+        .iter()
+        .partition(|feed| queued.iter().any(|item| item.feed_url == feed.url));
+
+    for feed in resumed.into_iter().chain(fresh) {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let feed_url = feed.url.as_str();
+
+        let items: Vec<QueuedItem> = queued
+            .iter()
+            .filter(|item| item.feed_url == feed_url)
+            .cloned()
+            .collect();
+        let items = if items.is_empty() {
+            match collector.discover(feed_url, run).await {
+                Some(items) => items,
+                None => continue,
+            }
+        } else {
+            info!(
+                feed_url,
+                item_count = items.len(),
+                "Resuming feed items queued by an earlier run"
+            );
+            run.stats.resumed_items += items.len();
+            items
+        };
+
+        let before = run.stats.clone();
+        for item in &items {
+            if cancel.is_cancelled() {
+                break;
+            }
+            collector.process(item, &feed.tags, run).await;
+        }
+
+        run.feeds.push(FeedRun {
+            feed_url: feed_url.to_string(),
+            items: run.stats.total_items - before.total_items,
+            articles_saved: run.stats.articles_saved - before.articles_saved,
+            vocabularies_saved: run.stats.vocabularies_saved - before.vocabularies_saved,
+            error: None,
+        });
+        collector.save_progress(run).await;
+    }
+
+    run.cancelled = cancel.is_cancelled();
+    let stats = &run.stats;
+    info!(
+        articles_saved = stats.articles_saved,
+        vocabularies_saved = stats.vocabularies_saved,
+        vocabularies_rejected = stats.vocabularies_rejected,
+        contexts_snapped = stats.contexts_snapped,
+        skipped_duplicates = stats.skipped_duplicates,
+        resumed_items = stats.resumed_items,
+        cancelled = run.cancelled,
+        "Pipeline completed"
+    );
+
+    Ok(())
+}
+
+/// What the pipeline steps share within one run
+struct Collector<'a, S, L: ?Sized> {
+    fetcher: &'a RssFetcher,
+    storage: &'a S,
+    /// Dry run: save here instead of `storage`, and leave the work queue alone
+    preview: Option<&'a InMemoryStorage>,
+    llm: &'a L,
+    validator: VocabularyValidator,
+    cancel: &'a CancellationToken,
+}
+
+impl<'a, S, L> Collector<'a, S, L>
 where
     S: StoragePort,
-    L: LlmPort,
+    L: LlmPort + ?Sized,
 {
-    let mut stats = PipelineStats::default();
+    fn new(
+        fetcher: &'a RssFetcher,
+        storage: &'a S,
+        preview: Option<&'a InMemoryStorage>,
+        llm: &'a L,
+        known_words: &[String],
+        cancel: &'a CancellationToken,
+    ) -> Self {
+        Self {
+            fetcher,
+            storage,
+            preview,
+            llm,
+            validator: VocabularyValidator::new().with_known_words(known_words.iter().cloned()),
+            cancel,
+        }
+    }
+
+    /// Items queued by earlier runs for the configured feeds. Items of feeds
+    /// no longer configured are dropped from the queue.
+    async fn queued_items(&self, feeds: &[FeedConfig], run: &mut PipelineRun) -> Vec<QueuedItem> {
+        let queued = match self.storage.get_queued_items().await {
+            Ok(items) => items,
+            Err(e) => {
+                warn!(error = %e, "Failed to load the work queue, fetching every feed");
+                run.stats.storage_errors += 1;
+                return Vec::new();
+            }
+        };
+
+        let (queued, orphaned): (Vec<QueuedItem>, Vec<QueuedItem>) = queued
+            .into_iter()
+            .partition(|item| feeds.iter().any(|feed| feed.url == item.feed_url));
+        for item in &orphaned {
+            info!(url = %item.url, feed_url = %item.feed_url, "Dropping queued item of a feed no longer configured");
+            self.dequeue(&item.url).await;
+        }
+        queued
+    }
 
-    for feed_url in feed_urls {
+    /// Fetch the items of a feed and queue them. `None` if the feed could not be read.
+    async fn discover(&self, feed_url: &str, run: &mut PipelineRun) -> Option<Vec<QueuedItem>> {
         info!(feed_url, "Fetching RSS feed");
 
-        let feed_items = match fetcher.fetch_feed(feed_url).await {
+        let feed_items = match self.fetcher.fetch_feed(feed_url).await {
             Ok(items) => items,
             Err(e) => {
                 error!(feed_url, error = %e, "Failed to fetch feed");
-                stats.feed_errors += 1;
-                continue;
+                run.stats.feed_errors += 1;
+                run.record_error(RunStage::Feed, feed_url, &e);
+                run.feeds.push(FeedRun {
+                    feed_url: feed_url.to_string(),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                });
+                return None;
             }
         };
 
@@ -47,115 +245,202 @@ where
             "Fetched feed items"
         );
 
-        for item in feed_items {
-            stats.total_items += 1;
-
-            // Step 2: Deduplication check
-            match storage.exists(&item.url).await {
-                Ok(true) => {
-                    info!(url = %item.url, "Article already exists, skipping");
-                    stats.skipped_duplicates += 1;
-                    continue;
-                }
-                Ok(false) => {
-                    // New article, proceed
-                }
-                Err(e) => {
-                    error!(url = %item.url, error = %e, "Failed to check if article exists");
-                    stats.storage_errors += 1;
-                    continue;
-                }
+        let items: Vec<QueuedItem> = feed_items
+            .into_iter()
+            .map(|item| QueuedItem {
+                feed_url: feed_url.to_string(),
+                url: item.url,
+                title: item.title,
+                published_at: item.published_at,
+            })
+            .collect();
+        if self.preview.is_none() {
+            if let Err(e) = self.storage.enqueue_items(&items).await {
+                // The run goes on; only resuming it would not work
+                warn!(feed_url, error = %e, "Failed to queue feed items");
+                run.stats.storage_errors += 1;
+                run.record_error(RunStage::Save, feed_url, e);
             }
+        }
+        Some(items)
+    }
 
-            // Step 3: Fetch body content
-            let body = match fetcher.fetch_body(&item.url).await {
-                Ok(content) => {
-                    if content.is_empty() {
-                        warn!(url = %item.url, "Fetched empty body content");
-                    }
-                    content
-                }
-                Err(e) => {
-                    error!(url = %item.url, error = %e, "Failed to fetch body");
-                    stats.fetch_errors += 1;
-                    continue;
-                }
-            };
+    /// Take one item through the pipeline and off the queue, then wait out
+    /// the rate limit if it reached the LLM.
+    async fn process(&self, item: &QueuedItem, tags: &[String], run: &mut PipelineRun) {
+        let saved = self.collect_item(item, tags, run).await;
+        self.dequeue(&item.url).await;
 
-            // Step 4: AI Extract vocabularies
-            let vocabularies = match llm.extract(&body).await {
-                Ok(vocabs) => {
-                    info!(url = %item.url, vocab_count = vocabs.len(), "Extracted vocabularies");
-                    vocabs
-                }
-                Err(e) => {
-                    // LLM failure: save article without vocabularies
-                    warn!(url = %item.url, error = %e, "LLM extraction failed, saving article without vocabularies");
-                    stats.llm_errors += 1;
-                    Vec::new()
-                }
-            };
-
-            // Step 5: Persist article
-            let article = Article {
-                url: item.url.clone(),
-                title: item.title.clone(),
-                content: body,
-                source: SourceType::RSS,
-                published_at: item.published_at,
-                collected_at: Utc::now(),
-            };
+        if saved {
+            // Rate limiting: sleep between LLM calls
+            tokio::select! {
+                _ = sleep(Duration::from_secs(LLM_RATE_LIMIT_DELAY_SECS)) => {}
+                _ = self.cancel.cancelled() => {}
+            }
+        }
+    }
 
-            if let Err(e) = storage.save_article(&article).await {
-                error!(url = %item.url, error = %e, "Failed to save article");
-                stats.storage_errors += 1;
-                continue;
+    /// Steps 2 to 5 for one item. Returns `true` if the article was saved.
+    async fn collect_item(
+        &self,
+        item: &QueuedItem,
+        tags: &[String],
+        run: &mut PipelineRun,
+    ) -> bool {
+        run.stats.total_items += 1;
+
+        // Step 2: Deduplication check (a dry run also sees what it would have saved)
+        let exists = match self.preview {
+            Some(preview) if preview.exists(&item.url).await.unwrap_or(false) => Ok(true),
+            _ => self.storage.exists(&item.url).await,
+        };
+        match exists {
+            Ok(true) => {
+                info!(url = %item.url, "Article already exists, skipping");
+                run.stats.skipped_duplicates += 1;
+                return false;
+            }
+            Ok(false) => {
+                // New article, proceed
             }
+            Err(e) => {
+                error!(url = %item.url, error = %e, "Failed to check if article exists");
+                run.stats.storage_errors += 1;
+                run.record_error(RunStage::Save, &item.url, e);
+                return false;
+            }
+        }
 
-            info!(url = %item.url, title = %item.title, "Saved article");
-            stats.articles_saved += 1;
-
-            // Step 5: Persist vocabularies
-            for vocab in vocabularies {
-                let vocab_with_source = Vocabulary {
-                    source_url: item.url.clone(),
-                    ..vocab
-                };
-
-                if let Err(e) = storage.save_vocab(&vocab_with_source).await {
-                    error!(word = %vocab_with_source.word, error = %e, "Failed to save vocabulary");
-                    stats.storage_errors += 1;
-                } else {
-                    stats.vocabularies_saved += 1;
+        // Step 3: Fetch body content
+        let body = match self.fetcher.fetch_body(&item.url).await {
+            Ok(content) => {
+                if content.is_empty() {
+                    warn!(url = %item.url, "Fetched empty body content");
                 }
+                content
+            }
+            Err(e) => {
+                error!(url = %item.url, error = %e, "Failed to fetch body");
+                run.stats.fetch_errors += 1;
+                run.record_error(RunStage::Fetch, &item.url, e);
+                return false;
             }
+        };
 
-            // Rate limiting: sleep between LLM calls
-            sleep(Duration::from_secs(LLM_RATE_LIMIT_DELAY_SECS)).await;
+        // Step 4: AI Extract vocabularies
+        let vocabularies = match self.llm.extract(&body).await {
+            Ok(vocabs) => {
+                info!(url = %item.url, vocab_count = vocabs.len(), "Extracted vocabularies");
+                vocabs
+            }
+            Err(e) => {
+                // LLM failure: save article without vocabularies
+                warn!(url = %item.url, error = %e, "LLM extraction failed, saving article without vocabularies");
+                run.stats.llm_errors += 1;
+                run.record_error(RunStage::Extract, &item.url, e);
+                Vec::new()
+            }
+        };
+
+        // Step 4.5: Validate against the article body
+        let vocabularies =
+            validate_vocabularies(&self.validator, vocabularies, &body, &mut run.stats);
+
+        // Step 5: Persist article and vocabularies
+        let article = Article {
+            id: None,
+            url: item.url.clone(),
+            title: item.title.clone(),
+            content: body,
+            source: SourceType::RSS,
+            feed_url: Some(item.feed_url.clone()),
+            published_at: item.published_at,
+            collected_at: Utc::now(),
+        };
+
+        match self.preview {
+            Some(preview) => persist_article(preview, &article, vocabularies, tags, run).await,
+            None => persist_article(self.storage, &article, vocabularies, tags, run).await,
         }
     }
 
-    info!(
-        articles_saved = stats.articles_saved,
-        vocabularies_saved = stats.vocabularies_saved,
-        skipped_duplicates = stats.skipped_duplicates,
-        "Pipeline completed"
-    );
+    /// Take a processed item off the queue; a dry run leaves it alone.
+    async fn dequeue(&self, url: &str) {
+        if self.preview.is_some() {
+            return;
+        }
+        if let Err(e) = self.storage.dequeue_item(url).await {
+            warn!(url, error = %e, "Failed to take item off the work queue");
+        }
+    }
 
-    Ok(stats)
+    /// Store the run so far, so an interrupted run still shows its progress.
+    async fn save_progress(&self, run: &PipelineRun) {
+        if self.preview.is_some() || run.id.is_none() {
+            return;
+        }
+        if let Err(e) = self.storage.update_run(run).await {
+            warn!(error = %e, "Failed to record run progress");
+        }
+    }
 }
 
-/// Statistics collected during pipeline execution
-#[derive(Debug, Default)]
-pub struct PipelineStats {
-    pub total_items: usize,
-    pub articles_saved: usize,
-    pub vocabularies_saved: usize,
-    pub skipped_duplicates: usize,
-    pub feed_errors: usize,
-    pub fetch_errors: usize,
-    pub llm_errors: usize,
-    pub storage_errors: usize,
+/// Run the shared validator over extracted vocabularies and record the outcome in `stats`.
+fn validate_vocabularies(
+    validator: &VocabularyValidator,
+    vocabularies: Vec<Vocabulary>,
+    body: &str,
+    stats: &mut RunStats,
+) -> Vec<Vocabulary> {
+    let report = validator.validate(vocabularies, body);
+
+    for (vocab, reason) in &report.rejected {
+        warn!(word = %vocab.word, reason = ?reason, "Dropped vocabulary that failed validation");
+    }
+
+    stats.vocabularies_rejected += report.rejected.len();
+    stats.contexts_snapped += report.snapped;
+
+    report.accepted
+}
+
+/// Save an article together with its vocabularies, pointing each word at the
+/// article URL and adding `tags` to it.
+///
+/// Returns `false` if nothing could be saved.
+async fn persist_article<S: StoragePort>(
+    storage: &S,
+    article: &Article,
+    vocabularies: Vec<Vocabulary>,
+    tags: &[String],
+    run: &mut PipelineRun,
+) -> bool {
+    let vocabularies: Vec<Vocabulary> = vocabularies
+        .into_iter()
+        .map(|mut vocab| {
+            vocab.tags.extend_from_slice(tags);
+            Vocabulary {
+                source_url: article.url.clone(),
+                ..vocab
+            }
+        })
+        .collect();
+
+    if let Err(e) = storage
+        .save_article_with_vocab(article, &vocabularies)
+        .await
+    {
+        error!(url = %article.url, error = %e, "Failed to save article and vocabularies");
+        run.stats.storage_errors += 1;
+        run.record_error(RunStage::Save, &article.url, e);
+        return false;
+    }
+
+    info!(url = %article.url, title = %article.title, vocab_count = vocabularies.len(), "Saved article");
+    run.stats.articles_saved += 1;
+    run.stats.vocabularies_saved += vocabularies.len();
+
+    true
 }
 
 /// Pipeline error type
@@ -172,41 +457,6 @@ mod tests {
     use async_trait::async_trait;
     use spread_core::CoreError;
 
-    struct MockStorage {
-        existing_urls: Vec<String>,
-    }
-
-    #[async_trait]
-    impl StoragePort for MockStorage {
-        async fn exists(&self, url: &str) -> Result<bool, CoreError> {
-            Ok(self.existing_urls.contains(&url.to_string()))
-        }
-
-        async fn save_article(&self, _article: &Article) -> Result<(), CoreError> {
-            Ok(())
-        }
-
-        async fn save_vocab(&self, _vocab: &Vocabulary) -> Result<(), CoreError> {
-            Ok(())
-        }
-
-        async fn get_all_vocab(&self) -> Result<Vec<Vocabulary>, CoreError> {
-            Ok(vec![])
-        }
-
-        async fn search_vocab(&self, _query: &str) -> Result<Vec<Vocabulary>, CoreError> {
-            Ok(vec![])
-        }
-
-        async fn get_today_vocab(&self) -> Result<Vec<Vocabulary>, CoreError> {
-            Ok(vec![])
-        }
-
-        async fn get_random_vocab(&self) -> Result<Option<Vocabulary>, CoreError> {
-            Ok(None)
-        }
-    }
-
     struct MockLlm;
 
     #[async_trait]
@@ -217,14 +467,153 @@ mod tests {
                 definition: "a test word".to_string(),
                 context_sentence: "This is a test.".to_string(),
                 source_url: String::new(),
+                part_of_speech: Some("noun".to_string()),
+                synonyms: vec!["trial".to_string()],
+                ..Default::default()
             }])
         }
     }
 
     #[test]
     fn test_pipeline_stats_default() {
-        let stats = PipelineStats::default();
+        let stats = RunStats::default();
         assert_eq!(stats.total_items, 0);
         assert_eq!(stats.articles_saved, 0);
     }
+
+    #[tokio::test]
+    async fn test_persist_article_keeps_lexical_fields() {
+        let storage = InMemoryStorage::new();
+        let article = Article {
+            id: None,
+            url: "https://example.com/post".to_string(),
+            title: "Post".to_string(),
+            content: "This is a test.".to_string(),
+            source: SourceType::RSS,
+            feed_url: None,
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        assert!(!storage.exists(&article.url).await.unwrap());
+
+        let vocabularies = MockLlm.extract(&article.content).await.unwrap();
+        let mut run = PipelineRun::new(RunTrigger::Manual, Utc::now());
+        let tags = vec!["rust".to_string()];
+        assert!(persist_article(&storage, &article, vocabularies, &tags, &mut run).await);
+
+        assert_eq!(run.stats.articles_saved, 1);
+        assert_eq!(run.stats.vocabularies_saved, 1);
+        assert!(run.errors.is_empty());
+
+        let saved = storage.get_vocab_by_word("test").await.unwrap().unwrap();
+        assert_eq!(saved.source_url, "https://example.com/post");
+        assert_eq!(saved.part_of_speech.as_deref(), Some("noun"));
+        assert_eq!(saved.synonyms, vec!["trial"]);
+        assert_eq!(saved.tags, vec!["rust"]);
+        assert!(storage.exists(&article.url).await.unwrap());
+    }
+
+    #[test]
+    fn test_validate_vocabularies_counts() {
+        let vocabularies = vec![
+            Vocabulary {
+                word: "ubiquitous".to_string(),
+                definition: "Present everywhere".to_string(),
+                context_sentence: "Phones are everywhere.".to_string(),
+                ..Default::default()
+            },
+            Vocabulary {
+                word: "serendipity".to_string(),
+                definition: "Happy chance".to_string(),
+                context_sentence: "It was serendipity.".to_string(),
+                ..Default::default()
+            },
+        ];
+        let body = "Smartphones are ubiquitous. Nothing else here.";
+
+        let mut stats = RunStats::default();
+        let accepted =
+            validate_vocabularies(&VocabularyValidator::new(), vocabularies, body, &mut stats);
+
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(accepted[0].context_sentence, "Smartphones are ubiquitous.");
+        assert_eq!(stats.vocabularies_rejected, 1);
+        assert_eq!(stats.contexts_snapped, 1);
+    }
+
+    /// A feed nothing listens on, so fetching it would count as a feed error
+    const UNREACHABLE_FEED: &str = "http://127.0.0.1:9/feed.xml";
+
+    fn queued(url: &str) -> QueuedItem {
+        QueuedItem {
+            feed_url: UNREACHABLE_FEED.to_string(),
+            url: url.to_string(),
+            title: "Queued".to_string(),
+            published_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queued_items_are_resumed_without_fetching_the_feed() {
+        let storage = InMemoryStorage::new();
+        let saved = Article {
+            id: None,
+            url: "https://example.com/saved".to_string(),
+            title: "Saved".to_string(),
+            content: "Already here.".to_string(),
+            source: SourceType::RSS,
+            feed_url: Some(UNREACHABLE_FEED.to_string()),
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        storage.save_article(&saved).await.unwrap();
+        let gone = QueuedItem {
+            feed_url: "https://removed.example/rss".to_string(),
+            ..queued("https://removed.example/post")
+        };
+        storage
+            .enqueue_items(&[queued(&saved.url), gone])
+            .await
+            .unwrap();
+
+        let feeds = [FeedConfig::new(UNREACHABLE_FEED)];
+        let mut run = PipelineRun::new(RunTrigger::Manual, Utc::now());
+        let fetcher = RssFetcher::new();
+        let cancel = CancellationToken::new();
+        let collector = Collector::new(&fetcher, &storage, None, &MockLlm, &[], &cancel);
+        collect(&feeds, &collector, &mut run).await.unwrap();
+
+        assert_eq!(run.stats.resumed_items, 1);
+        assert_eq!(run.stats.skipped_duplicates, 1);
+        assert_eq!(run.stats.feed_errors, 0);
+        assert_eq!(run.feeds.len(), 1);
+        assert_eq!(run.feeds[0].items, 1);
+        assert!(!run.cancelled);
+        // Processed, and the item of the removed feed dropped
+        assert!(storage.get_queued_items().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_run_leaves_items_queued() {
+        let storage = InMemoryStorage::new();
+        storage
+            .enqueue_items(&[
+                queued("https://example.com/1"),
+                queued("https://example.com/2"),
+            ])
+            .await
+            .unwrap();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let feeds = [FeedConfig::new(UNREACHABLE_FEED)];
+        let mut run = PipelineRun::new(RunTrigger::Manual, Utc::now());
+        let fetcher = RssFetcher::new();
+        let collector = Collector::new(&fetcher, &storage, None, &MockLlm, &[], &cancel);
+        collect(&feeds, &collector, &mut run).await.unwrap();
+
+        assert!(run.cancelled);
+        assert_eq!(run.stats.total_items, 0);
+        assert_eq!(storage.get_queued_items().await.unwrap().len(), 2);
+    }
 }
@@ -9,6 +9,9 @@ pub enum CoreError {
     #[error("Database error: {0}")]
     Database(String),
 
+    #[error("Not found: {0}")]
+    NotFound(String),
+
     #[error("LLM error: {0}")]
     Llm(String),
 
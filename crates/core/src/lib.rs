@@ -1,7 +1,16 @@
 pub mod error;
 pub mod model;
 pub mod port;
+pub mod query;
+pub mod quiz;
+pub mod run;
+pub mod srs;
+pub mod timezone;
 
 pub use error::CoreError;
-pub use model::{Article, SourceType, Vocabulary};
+pub use model::{Article, Deck, Occurrence, ReviewCard, SearchHit, SourceType, Vocabulary};
 pub use port::{FetcherPort, LlmPort, StoragePort};
+pub use query::{ReviewStatus, TagFilter, VocabPage, VocabQuery, VocabSort};
+pub use quiz::{Question, QuestionKind};
+pub use run::{FeedHealth, PipelineRun, QueuedItem, RunStats, RunTrigger};
+pub use srs::{Algorithm, Grade, ReviewState};
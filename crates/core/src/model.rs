@@ -1,7 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::srs::ReviewState;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SourceType {
     RSS,
     Manual,
@@ -10,18 +12,112 @@ pub enum SourceType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Article {
+    /// Storage ID; `None` until the article is saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
     pub url: String,
     pub title: String,
     pub content: String,
     pub source: SourceType,
+    /// Feed the article was found in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed_url: Option<String>,
     pub published_at: DateTime<Utc>,
     pub collected_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Vocabulary {
+    /// Storage ID; `None` for words not saved yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
     pub word: String,
     pub definition: String,
     pub context_sentence: String,
     pub source_url: String,
+
+    // Optional lexical details (older rows and providers may not have them)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part_of_speech: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipa: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cefr_level: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub antonyms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collocations: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example_sentence: Option<String>,
+
+    /// Every article the word was seen in, oldest first. `context_sentence` and
+    /// `source_url` mirror the first entry. Ignored by `StoragePort::save_vocab`,
+    /// which records the top-level context as a new occurrence.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub occurrences: Vec<Occurrence>,
+
+    /// Topic tags, normalized to lowercase with spaces as `-`. Saving a word
+    /// adds these to its existing tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Fields changed by hand (e.g. `"definition"`, `"synonyms"`), which
+    /// later extractions never overwrite
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edited_fields: Vec<String>,
+    /// Left out of reviews
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub suspended: bool,
+}
+
+/// One sighting of a word in an article
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Occurrence {
+    pub source_url: String,
+    pub context_sentence: String,
+    /// Definition the extractor gave in this context
+    pub definition: String,
+    pub extracted_at: DateTime<Utc>,
+}
+
+/// A named set of tags to study together; holds every word carrying any of them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deck {
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+/// A word scheduled for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewCard {
+    pub word_id: i64,
+    pub vocabulary: Vocabulary,
+    pub state: ReviewState,
+}
+
+/// Marks matched terms in search snippets, e.g. `a **lucid** essay`
+pub const SNIPPET_HIGHLIGHT: &str = "**";
+
+/// A full-text search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchHit {
+    Word {
+        word_id: i64,
+        vocabulary: Box<Vocabulary>,
+        /// Best-matching word, definition or context excerpt
+        snippet: String,
+        /// BM25 rank; lower is a better match
+        rank: f64,
+    },
+    Article {
+        article_id: i64,
+        url: String,
+        title: String,
+        /// Best-matching title or body excerpt
+        snippet: String,
+        rank: f64,
+    },
 }
@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 
 use crate::error::CoreError;
-use crate::model::{Article, Vocabulary};
+use crate::model::{Article, Deck, ReviewCard, SearchHit, Vocabulary};
+use crate::query::{TagFilter, VocabPage, VocabQuery};
+use crate::run::{PipelineRun, QueuedItem};
+use crate::srs::{Grade, ReviewState};
 
 #[async_trait]
 pub trait FetcherPort: Send + Sync {
@@ -13,15 +16,116 @@ pub trait StoragePort: Send + Sync {
     async fn exists(&self, url: &str) -> Result<bool, CoreError>;
     async fn save_article(&self, article: &Article) -> Result<(), CoreError>;
     async fn save_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError>;
+    /// Save an article and the words seen in it in one transaction, so a
+    /// failure leaves neither behind.
+    async fn save_article_with_vocab(
+        &self,
+        article: &Article,
+        vocabularies: &[Vocabulary],
+    ) -> Result<(), CoreError>;
+
+    /// A saved word by its id.
+    async fn get_vocab_by_id(&self, id: i64) -> Result<Option<Vocabulary>, CoreError>;
+    /// A saved word by its spelling, or by a spelling merged into it.
+    async fn get_vocab_by_word(&self, word: &str) -> Result<Option<Vocabulary>, CoreError>;
+    /// Overwrite the word, lexical fields, `edited_fields` and `suspended` of
+    /// the saved word `vocab.id`. Occurrences are left as they are.
+    async fn update_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError>;
+    /// Delete a word with its occurrences, tags and review history, returning
+    /// whether it existed.
+    async fn delete_vocab(&self, id: i64) -> Result<bool, CoreError>;
+    /// Fold word `from` into word `into`: contexts, tags and review history
+    /// move over, missing lexical fields are filled in, and later sightings of
+    /// `from` are recorded under `into`. Returns the merged word.
+    async fn merge_vocab(&self, from: i64, into: i64) -> Result<Vocabulary, CoreError>;
 
     // Query methods for integration crate
-    async fn get_all_vocab(&self) -> Result<Vec<Vocabulary>, CoreError>;
+    /// Words matching `query`, one page at a time.
+    async fn query_vocab(&self, query: &VocabQuery) -> Result<VocabPage, CoreError>;
+    /// Words matching a full-text query, best match first.
+    ///
+    /// Bare terms match as prefixes and `"quoted text"` as a phrase.
     async fn search_vocab(&self, query: &str) -> Result<Vec<Vocabulary>, CoreError>;
-    async fn get_today_vocab(&self) -> Result<Vec<Vocabulary>, CoreError>;
+    /// Up to `limit` word hits followed by up to `limit` article hits, each
+    /// ranked best first, with highlighted snippets.
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>, CoreError>;
     async fn get_random_vocab(&self) -> Result<Option<Vocabulary>, CoreError>;
+
+    // Known words: lemmas the learner has already mastered (stored lowercase)
+    /// Add lemmas to the known-words list, returning how many were new.
+    async fn add_known_words(&self, words: &[String]) -> Result<usize, CoreError>;
+    /// Remove a lemma from the known-words list, returning whether it was present.
+    async fn remove_known_word(&self, word: &str) -> Result<bool, CoreError>;
+    /// All known lemmas, most recently added first.
+    async fn get_known_words(&self) -> Result<Vec<String>, CoreError>;
+    /// Lemmas whose review interval has reached `srs::MASTERED_INTERVAL_DAYS`.
+    async fn get_mastered_words(&self) -> Result<Vec<String>, CoreError>;
+
+    // Tags and decks (tag names are normalized like `Vocabulary::tags`)
+    /// Tag a word, returning how many of the tags were new to it.
+    async fn add_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError>;
+    /// Untag a word, returning how many of the tags it had.
+    async fn remove_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError>;
+    /// Every tag in use with its number of words, by name.
+    async fn get_tags(&self) -> Result<Vec<(String, usize)>, CoreError>;
+    /// Create a deck, or replace the tags of an existing one.
+    async fn save_deck(&self, deck: &Deck) -> Result<(), CoreError>;
+    /// Delete a deck (its tags stay), returning whether it existed.
+    async fn delete_deck(&self, name: &str) -> Result<bool, CoreError>;
+    /// All decks, by name.
+    async fn get_decks(&self) -> Result<Vec<Deck>, CoreError>;
+
+    // Spaced repetition
+    /// Reviewed words matching `filter` that are due now, most overdue first.
+    /// Suspended words are left out here and in `get_new_vocab`.
+    async fn get_due_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError>;
+    /// Words matching `filter` that have never been reviewed, oldest first.
+    async fn get_new_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError>;
+    /// Schedule the next review of a word and append it to the review log.
+    async fn record_review(&self, word_id: i64, grade: Grade) -> Result<ReviewState, CoreError>;
+
+    // Pipeline runs
+    /// Record the start of a run, returning its id.
+    async fn start_run(&self, run: &PipelineRun) -> Result<i64, CoreError>;
+    /// Store the finish time, cancellation, counts, feed results and errors of
+    /// the started run `run.id`, replacing what was stored before.
+    async fn update_run(&self, run: &PipelineRun) -> Result<(), CoreError>;
+    /// A run with its feed results and errors.
+    async fn get_run(&self, id: i64) -> Result<Option<PipelineRun>, CoreError>;
+    /// Up to `limit` runs (all with `None`), newest first, with their feed
+    /// results and errors.
+    async fn get_runs(&self, limit: Option<usize>) -> Result<Vec<PipelineRun>, CoreError>;
+
+    // Work queue
+    /// Queue feed items for processing, skipping URLs already queued.
+    /// Returns how many were added.
+    async fn enqueue_items(&self, items: &[QueuedItem]) -> Result<usize, CoreError>;
+    /// Queued items, oldest first.
+    async fn get_queued_items(&self) -> Result<Vec<QueuedItem>, CoreError>;
+    /// Remove a processed item from the queue.
+    async fn dequeue_item(&self, url: &str) -> Result<bool, CoreError>;
 }
 
 #[async_trait]
 pub trait LlmPort: Send + Sync {
     async fn extract(&self, text: &str) -> Result<Vec<Vocabulary>, CoreError>;
+
+    /// Score how well `answer` defines `word` compared to `reference`, from 0.0 to 1.0.
+    /// Returns `None` when the engine cannot judge answers.
+    async fn grade_definition(
+        &self,
+        _word: &str,
+        _reference: &str,
+        _answer: &str,
+    ) -> Result<Option<f64>, CoreError> {
+        Ok(None)
+    }
 }
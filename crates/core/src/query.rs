@@ -0,0 +1,251 @@
+//! Filtered, sorted and paginated vocabulary queries.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::{SourceType, Vocabulary};
+use crate::timezone::{day_bounds, Tz};
+
+/// Where a word stands in spaced repetition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    /// Never reviewed
+    New,
+    /// Reviewed and due now
+    Due,
+    /// Reviewed, interval below `srs::MASTERED_INTERVAL_DAYS`
+    Learning,
+    /// Reviewed, interval at or above `srs::MASTERED_INTERVAL_DAYS`
+    Mastered,
+}
+
+impl FromStr for ReviewStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "new" => Ok(Self::New),
+            "due" => Ok(Self::Due),
+            "learning" => Ok(Self::Learning),
+            "mastered" => Ok(Self::Mastered),
+            other => Err(format!(
+                "Unknown review status '{}' (expected new, due, learning or mastered)",
+                other
+            )),
+        }
+    }
+}
+
+/// Result order of a [`VocabQuery`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VocabSort {
+    /// First saved first
+    #[default]
+    Oldest,
+    /// Most recently saved first
+    Newest,
+    /// By lemma
+    Alphabetical,
+}
+
+impl fmt::Display for VocabSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Oldest => "oldest",
+            Self::Newest => "newest",
+            Self::Alphabetical => "alphabetical",
+        })
+    }
+}
+
+impl FromStr for VocabSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "oldest" => Ok(Self::Oldest),
+            "newest" => Ok(Self::Newest),
+            "alphabetical" | "alpha" => Ok(Self::Alphabetical),
+            other => Err(format!(
+                "Unknown sort order '{}' (expected oldest, newest or alphabetical)",
+                other
+            )),
+        }
+    }
+}
+
+/// Which words to load from storage.
+///
+/// Collection date, feed and source type filters match a word when any article
+/// it was seen in matches all of them. Page either with `offset` or by passing
+/// the previous page's `next_cursor` back in.
+#[derive(Debug, Clone, Default)]
+pub struct VocabQuery {
+    pub limit: Option<usize>,
+    pub offset: usize,
+    pub cursor: Option<String>,
+    /// Inclusive lower bound on the article's collection time
+    pub collected_from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on the article's collection time
+    pub collected_to: Option<DateTime<Utc>>,
+    /// URL of the feed the article came from
+    pub feed: Option<String>,
+    pub source: Option<SourceType>,
+    pub tag: Option<String>,
+    /// Name of a deck; matches words carrying any of its tags
+    pub deck: Option<String>,
+    pub review_status: Option<ReviewStatus>,
+    pub sort: VocabSort,
+}
+
+impl VocabQuery {
+    /// Every word, oldest first
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Continue after the page that returned this cursor
+    pub fn with_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    pub fn with_collected_from(mut self, from: DateTime<Utc>) -> Self {
+        self.collected_from = Some(from);
+        self
+    }
+
+    pub fn with_collected_to(mut self, to: DateTime<Utc>) -> Self {
+        self.collected_to = Some(to);
+        self
+    }
+
+    /// Words from articles collected on the local day `date` in `tz`
+    pub fn with_collected_on(self, date: NaiveDate, tz: Tz) -> Self {
+        let (start, end) = day_bounds(date, tz);
+        self.with_collected_from(start).with_collected_to(end)
+    }
+
+    pub fn with_feed(mut self, feed_url: impl Into<String>) -> Self {
+        self.feed = Some(feed_url.into());
+        self
+    }
+
+    pub fn with_source(mut self, source: SourceType) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    pub fn with_deck(mut self, deck: impl Into<String>) -> Self {
+        self.deck = Some(deck.into());
+        self
+    }
+
+    /// Apply the tag and deck of `filter`
+    pub fn with_tag_filter(mut self, filter: &TagFilter) -> Self {
+        self.tag = filter.tag.clone();
+        self.deck = filter.deck.clone();
+        self
+    }
+
+    pub fn with_review_status(mut self, status: ReviewStatus) -> Self {
+        self.review_status = Some(status);
+        self
+    }
+
+    pub fn with_sort(mut self, sort: VocabSort) -> Self {
+        self.sort = sort;
+        self
+    }
+}
+
+/// Limits review sessions to a tag, a deck, or both. The default matches every word.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    pub tag: Option<String>,
+    /// Name of a deck; matches words carrying any of its tags
+    pub deck: Option<String>,
+}
+
+impl TagFilter {
+    /// Every word
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    pub fn with_deck(mut self, deck: impl Into<String>) -> Self {
+        self.deck = Some(deck.into());
+        self
+    }
+}
+
+/// One page of [`VocabQuery`] results.
+#[derive(Debug, Clone, Default)]
+pub struct VocabPage {
+    pub vocabulary: Vec<Vocabulary>,
+    /// Pass to [`VocabQuery::with_cursor`] for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_collected_on_covers_one_day() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let query = VocabQuery::new().with_collected_on(date, Tz::UTC);
+        assert_eq!(
+            query.collected_from,
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            query.collected_to,
+            Some(Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap())
+        );
+
+        // A Seoul day starts at 15:00 UTC the day before
+        let query = VocabQuery::new().with_collected_on(date, Tz::Asia__Seoul);
+        assert_eq!(
+            query.collected_from,
+            Some(Utc.with_ymd_and_hms(2026, 2, 28, 15, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            "Mastered".parse::<ReviewStatus>(),
+            Ok(ReviewStatus::Mastered)
+        );
+        assert!("done".parse::<ReviewStatus>().is_err());
+        assert_eq!("alpha".parse::<VocabSort>(), Ok(VocabSort::Alphabetical));
+        assert_eq!(VocabSort::default(), VocabSort::Oldest);
+    }
+}
@@ -0,0 +1,84 @@
+//! Quiz questions generated from stored vocabulary.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Kinds of quiz question.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    /// Fill the word back into its context sentence
+    Cloze,
+    /// Pick the definition of the word among distractors
+    MultipleChoice,
+    /// Name the word from its definition
+    Reverse,
+    /// Spell the word from its definition and a letter hint
+    Spelling,
+}
+
+impl QuestionKind {
+    pub const ALL: [QuestionKind; 4] = [
+        Self::Cloze,
+        Self::MultipleChoice,
+        Self::Reverse,
+        Self::Spelling,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cloze => "cloze",
+            Self::MultipleChoice => "multiple_choice",
+            Self::Reverse => "reverse",
+            Self::Spelling => "spelling",
+        }
+    }
+
+    /// Whether the expected answer is the word itself rather than a definition
+    pub fn expects_word(self) -> bool {
+        !matches!(self, Self::MultipleChoice)
+    }
+}
+
+impl fmt::Display for QuestionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for QuestionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "cloze" => Ok(Self::Cloze),
+            "multiple_choice" | "choice" | "mc" => Ok(Self::MultipleChoice),
+            "reverse" => Ok(Self::Reverse),
+            "spelling" => Ok(Self::Spelling),
+            other => Err(format!(
+                "Unknown question type '{}' (expected cloze, multiple_choice, reverse or spelling)",
+                other
+            )),
+        }
+    }
+}
+
+/// A single quiz question about one word.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Question {
+    pub kind: QuestionKind,
+    /// Word being tested
+    pub word: String,
+    pub prompt: String,
+    /// Options for multiple-choice questions, empty otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+    /// Index of the correct option in `choices`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_index: Option<usize>,
+    pub answer: String,
+    pub context_sentence: String,
+    pub source_url: String,
+}
@@ -0,0 +1,303 @@
+//! Records of pipeline runs and the feed health derived from them.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Consecutive failed runs after which a feed is reported as broken
+pub const BROKEN_FEED_FAILURES: usize = 3;
+
+/// Latest runs that feed health is worked out from
+pub const FEED_HEALTH_RUNS: usize = 50;
+
+/// What started a pipeline run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    /// Started by hand
+    #[default]
+    Manual,
+    /// Started by a scheduler such as cron or launchd
+    Scheduled,
+}
+
+impl fmt::Display for RunTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Manual => "manual",
+            Self::Scheduled => "scheduled",
+        })
+    }
+}
+
+impl FromStr for RunTrigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "manual" => Ok(Self::Manual),
+            "scheduled" => Ok(Self::Scheduled),
+            other => Err(format!(
+                "Unknown run trigger '{}' (expected manual or scheduled)",
+                other
+            )),
+        }
+    }
+}
+
+/// Pipeline step an error happened in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStage {
+    /// Reading the feed itself
+    Feed,
+    /// Fetching an article body
+    Fetch,
+    /// Extracting words from an article
+    Extract,
+    /// Reading or writing storage
+    Save,
+}
+
+impl fmt::Display for RunStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Feed => "feed",
+            Self::Fetch => "fetch",
+            Self::Extract => "extract",
+            Self::Save => "save",
+        })
+    }
+}
+
+impl FromStr for RunStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "feed" => Ok(Self::Feed),
+            "fetch" => Ok(Self::Fetch),
+            "extract" => Ok(Self::Extract),
+            "save" => Ok(Self::Save),
+            other => Err(format!("Unknown run stage '{}'", other)),
+        }
+    }
+}
+
+/// Counts collected during a pipeline run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    pub total_items: usize,
+    pub articles_saved: usize,
+    pub vocabularies_saved: usize,
+    /// Vocabularies dropped because the word is not in the article, is a stop word or is already known
+    pub vocabularies_rejected: usize,
+    /// Vocabularies whose context sentence was replaced by the real sentence from the article
+    pub contexts_snapped: usize,
+    pub skipped_duplicates: usize,
+    /// Items taken from the work queue left by an earlier, unfinished run
+    pub resumed_items: usize,
+    pub feed_errors: usize,
+    pub fetch_errors: usize,
+    pub llm_errors: usize,
+    pub storage_errors: usize,
+}
+
+impl RunStats {
+    pub fn errors(&self) -> usize {
+        self.feed_errors + self.fetch_errors + self.llm_errors + self.storage_errors
+    }
+}
+
+/// Something that went wrong during a run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunError {
+    pub stage: RunStage,
+    /// Feed or article the error is about
+    pub url: String,
+    pub message: String,
+}
+
+/// How one feed fared in a run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedRun {
+    pub feed_url: String,
+    pub items: usize,
+    pub articles_saved: usize,
+    pub vocabularies_saved: usize,
+    /// Why the feed could not be read; `None` when it was
+    pub error: Option<String>,
+}
+
+/// One execution of the collection pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineRun {
+    /// Storage ID; `None` until the run is started in storage
+    pub id: Option<i64>,
+    pub trigger: RunTrigger,
+    pub started_at: DateTime<Utc>,
+    /// `None` while running, or if the run was interrupted
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Whether the run was stopped before processing every item
+    pub cancelled: bool,
+    pub stats: RunStats,
+    pub feeds: Vec<FeedRun>,
+    pub errors: Vec<RunError>,
+}
+
+impl PipelineRun {
+    pub fn new(trigger: RunTrigger, started_at: DateTime<Utc>) -> Self {
+        Self {
+            id: None,
+            trigger,
+            started_at,
+            finished_at: None,
+            cancelled: false,
+            stats: RunStats::default(),
+            feeds: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Record an error about `url`.
+    pub fn record_error(&mut self, stage: RunStage, url: &str, message: impl fmt::Display) {
+        self.errors.push(RunError {
+            stage,
+            url: url.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
+/// A feed item found by a run and waiting to be processed. Items stay
+/// queued until processed, so an interrupted run can be resumed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedItem {
+    pub feed_url: String,
+    pub url: String,
+    pub title: String,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Recent track record of a feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedHealth {
+    pub feed_url: String,
+    /// Runs in a row, up to the latest, in which the feed could not be read
+    pub consecutive_failures: usize,
+    /// Start of the latest run that read the feed, if among the runs looked at
+    pub last_success: Option<DateTime<Utc>>,
+    /// Error of the latest run, if it failed
+    pub last_error: Option<String>,
+}
+
+impl FeedHealth {
+    /// Whether the feed has failed at least [`BROKEN_FEED_FAILURES`] runs in a row
+    pub fn is_broken(&self) -> bool {
+        self.consecutive_failures >= BROKEN_FEED_FAILURES
+    }
+}
+
+/// Health of every feed in `runs`, by feed URL. `runs` may be in any order.
+pub fn feed_health(runs: &[PipelineRun]) -> Vec<FeedHealth> {
+    let mut runs: Vec<&PipelineRun> = runs.iter().collect();
+    runs.sort_by_key(|run| (run.started_at, run.id));
+
+    let mut health: BTreeMap<&str, FeedHealth> = BTreeMap::new();
+    for run in runs {
+        for feed in &run.feeds {
+            let entry = health
+                .entry(feed.feed_url.as_str())
+                .or_insert_with(|| FeedHealth {
+                    feed_url: feed.feed_url.clone(),
+                    consecutive_failures: 0,
+                    last_success: None,
+                    last_error: None,
+                });
+            match &feed.error {
+                Some(error) => {
+                    entry.consecutive_failures += 1;
+                    entry.last_error = Some(error.clone());
+                }
+                None => {
+                    entry.consecutive_failures = 0;
+                    entry.last_success = Some(run.started_at);
+                    entry.last_error = None;
+                }
+            }
+        }
+    }
+    health.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_feed_health() {
+        let run = |day: u32, results: &[(&str, Option<&str>)]| PipelineRun {
+            feeds: results
+                .iter()
+                .map(|(url, error)| FeedRun {
+                    feed_url: url.to_string(),
+                    error: error.map(str::to_string),
+                    ..Default::default()
+                })
+                .collect(),
+            ..PipelineRun::new(
+                RunTrigger::Manual,
+                Utc.with_ymd_and_hms(2026, 3, day, 8, 0, 0).unwrap(),
+            )
+        };
+        // Newest first, as storage returns them
+        let runs = [
+            run(
+                4,
+                &[
+                    ("https://a.example", Some("404")),
+                    ("https://b.example", None),
+                ],
+            ),
+            run(3, &[("https://a.example", Some("timeout"))]),
+            run(2, &[("https://a.example", Some("timeout"))]),
+            run(
+                1,
+                &[
+                    ("https://a.example", None),
+                    ("https://b.example", Some("dns")),
+                ],
+            ),
+        ];
+
+        let health = feed_health(&runs);
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[0].feed_url, "https://a.example");
+        assert_eq!(health[0].consecutive_failures, 3);
+        assert!(health[0].is_broken());
+        assert_eq!(health[0].last_error.as_deref(), Some("404"));
+        assert_eq!(
+            health[0].last_success,
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap())
+        );
+        assert_eq!(health[1].consecutive_failures, 0);
+        assert!(!health[1].is_broken());
+        assert_eq!(health[1].last_error, None);
+    }
+
+    #[test]
+    fn test_parse_trigger() {
+        assert_eq!(
+            " Scheduled".parse::<RunTrigger>(),
+            Ok(RunTrigger::Scheduled)
+        );
+        assert!("cron".parse::<RunTrigger>().is_err());
+        assert_eq!("save".parse::<RunStage>(), Ok(RunStage::Save));
+        assert_eq!(RunStage::Extract.to_string(), "extract");
+    }
+}
@@ -0,0 +1,417 @@
+//! Spaced-repetition scheduling (SM-2 and FSRS).
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Environment variable selecting the scheduling algorithm (`sm2` or `fsrs`).
+pub const ENV_SCHEDULER: &str = "SPREAD_SCHEDULER";
+
+/// Words whose review interval reaches this many days count as mastered.
+pub const MASTERED_INTERVAL_DAYS: u32 = 21;
+
+/// Learner's self-assessment after a review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Grade {
+    Again = 1,
+    Hard = 2,
+    Good = 3,
+    Easy = 4,
+}
+
+impl Grade {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Self::Again),
+            2 => Some(Self::Hard),
+            3 => Some(Self::Good),
+            4 => Some(Self::Easy),
+            _ => None,
+        }
+    }
+
+    /// Whether the word was recalled
+    pub fn is_pass(self) -> bool {
+        self != Self::Again
+    }
+}
+
+/// Scheduling state of one word.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewState {
+    /// Successful reviews in a row
+    pub reps: u32,
+    pub lapses: u32,
+    /// SM-2 ease factor
+    pub ease: f64,
+    pub interval_days: u32,
+    /// FSRS memory stability in days (0 until first FSRS review)
+    pub stability: f64,
+    /// FSRS difficulty, 1 (easy) to 10 (hard)
+    pub difficulty: f64,
+    pub due_at: DateTime<Utc>,
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl ReviewState {
+    /// State of a word that has never been reviewed
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            reps: 0,
+            lapses: 0,
+            ease: SM2_INITIAL_EASE,
+            interval_days: 0,
+            stability: 0.0,
+            difficulty: 0.0,
+            due_at: now,
+            last_reviewed_at: None,
+        }
+    }
+
+    pub fn is_mastered(&self) -> bool {
+        self.interval_days >= MASTERED_INTERVAL_DAYS
+    }
+}
+
+/// Computes the next state of a word after a review.
+pub trait Scheduler: Send + Sync {
+    fn schedule(&self, state: &ReviewState, grade: Grade, now: DateTime<Utc>) -> ReviewState;
+}
+
+/// Available scheduling algorithms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    #[default]
+    Sm2,
+    Fsrs,
+}
+
+impl Algorithm {
+    /// Read from `SPREAD_SCHEDULER`, falling back to SM-2 when unset or invalid.
+    pub fn from_env() -> Self {
+        std::env::var(ENV_SCHEDULER)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sm2 => "sm2",
+            Self::Fsrs => "fsrs",
+        }
+    }
+
+    pub fn scheduler(self) -> Box<dyn Scheduler> {
+        match self {
+            Self::Sm2 => Box::new(Sm2),
+            Self::Fsrs => Box::new(Fsrs::default()),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sm2" | "sm-2" => Ok(Self::Sm2),
+            "fsrs" => Ok(Self::Fsrs),
+            other => Err(format!(
+                "Unknown scheduler '{}' (expected sm2 or fsrs)",
+                other
+            )),
+        }
+    }
+}
+
+const SM2_INITIAL_EASE: f64 = 2.5;
+const SM2_MIN_EASE: f64 = 1.3;
+
+/// Classic SuperMemo-2, with the four grades mapped to quality 1, 3, 4 and 5.
+pub struct Sm2;
+
+impl Scheduler for Sm2 {
+    fn schedule(&self, state: &ReviewState, grade: Grade, now: DateTime<Utc>) -> ReviewState {
+        let quality = match grade {
+            Grade::Again => 1.0,
+            Grade::Hard => 3.0,
+            Grade::Good => 4.0,
+            Grade::Easy => 5.0,
+        };
+
+        let mut next = state.clone();
+        next.ease = (state.ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02))
+            .max(SM2_MIN_EASE);
+
+        if grade.is_pass() {
+            next.interval_days = match state.reps {
+                0 => 1,
+                1 => 6,
+                _ => (state.interval_days as f64 * next.ease).round() as u32,
+            };
+            next.reps += 1;
+        } else {
+            next.interval_days = 1;
+            next.reps = 0;
+            next.lapses += 1;
+        }
+
+        next.due_at = now + Duration::days(next.interval_days as i64);
+        next.last_reviewed_at = Some(now);
+        next
+    }
+}
+
+/// Default FSRS-5 parameters.
+const FSRS_DEFAULT_WEIGHTS: [f64; 19] = [
+    0.4072, 1.1829, 3.1262, 15.4722, 7.2102, 0.5316, 1.0651, 0.0234, 1.616, 0.1544, 1.0824, 1.9813,
+    0.0953, 0.2975, 2.2042, 0.2407, 2.9466, 0.5034, 0.6567,
+];
+const FSRS_DECAY: f64 = -0.5;
+const FSRS_FACTOR: f64 = 19.0 / 81.0;
+const FSRS_MAX_INTERVAL_DAYS: f64 = 36500.0;
+
+/// Free Spaced Repetition Scheduler (FSRS-5). Reviews are scheduled whole days
+/// apart, so the same-day review update is left out; w17 and w18 only cap the
+/// stability after a lapse.
+pub struct Fsrs {
+    weights: [f64; 19],
+    /// Target probability of recall when a word comes due
+    desired_retention: f64,
+}
+
+impl Default for Fsrs {
+    fn default() -> Self {
+        Self {
+            weights: FSRS_DEFAULT_WEIGHTS,
+            desired_retention: 0.9,
+        }
+    }
+}
+
+impl Fsrs {
+    pub fn with_desired_retention(mut self, retention: f64) -> Self {
+        self.desired_retention = retention.clamp(0.7, 0.99);
+        self
+    }
+
+    fn grade_value(grade: Grade) -> f64 {
+        grade.as_u8() as f64
+    }
+
+    fn initial_stability(&self, grade: Grade) -> f64 {
+        self.weights[grade.as_u8() as usize - 1]
+    }
+
+    fn initial_difficulty(&self, grade: Grade) -> f64 {
+        let w = &self.weights;
+        (w[4] - (w[5] * (Self::grade_value(grade) - 1.0)).exp() + 1.0).clamp(1.0, 10.0)
+    }
+
+    fn next_difficulty(&self, difficulty: f64, grade: Grade) -> f64 {
+        let w = &self.weights;
+        let delta = -w[6] * (Self::grade_value(grade) - 3.0);
+        // Linear damping: changes shrink as difficulty nears 10
+        let next = difficulty + delta * (10.0 - difficulty) / 9.0;
+        // Mean reversion towards the difficulty of an "Easy" first answer
+        (w[7] * self.initial_difficulty(Grade::Easy) + (1.0 - w[7]) * next).clamp(1.0, 10.0)
+    }
+
+    fn retrievability(elapsed_days: f64, stability: f64) -> f64 {
+        (1.0 + FSRS_FACTOR * elapsed_days / stability).powf(FSRS_DECAY)
+    }
+
+    fn recall_stability(&self, d: f64, s: f64, r: f64, grade: Grade) -> f64 {
+        let w = &self.weights;
+        let hard_penalty = if grade == Grade::Hard { w[15] } else { 1.0 };
+        let easy_bonus = if grade == Grade::Easy { w[16] } else { 1.0 };
+        s * (w[8].exp()
+            * (11.0 - d)
+            * s.powf(-w[9])
+            * ((w[10] * (1.0 - r)).exp() - 1.0)
+            * hard_penalty
+            * easy_bonus
+            + 1.0)
+    }
+
+    fn forget_stability(&self, d: f64, s: f64, r: f64) -> f64 {
+        let w = &self.weights;
+        let next =
+            w[11] * d.powf(-w[12]) * ((s + 1.0).powf(w[13]) - 1.0) * (w[14] * (1.0 - r)).exp();
+        next.min(s / (w[17] * w[18]).exp())
+    }
+
+    fn interval(&self, stability: f64) -> u32 {
+        let days = stability / FSRS_FACTOR * (self.desired_retention.powf(1.0 / FSRS_DECAY) - 1.0);
+        days.round().clamp(1.0, FSRS_MAX_INTERVAL_DAYS) as u32
+    }
+}
+
+impl Scheduler for Fsrs {
+    fn schedule(&self, state: &ReviewState, grade: Grade, now: DateTime<Utc>) -> ReviewState {
+        let mut next = state.clone();
+
+        let (stability, difficulty) = match state.last_reviewed_at {
+            Some(last) if state.stability > 0.0 => {
+                let elapsed = (now - last).num_seconds().max(0) as f64 / 86_400.0;
+                let r = Self::retrievability(elapsed, state.stability);
+                let d = state.difficulty;
+                let s = if grade.is_pass() {
+                    self.recall_stability(d, state.stability, r, grade)
+                } else {
+                    self.forget_stability(d, state.stability, r)
+                };
+                (s, self.next_difficulty(d, grade))
+            }
+            // Words scheduled by SM-2 so far start from their current interval
+            Some(_) if state.interval_days > 0 => {
+                let s = state.interval_days as f64;
+                let d = self.initial_difficulty(Grade::Good);
+                let s = if grade.is_pass() {
+                    self.recall_stability(d, s, self.desired_retention, grade)
+                } else {
+                    self.forget_stability(d, s, self.desired_retention)
+                };
+                (s, self.next_difficulty(d, grade))
+            }
+            _ => (
+                self.initial_stability(grade),
+                self.initial_difficulty(grade),
+            ),
+        };
+
+        next.stability = stability;
+        next.difficulty = difficulty;
+        next.interval_days = self.interval(stability);
+        if grade.is_pass() {
+            next.reps += 1;
+        } else {
+            next.reps = 0;
+            next.lapses += 1;
+        }
+        next.due_at = now + Duration::days(next.interval_days as i64);
+        next.last_reviewed_at = Some(now);
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review_sequence(scheduler: &dyn Scheduler, grades: &[Grade]) -> ReviewState {
+        let mut now = Utc::now();
+        let mut state = ReviewState::new(now);
+        for grade in grades {
+            state = scheduler.schedule(&state, *grade, now);
+            now = state.due_at;
+        }
+        state
+    }
+
+    #[test]
+    fn test_sm2_intervals() {
+        let state = review_sequence(&Sm2, &[Grade::Good, Grade::Good, Grade::Good]);
+        assert_eq!(state.reps, 3);
+        // 1 day, 6 days, then 6 * ease (2.5 unchanged by "Good")
+        assert_eq!(state.interval_days, 15);
+        assert!((state.ease - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sm2_lapse_resets() {
+        let state = review_sequence(&Sm2, &[Grade::Good, Grade::Good, Grade::Again]);
+        assert_eq!(state.reps, 0);
+        assert_eq!(state.lapses, 1);
+        assert_eq!(state.interval_days, 1);
+        assert!(state.ease < 2.5);
+        assert!(state.ease >= SM2_MIN_EASE);
+    }
+
+    #[test]
+    fn test_sm2_ease_floor() {
+        let state = review_sequence(&Sm2, &[Grade::Again; 10]);
+        assert!((state.ease - SM2_MIN_EASE).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fsrs_first_review_uses_initial_stability() {
+        let fsrs = Fsrs::default();
+        let state = review_sequence(&fsrs, &[Grade::Good]);
+        assert!((state.stability - FSRS_DEFAULT_WEIGHTS[2]).abs() < 1e-9);
+        assert_eq!(state.interval_days, 3);
+        assert!(state.difficulty >= 1.0 && state.difficulty <= 10.0);
+    }
+
+    #[test]
+    fn test_fsrs_intervals_grow_and_lapses_shrink() {
+        let fsrs = Fsrs::default();
+        let good = review_sequence(&fsrs, &[Grade::Good, Grade::Good, Grade::Good]);
+        let easy = review_sequence(&fsrs, &[Grade::Good, Grade::Good, Grade::Easy]);
+        let lapse = review_sequence(&fsrs, &[Grade::Good, Grade::Good, Grade::Again]);
+
+        assert!(good.interval_days > 3);
+        assert!(easy.interval_days > good.interval_days);
+        assert!(lapse.interval_days < good.interval_days);
+        assert_eq!(lapse.lapses, 1);
+    }
+
+    #[test]
+    fn test_fsrs_matches_reference_values() {
+        // Stability, difficulty and interval after each review, worked out
+        // separately from the FSRS-5 formulas as py-fsrs implements them, with
+        // default parameters and 90% retention, each review on its due day
+        let expected = [
+            (Grade::Good, 3.126200, 5.314578, 3),
+            (Grade::Good, 11.388439, 5.267036, 11),
+            (Grade::Good, 36.393666, 5.220606, 36),
+            (Grade::Again, 4.078747, 6.280021, 4),
+            (Grade::Good, 12.838265, 6.209888, 13),
+            (Grade::Hard, 18.645913, 6.579438, 19),
+            (Grade::Easy, 109.113179, 6.106966, 109),
+        ];
+
+        let fsrs = Fsrs::default();
+        let mut now = Utc::now();
+        let mut state = ReviewState::new(now);
+        for (grade, stability, difficulty, interval_days) in expected {
+            state = fsrs.schedule(&state, grade, now);
+            assert!((state.stability - stability).abs() < 1e-5, "{:?}", state);
+            assert!((state.difficulty - difficulty).abs() < 1e-5, "{:?}", state);
+            assert_eq!(state.interval_days, interval_days);
+            now = state.due_at;
+        }
+    }
+
+    #[test]
+    fn test_fsrs_difficulty_is_damped_near_the_maximum() {
+        let fsrs = Fsrs::default();
+        let hard = fsrs.next_difficulty(9.5, Grade::Again);
+        // Undamped, an "Again" would add 2 * w6 and hit the cap
+        assert!(hard < 9.7);
+        assert!(hard > fsrs.next_difficulty(9.5, Grade::Good));
+    }
+
+    #[test]
+    fn test_fsrs_continues_from_sm2_state() {
+        let sm2 = review_sequence(&Sm2, &[Grade::Good, Grade::Good]);
+        let next = Fsrs::default().schedule(&sm2, Grade::Good, sm2.due_at);
+        assert!(next.stability > sm2.interval_days as f64);
+        assert!(next.interval_days > sm2.interval_days);
+    }
+
+    #[test]
+    fn test_algorithm_parsing() {
+        assert_eq!("SM2".parse::<Algorithm>(), Ok(Algorithm::Sm2));
+        assert_eq!("fsrs".parse::<Algorithm>(), Ok(Algorithm::Fsrs));
+        assert!("leitner".parse::<Algorithm>().is_err());
+        assert_eq!(Grade::from_u8(3), Some(Grade::Good));
+        assert_eq!(Grade::from_u8(0), None);
+    }
+}
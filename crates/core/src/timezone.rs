@@ -0,0 +1,94 @@
+//! The learner's timezone, which decides what "today" means.
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+pub use chrono_tz::Tz;
+
+pub const ENV_TIMEZONE: &str = "SPREAD_TIMEZONE";
+
+/// Read an IANA name such as `Asia/Seoul` from `SPREAD_TIMEZONE`, UTC when unset.
+pub fn timezone_from_env() -> Result<Tz, String> {
+    match std::env::var(ENV_TIMEZONE) {
+        Ok(name) if !name.trim().is_empty() => parse_timezone(&name),
+        _ => Ok(Tz::UTC),
+    }
+}
+
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.trim().parse().map_err(|_| {
+        format!(
+            "Unknown timezone '{}' (expected an IANA name such as Asia/Seoul)",
+            name.trim()
+        )
+    })
+}
+
+/// Calendar date of `instant` in `tz`
+pub fn local_date(instant: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    instant.with_timezone(&tz).date_naive()
+}
+
+/// Today's date in `tz`
+pub fn today(tz: Tz) -> NaiveDate {
+    local_date(Utc::now(), tz)
+}
+
+/// UTC instants bounding the local day `date` in `tz`, end exclusive.
+///
+/// Days are not always 24 hours long across DST changes.
+pub fn day_bounds(date: NaiveDate, tz: Tz) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = start_of_day(date, tz);
+    let end = date.succ_opt().map_or(start, |next| start_of_day(next, tz));
+    (start, end)
+}
+
+/// First instant of `date` in `tz`; midnight may be skipped by a DST change.
+fn start_of_day(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    (0..24)
+        .filter_map(|hour| date.and_hms_opt(hour, 0, 0))
+        .find_map(|time| tz.from_local_datetime(&time).earliest())
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_day_bounds() {
+        let (start, end) = day_bounds(date(2026, 3, 1), Tz::Asia__Seoul);
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 2, 28, 15, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2026, 3, 1, 15, 0, 0).unwrap());
+
+        // 23-hour day when clocks spring forward
+        let (start, end) = day_bounds(date(2026, 3, 8), Tz::America__New_York);
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 3, 8, 5, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2026, 3, 9, 4, 0, 0).unwrap());
+
+        // Midnight does not exist on this day in Santiago
+        let (start, _) = day_bounds(date(2026, 9, 6), Tz::America__Santiago);
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 9, 6, 4, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_local_date() {
+        let instant = Utc.with_ymd_and_hms(2026, 3, 1, 2, 0, 0).unwrap();
+        assert_eq!(local_date(instant, Tz::UTC), date(2026, 3, 1));
+        assert_eq!(
+            local_date(instant, Tz::America__Los_Angeles),
+            date(2026, 2, 28)
+        );
+        assert_eq!(local_date(instant, Tz::Asia__Seoul), date(2026, 3, 1));
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone(" Asia/Seoul "), Ok(Tz::Asia__Seoul));
+        assert!(parse_timezone("Mars/Olympus").is_err());
+    }
+}
@@ -176,10 +176,12 @@ impl FetcherPort for RssFetcher {
         let collected_at = Utc::now();
 
         Ok(Article {
+            id: None,
             url: link,
             title,
             content,
             source: SourceType::RSS,
+            feed_url: None,
             published_at,
             collected_at,
         })
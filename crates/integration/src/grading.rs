@@ -0,0 +1,400 @@
+//! Grading of typed quiz answers.
+
+use std::collections::HashSet;
+
+use spread_core::quiz::{Question, QuestionKind};
+use spread_core::{Grade, LlmPort};
+use spread_llm::validation::{is_inflection_of, is_stop_word, tokenize};
+use tracing::warn;
+
+/// Definition scores at or above this count as correct.
+const PASS_SCORE: f64 = 0.6;
+/// Definition scores at or above this count as partially correct.
+const PARTIAL_SCORE: f64 = 0.3;
+
+/// How an answer matched the expected one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchKind {
+    Exact,
+    /// Same word up to case, spacing or punctuation
+    Lemma,
+    /// An inflected form of the word, e.g. "mitigated" for "mitigate"
+    Inflection,
+    /// Within a small edit distance of the word
+    Typo {
+        distance: usize,
+    },
+    /// Picked the right multiple-choice option
+    Choice,
+    /// Definition judged by the LLM
+    Semantic,
+    /// Definition judged by keyword overlap with the reference
+    KeywordOverlap,
+    Incorrect,
+}
+
+/// Outcome of grading one answer.
+#[derive(Debug, Clone)]
+pub struct GradeResult {
+    pub correct: bool,
+    /// 0.0 to 1.0
+    pub score: f64,
+    pub kind: MatchKind,
+    /// Grade to record in the review log
+    pub grade: Grade,
+}
+
+impl GradeResult {
+    fn incorrect() -> Self {
+        Self {
+            correct: false,
+            score: 0.0,
+            kind: MatchKind::Incorrect,
+            grade: Grade::Again,
+        }
+    }
+
+    fn from_score(score: f64, kind: MatchKind) -> Self {
+        let grade = if score >= PASS_SCORE {
+            Grade::Good
+        } else if score >= PARTIAL_SCORE {
+            Grade::Hard
+        } else {
+            Grade::Again
+        };
+        Self {
+            correct: score >= PASS_SCORE,
+            score,
+            kind: if grade == Grade::Again {
+                MatchKind::Incorrect
+            } else {
+                kind
+            },
+            grade,
+        }
+    }
+}
+
+/// Grades typed answers to quiz questions.
+///
+/// Word answers are matched exactly, by lemma, by inflection or within a small
+/// edit distance. Definition answers are judged by the LLM when one is
+/// configured and able to, otherwise by keyword overlap with the reference.
+#[derive(Default)]
+pub struct AnswerGrader {
+    llm: Option<Box<dyn LlmPort>>,
+}
+
+impl AnswerGrader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use an LLM for semantic grading of definitions
+    pub fn with_llm(mut self, llm: Box<dyn LlmPort>) -> Self {
+        self.llm = Some(llm);
+        self
+    }
+
+    /// Grade an answer to a quiz question.
+    pub async fn grade(&self, question: &Question, answer: &str) -> GradeResult {
+        if question.kind.expects_word() {
+            return grade_word(&question.answer, answer);
+        }
+
+        if question.kind == QuestionKind::MultipleChoice {
+            if let Some(index) = choice_index(answer, question.choices.len()) {
+                return if Some(index) == question.answer_index {
+                    GradeResult {
+                        correct: true,
+                        score: 1.0,
+                        kind: MatchKind::Choice,
+                        grade: Grade::Good,
+                    }
+                } else {
+                    GradeResult::incorrect()
+                };
+            }
+        }
+        self.grade_definition(&question.word, &question.answer, answer)
+            .await
+    }
+
+    /// Grade a free-text definition against the reference.
+    pub async fn grade_definition(&self, word: &str, reference: &str, answer: &str) -> GradeResult {
+        if answer.trim().is_empty() {
+            return GradeResult::incorrect();
+        }
+
+        if let Some(llm) = &self.llm {
+            match llm.grade_definition(word, reference, answer).await {
+                Ok(Some(score)) => return GradeResult::from_score(score, MatchKind::Semantic),
+                Ok(None) => {}
+                Err(e) => warn!(error = %e, "Semantic grading failed, using keyword overlap"),
+            }
+        }
+
+        GradeResult::from_score(
+            keyword_overlap(reference, answer),
+            MatchKind::KeywordOverlap,
+        )
+    }
+}
+
+/// Grade a typed word against the expected word.
+pub fn grade_word(expected: &str, answer: &str) -> GradeResult {
+    let answer_trimmed = answer.trim();
+    if answer_trimmed.is_empty() {
+        return GradeResult::incorrect();
+    }
+    if answer_trimmed == expected.trim() {
+        return GradeResult {
+            correct: true,
+            score: 1.0,
+            kind: MatchKind::Exact,
+            grade: Grade::Good,
+        };
+    }
+
+    let expected_norm = normalize(expected);
+    let answer_norm = normalize(answer);
+    if expected_norm == answer_norm {
+        return GradeResult {
+            correct: true,
+            score: 1.0,
+            kind: MatchKind::Lemma,
+            grade: Grade::Good,
+        };
+    }
+
+    if is_inflection_of(&answer_norm, &expected_norm)
+        || is_inflection_of(&expected_norm, &answer_norm)
+    {
+        return GradeResult {
+            correct: true,
+            score: 0.9,
+            kind: MatchKind::Inflection,
+            grade: Grade::Good,
+        };
+    }
+
+    let distance = edit_distance(&expected_norm, &answer_norm);
+    if distance <= typo_allowance(expected_norm.chars().count()) {
+        // Recalled, but not spelled right
+        return GradeResult {
+            correct: true,
+            score: 0.7,
+            kind: MatchKind::Typo { distance },
+            grade: Grade::Hard,
+        };
+    }
+
+    GradeResult::incorrect()
+}
+
+/// Lowercase and keep only letters, digits and single spaces.
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Number of typos tolerated for a word of the given length.
+fn typo_allowance(len: usize) -> usize {
+    match len {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance over characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Parse "B", "b)", or "2" as a choice index.
+fn choice_index(answer: &str, choices: usize) -> Option<usize> {
+    let answer = answer.trim().trim_end_matches([')', '.']);
+    let index = if let Ok(n) = answer.parse::<usize>() {
+        n.checked_sub(1)?
+    } else {
+        let mut chars = answer.chars();
+        let c = chars.next()?.to_ascii_uppercase();
+        if chars.next().is_some() || !c.is_ascii_uppercase() {
+            return None;
+        }
+        (c as u8 - b'A') as usize
+    };
+    (index < choices).then_some(index)
+}
+
+/// Share of the reference's content words present in the answer.
+fn keyword_overlap(reference: &str, answer: &str) -> f64 {
+    let reference = content_stems(reference);
+    if reference.is_empty() {
+        return 0.0;
+    }
+    let answer = content_stems(answer);
+    reference.intersection(&answer).count() as f64 / reference.len() as f64
+}
+
+fn content_stems(text: &str) -> HashSet<String> {
+    tokenize(text)
+        .into_iter()
+        .filter(|t| t.len() > 2 && !is_stop_word(t))
+        .map(|t| stem(&t))
+        .collect()
+}
+
+/// Crude suffix stripping so "lasting"/"lasts"/"lasted" compare equal.
+fn stem(token: &str) -> String {
+    for suffix in ["ing", "ed", "es", "ly", "s"] {
+        if let Some(stem) = token.strip_suffix(suffix) {
+            if stem.len() >= 3 {
+                return stem.to_string();
+            }
+        }
+    }
+    token.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use spread_core::{CoreError, Vocabulary};
+
+    fn question(kind: QuestionKind) -> Question {
+        Question {
+            kind,
+            word: "ephemeral".to_string(),
+            prompt: String::new(),
+            choices: vec![],
+            answer_index: None,
+            answer: "ephemeral".to_string(),
+            context_sentence: String::new(),
+            source_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_grade_word_matches() {
+        assert_eq!(grade_word("mitigate", "mitigate").kind, MatchKind::Exact);
+        assert_eq!(grade_word("mitigate", " Mitigate. ").kind, MatchKind::Lemma);
+        assert_eq!(
+            grade_word("mitigate", "mitigated").kind,
+            MatchKind::Inflection
+        );
+        assert_eq!(
+            grade_word("mitigate", "mitigste").kind,
+            MatchKind::Typo { distance: 1 }
+        );
+        assert_eq!(grade_word("ephemeral", "ephemeral").grade, Grade::Good);
+
+        let typo = grade_word("ephemeral", "ephemerel");
+        assert!(typo.correct);
+        assert_eq!(typo.grade, Grade::Hard);
+
+        // Short words must be spelled exactly
+        assert_eq!(grade_word("curt", "cart").kind, MatchKind::Incorrect);
+        assert_eq!(grade_word("ephemeral", "tenuous").grade, Grade::Again);
+        assert!(!grade_word("ephemeral", "").correct);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_keyword_overlap() {
+        let reference = "Lasting for a very short time";
+        assert_eq!(keyword_overlap(reference, "lasts only a short time"), 1.0);
+        assert!(keyword_overlap(reference, "short") < PASS_SCORE);
+        assert_eq!(keyword_overlap(reference, "something unrelated"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_multiple_choice_by_letter_or_text() {
+        let grader = AnswerGrader::new();
+        let mut q = question(QuestionKind::MultipleChoice);
+        q.choices = vec!["Very weak".to_string(), "Lasting a short time".to_string()];
+        q.answer_index = Some(1);
+        q.answer = "Lasting a short time".to_string();
+
+        assert_eq!(grader.grade(&q, "B").await.kind, MatchKind::Choice);
+        assert_eq!(grader.grade(&q, "2").await.grade, Grade::Good);
+        assert_eq!(grader.grade(&q, "a)").await.grade, Grade::Again);
+        // Free text falls back to definition grading
+        let result = grader.grade(&q, "it lasts a short time").await;
+        assert_eq!(result.kind, MatchKind::KeywordOverlap);
+        assert!(result.correct);
+    }
+
+    #[tokio::test]
+    async fn test_word_questions() {
+        let grader = AnswerGrader::new();
+        for kind in [
+            QuestionKind::Cloze,
+            QuestionKind::Reverse,
+            QuestionKind::Spelling,
+        ] {
+            assert!(grader.grade(&question(kind), "Ephemeral").await.correct);
+        }
+    }
+
+    struct FixedScoreLlm(Option<f64>);
+
+    #[async_trait]
+    impl LlmPort for FixedScoreLlm {
+        async fn extract(&self, _text: &str) -> Result<Vec<Vocabulary>, CoreError> {
+            Ok(vec![])
+        }
+
+        async fn grade_definition(
+            &self,
+            _word: &str,
+            _reference: &str,
+            _answer: &str,
+        ) -> Result<Option<f64>, CoreError> {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_grading_with_fallback() {
+        let grader = AnswerGrader::new().with_llm(Box::new(FixedScoreLlm(Some(0.4))));
+        let result = grader
+            .grade_definition("ephemeral", "Lasting a short time", "fleeting")
+            .await;
+        assert_eq!(result.kind, MatchKind::Semantic);
+        assert_eq!(result.grade, Grade::Hard);
+
+        // Engines that cannot grade fall back to keyword overlap
+        let grader = AnswerGrader::new().with_llm(Box::new(FixedScoreLlm(None)));
+        let result = grader
+            .grade_definition("ephemeral", "Lasting a short time", "fleeting")
+            .await;
+        assert_eq!(result.kind, MatchKind::Incorrect);
+        assert_eq!(result.grade, Grade::Again);
+    }
+}
@@ -1,7 +1,11 @@
 pub mod error;
+pub mod grading;
 pub mod mcp;
 pub mod obsidian;
+pub mod quiz;
 
 pub use error::IntegrationError;
+pub use grading::{AnswerGrader, GradeResult};
 pub use mcp::McpServer;
 pub use obsidian::MarkdownExporter;
+pub use quiz::QuizGenerator;
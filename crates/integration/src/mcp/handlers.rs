@@ -1,31 +1,136 @@
+use chrono::NaiveDate;
 use serde::Deserialize;
 use serde_json::{json, Value};
-use spread_core::model::Vocabulary;
+use spread_core::model::{SearchHit, SourceType, Vocabulary};
 use spread_core::port::StoragePort;
+use spread_core::query::{ReviewStatus, VocabQuery, VocabSort};
+use spread_core::quiz::{Question, QuestionKind};
+use spread_core::timezone::{day_bounds, today, Tz};
 
 use crate::error::IntegrationError;
+use crate::grading::AnswerGrader;
+use crate::quiz::QuizGenerator;
 
 use super::protocol::{TextContent, ToolCallResult};
 
+/// Results per kind (words, articles) when no limit is given
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
 #[derive(Debug, Deserialize)]
 pub struct SearchVocaArgs {
     pub query: String,
+    /// Maximum word and article results each
+    #[serde(default)]
+    pub limit: Option<usize>,
 }
 
 pub async fn search_voca<S: StoragePort>(
     storage: &S,
     args: SearchVocaArgs,
 ) -> Result<ToolCallResult, IntegrationError> {
-    let vocabs = storage.search_vocab(&args.query).await?;
+    let hits = storage
+        .search(&args.query, args.limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+        .await?;
 
-    let text = if vocabs.is_empty() {
+    let text = if hits.is_empty() {
         format!("No vocabulary found matching '{}'", args.query)
     } else {
-        vocabs
+        hits.iter()
+            .map(format_search_hit)
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n")
+    };
+
+    Ok(ToolCallResult {
+        content: vec![TextContent {
+            content_type: "text".to_string(),
+            text,
+        }],
+        is_error: None,
+    })
+}
+
+/// Words per page when no limit is given
+const DEFAULT_LIST_LIMIT: usize = 20;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListVocaArgs {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    /// First local collection date, inclusive (YYYY-MM-DD)
+    pub from: Option<NaiveDate>,
+    /// Last local collection date, inclusive
+    pub to: Option<NaiveDate>,
+    /// Feed URL
+    pub feed: Option<String>,
+    pub source: Option<SourceType>,
+    pub tag: Option<String>,
+    /// Deck name; matches words with any of its tags
+    pub deck: Option<String>,
+    pub status: Option<ReviewStatus>,
+    pub sort: Option<VocabSort>,
+}
+
+impl ListVocaArgs {
+    /// Dates are local days in `tz`
+    fn into_query(self, tz: Tz) -> VocabQuery {
+        let mut query = VocabQuery::new()
+            .with_limit(self.limit.unwrap_or(DEFAULT_LIST_LIMIT))
+            .with_offset(self.offset.unwrap_or(0))
+            .with_sort(self.sort.unwrap_or_default());
+        if let Some(cursor) = self.cursor {
+            query = query.with_cursor(cursor);
+        }
+        if let Some(from) = self.from {
+            query = query.with_collected_from(day_bounds(from, tz).0);
+        }
+        if let Some(to) = self.to {
+            query = query.with_collected_to(day_bounds(to, tz).1);
+        }
+        if let Some(feed) = self.feed {
+            query = query.with_feed(feed);
+        }
+        if let Some(source) = self.source {
+            query = query.with_source(source);
+        }
+        if let Some(tag) = self.tag {
+            query = query.with_tag(tag);
+        }
+        if let Some(deck) = self.deck {
+            query = query.with_deck(deck);
+        }
+        if let Some(status) = self.status {
+            query = query.with_review_status(status);
+        }
+        query
+    }
+}
+
+pub async fn list_voca<S: StoragePort>(
+    storage: &S,
+    args: ListVocaArgs,
+    tz: Tz,
+) -> Result<ToolCallResult, IntegrationError> {
+    let page = storage.query_vocab(&args.into_query(tz)).await?;
+
+    let text = if page.vocabulary.is_empty() {
+        "No vocabulary matches these filters.".to_string()
+    } else {
+        let mut text = page
+            .vocabulary
             .iter()
             .map(format_vocabulary)
             .collect::<Vec<_>>()
-            .join("\n\n---\n\n")
+            .join("\n\n---\n\n");
+        if let Some(cursor) = page.next_cursor {
+            text.push_str(&format!(
+                "\n\n---\n\nMore results: call again with cursor \"{}\"",
+                cursor
+            ));
+        }
+        text
     };
 
     Ok(ToolCallResult {
@@ -37,24 +142,51 @@ pub async fn search_voca<S: StoragePort>(
     })
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct QuizArgs {
+    /// Question type; a random suitable one when omitted
+    #[serde(rename = "type")]
+    pub kind: Option<QuestionKind>,
+    /// Seed for reproducible questions
+    pub seed: Option<u64>,
+}
+
 pub async fn get_random_quiz<S: StoragePort>(
     storage: &S,
+    args: QuizArgs,
 ) -> Result<ToolCallResult, IntegrationError> {
-    let vocab = storage.get_random_vocab().await?;
+    let pool = storage.query_vocab(&VocabQuery::new()).await?.vocabulary;
 
-    let text = match vocab {
-        Some(v) => {
-            let quiz = json!({
+    let mut generator = match args.seed {
+        Some(seed) => QuizGenerator::with_seed(seed),
+        None => QuizGenerator::new(),
+    };
+    let kinds = match args.kind {
+        Some(kind) => vec![kind],
+        None => QuestionKind::ALL.to_vec(),
+    };
+
+    let text = match generator.quiz(&pool, 1, &kinds).into_iter().next() {
+        Some(q) => {
+            let mut quiz = json!({
                 "type": "quiz",
-                "word": v.word,
-                "question": format!("What is the meaning of '{}'?", v.word),
-                "answer": v.definition,
-                "context": v.context_sentence,
-                "source": v.source_url
+                "question_type": q.kind,
+                "word": q.word,
+                "question": q.prompt,
+                "answer": q.answer,
+                "context": q.context_sentence,
+                "source": q.source_url
             });
+            if !q.choices.is_empty() {
+                quiz["choices"] = json!(q.choices);
+                quiz["answer_index"] = json!(q.answer_index);
+            }
             serde_json::to_string_pretty(&quiz)?
         }
-        None => "No vocabulary available for quiz. Please collect some words first.".to_string(),
+        None if pool.is_empty() => {
+            "No vocabulary available for quiz. Please collect some words first.".to_string()
+        }
+        None => "Not enough vocabulary for this question type.".to_string(),
     };
 
     Ok(ToolCallResult {
@@ -66,8 +198,94 @@ pub async fn get_random_quiz<S: StoragePort>(
     })
 }
 
-pub async fn get_daily_words<S: StoragePort>(storage: &S) -> Result<Value, IntegrationError> {
-    let vocabs = storage.get_today_vocab().await?;
+#[derive(Debug, Deserialize)]
+pub struct GradeAnswerArgs {
+    /// The quiz object returned by `get_random_quiz`
+    pub question: QuizQuestion,
+    pub answer: String,
+}
+
+/// A question in the shape `get_random_quiz` returns it.
+#[derive(Debug, Deserialize)]
+pub struct QuizQuestion {
+    pub question_type: QuestionKind,
+    pub word: String,
+    pub question: String,
+    pub answer: String,
+    #[serde(default)]
+    pub choices: Vec<String>,
+    #[serde(default)]
+    pub answer_index: Option<usize>,
+    #[serde(default)]
+    pub context: String,
+    #[serde(default)]
+    pub source: String,
+}
+
+impl From<QuizQuestion> for Question {
+    fn from(quiz: QuizQuestion) -> Self {
+        Question {
+            kind: quiz.question_type,
+            word: quiz.word,
+            prompt: quiz.question,
+            choices: quiz.choices,
+            answer_index: quiz.answer_index,
+            answer: quiz.answer,
+            context_sentence: quiz.context,
+            source_url: quiz.source,
+        }
+    }
+}
+
+/// Grade an answer to a quiz question and record it as a review of the word.
+pub async fn grade_answer<S: StoragePort>(
+    storage: &S,
+    grader: &AnswerGrader,
+    args: GradeAnswerArgs,
+) -> Result<ToolCallResult, IntegrationError> {
+    let question = Question::from(args.question);
+    let Some(word_id) = storage
+        .get_vocab_by_word(&question.word)
+        .await?
+        .and_then(|vocab| vocab.id)
+    else {
+        return Ok(ToolCallResult {
+            content: vec![TextContent {
+                content_type: "text".to_string(),
+                text: format!("'{}' is not in the word bank", question.word),
+            }],
+            is_error: Some(true),
+        });
+    };
+
+    let result = grader.grade(&question, &args.answer).await;
+    let state = storage.record_review(word_id, result.grade).await?;
+    let graded = json!({
+        "word": question.word,
+        "correct": result.correct,
+        "score": result.score,
+        "grade": result.grade,
+        "expected": question.answer,
+        "next_due": state.due_at
+    });
+
+    Ok(ToolCallResult {
+        content: vec![TextContent {
+            content_type: "text".to_string(),
+            text: serde_json::to_string_pretty(&graded)?,
+        }],
+        is_error: None,
+    })
+}
+
+pub async fn get_daily_words<S: StoragePort>(
+    storage: &S,
+    tz: Tz,
+) -> Result<Value, IntegrationError> {
+    let vocabs = storage
+        .query_vocab(&VocabQuery::new().with_collected_on(today(tz), tz))
+        .await?
+        .vocabulary;
 
     let text = if vocabs.is_empty() {
         "No vocabulary collected today.".to_string()
@@ -90,69 +308,93 @@ pub async fn get_daily_words<S: StoragePort>(storage: &S) -> Result<Value, Integ
     }))
 }
 
+fn format_search_hit(hit: &SearchHit) -> String {
+    match hit {
+        SearchHit::Word {
+            vocabulary,
+            snippet,
+            ..
+        } => format!("{}\n\n*Match:* {}", format_vocabulary(vocabulary), snippet),
+        SearchHit::Article {
+            url,
+            title,
+            snippet,
+            ..
+        } => format!("*Article:* [{}]({})\n\n{}", title, url, snippet),
+    }
+}
+
 fn format_vocabulary(vocab: &Vocabulary) -> String {
-    format!(
-        "**{}**\n\n*Definition:* {}\n\n> {}\n\nSource: {}",
-        vocab.word, vocab.definition, vocab.context_sentence, vocab.source_url
-    )
+    let mut headword = format!("**{}**", vocab.word);
+    let details: Vec<&str> = [&vocab.part_of_speech, &vocab.ipa, &vocab.cefr_level]
+        .into_iter()
+        .filter_map(|d| d.as_deref())
+        .collect();
+    if !details.is_empty() {
+        headword.push_str(&format!(" ({})", details.join(", ")));
+    }
+
+    let mut text = format!("{}\n\n*Definition:* {}", headword, vocab.definition);
+    if vocab.occurrences.len() > 1 {
+        for occurrence in &vocab.occurrences {
+            text.push_str(&format!(
+                "\n\n> {}\n> — {}",
+                occurrence.context_sentence, occurrence.source_url
+            ));
+        }
+    } else {
+        text.push_str(&format!("\n\n> {}", vocab.context_sentence));
+    }
+
+    let lists = [
+        ("Synonyms", &vocab.synonyms),
+        ("Antonyms", &vocab.antonyms),
+        ("Collocations", &vocab.collocations),
+    ];
+    for (label, items) in lists {
+        if !items.is_empty() {
+            text.push_str(&format!("\n\n*{}:* {}", label, items.join(", ")));
+        }
+    }
+    if let Some(example) = &vocab.example_sentence {
+        text.push_str(&format!("\n\n*Example:* {}", example));
+    }
+
+    if vocab.occurrences.len() <= 1 {
+        text.push_str(&format!("\n\nSource: {}", vocab.source_url));
+    }
+    text
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use async_trait::async_trait;
-    use spread_core::error::CoreError;
-    use spread_core::model::Article;
-
-    struct MockStorage {
-        vocabs: Vec<Vocabulary>,
-    }
+    use spread_storage::InMemoryStorage;
 
-    #[async_trait]
-    impl StoragePort for MockStorage {
-        async fn exists(&self, _url: &str) -> Result<bool, CoreError> {
-            Ok(false)
-        }
-        async fn save_article(&self, _article: &Article) -> Result<(), CoreError> {
-            Ok(())
-        }
-        async fn save_vocab(&self, _vocab: &Vocabulary) -> Result<(), CoreError> {
-            Ok(())
-        }
-        async fn get_all_vocab(&self) -> Result<Vec<Vocabulary>, CoreError> {
-            Ok(self.vocabs.clone())
-        }
-        async fn search_vocab(&self, query: &str) -> Result<Vec<Vocabulary>, CoreError> {
-            Ok(self
-                .vocabs
-                .iter()
-                .filter(|v| v.word.contains(query) || v.definition.contains(query))
-                .cloned()
-                .collect())
-        }
-        async fn get_today_vocab(&self) -> Result<Vec<Vocabulary>, CoreError> {
-            Ok(self.vocabs.clone())
-        }
-        async fn get_random_vocab(&self) -> Result<Option<Vocabulary>, CoreError> {
-            Ok(self.vocabs.first().cloned())
+    async fn storage_with(vocabs: Vec<Vocabulary>) -> InMemoryStorage {
+        let storage = InMemoryStorage::new();
+        for vocab in &vocabs {
+            storage.save_vocab(vocab).await.unwrap();
         }
+        storage
     }
 
     #[tokio::test]
     async fn test_search_voca() {
-        let storage = MockStorage {
-            vocabs: vec![Vocabulary {
-                word: "serendipity".to_string(),
-                definition: "Finding good things by chance".to_string(),
-                context_sentence: "It was serendipity.".to_string(),
-                source_url: "https://example.com".to_string(),
-            }],
-        };
+        let storage = storage_with(vec![Vocabulary {
+            word: "serendipity".to_string(),
+            definition: "Finding good things by chance".to_string(),
+            context_sentence: "It was serendipity.".to_string(),
+            source_url: "https://example.com".to_string(),
+            ..Default::default()
+        }])
+        .await;
 
         let result = search_voca(
             &storage,
             SearchVocaArgs {
                 query: "serendip".to_string(),
+                limit: None,
             },
         )
         .await
@@ -161,14 +403,131 @@ mod tests {
         assert!(result.content[0].text.contains("serendipity"));
     }
 
+    #[test]
+    fn test_format_vocabulary_lexical_fields() {
+        let vocab = Vocabulary {
+            word: "ephemeral".to_string(),
+            definition: "Lasting for a short time".to_string(),
+            context_sentence: "Fame is ephemeral.".to_string(),
+            source_url: "https://example.com".to_string(),
+            part_of_speech: Some("adjective".to_string()),
+            cefr_level: Some("C2".to_string()),
+            synonyms: vec!["fleeting".to_string()],
+            ..Default::default()
+        };
+
+        let text = format_vocabulary(&vocab);
+        assert!(text.contains("**ephemeral** (adjective, C2)"));
+        assert!(text.contains("*Synonyms:* fleeting"));
+        assert!(!text.contains("Antonyms"));
+    }
+
+    #[test]
+    fn test_format_vocabulary_multiple_occurrences() {
+        use spread_core::model::Occurrence;
+
+        let occurrence = |url: &str, sentence: &str| Occurrence {
+            source_url: url.to_string(),
+            context_sentence: sentence.to_string(),
+            ..Default::default()
+        };
+        let vocab = Vocabulary {
+            word: "ephemeral".to_string(),
+            definition: "Lasting for a short time".to_string(),
+            context_sentence: "Fame is ephemeral.".to_string(),
+            source_url: "https://a.example".to_string(),
+            occurrences: vec![
+                occurrence("https://a.example", "Fame is ephemeral."),
+                occurrence("https://b.example", "Trends are ephemeral."),
+            ],
+            ..Default::default()
+        };
+
+        let text = format_vocabulary(&vocab);
+        assert!(text.contains("> Fame is ephemeral.\n> — https://a.example"));
+        assert!(text.contains("> Trends are ephemeral.\n> — https://b.example"));
+        assert!(!text.contains("Source:"));
+    }
+
+    #[test]
+    fn test_format_article_search_hit() {
+        let hit = SearchHit::Article {
+            article_id: 1,
+            url: "https://example.com/post".to_string(),
+            title: "Heat islands".to_string(),
+            snippet: "Cities **mitigate** urban heat…".to_string(),
+            rank: -1.0,
+        };
+        assert_eq!(
+            format_search_hit(&hit),
+            "*Article:* [Heat islands](https://example.com/post)\n\nCities **mitigate** urban heat…"
+        );
+    }
+
+    #[test]
+    fn test_list_voca_args_to_query() {
+        let args: ListVocaArgs = serde_json::from_value(json!({
+            "from": "2026-03-01",
+            "to": "2026-03-02",
+            "feed": "https://a.example/feed.xml",
+            "source": "RSS",
+            "tag": "toefl",
+            "deck": "TOEFL reading",
+            "status": "new",
+            "sort": "newest",
+            "cursor": "42"
+        }))
+        .unwrap();
+        let query = args.into_query(Tz::Asia__Seoul);
+
+        assert_eq!(query.limit, Some(DEFAULT_LIST_LIMIT));
+        assert_eq!(
+            query.collected_from.unwrap().to_rfc3339(),
+            "2026-02-28T15:00:00+00:00"
+        );
+        // The end date is inclusive
+        assert_eq!(
+            query.collected_to.unwrap().to_rfc3339(),
+            "2026-03-02T15:00:00+00:00"
+        );
+        assert_eq!(query.feed.as_deref(), Some("https://a.example/feed.xml"));
+        assert_eq!(query.source, Some(SourceType::RSS));
+        assert_eq!(query.tag.as_deref(), Some("toefl"));
+        assert_eq!(query.deck.as_deref(), Some("TOEFL reading"));
+        assert_eq!(query.review_status, Some(ReviewStatus::New));
+        assert_eq!(query.sort, VocabSort::Newest);
+        assert_eq!(query.cursor.as_deref(), Some("42"));
+    }
+
+    #[tokio::test]
+    async fn test_list_voca() {
+        let vocab = |word: &str| Vocabulary {
+            word: word.to_string(),
+            definition: "Definition".to_string(),
+            context_sentence: "Sentence.".to_string(),
+            source_url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+        let storage = storage_with(vec![vocab("ephemeral"), vocab("tenuous")]).await;
+
+        let args = ListVocaArgs {
+            limit: Some(1),
+            ..Default::default()
+        };
+        let result = list_voca(&storage, args, Tz::UTC).await.unwrap();
+        assert!(result.content[0].text.contains("**ephemeral**"));
+        assert!(!result.content[0].text.contains("tenuous"));
+    }
+
     #[tokio::test]
     async fn test_search_voca_no_results() {
-        let storage = MockStorage { vocabs: vec![] };
+        let storage = storage_with(vec![]).await;
 
         let result = search_voca(
             &storage,
             SearchVocaArgs {
                 query: "xyz".to_string(),
+                limit: None,
             },
         )
         .await
@@ -179,25 +538,124 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_random_quiz() {
-        let storage = MockStorage {
-            vocabs: vec![Vocabulary {
-                word: "ephemeral".to_string(),
-                definition: "Lasting for a short time".to_string(),
-                context_sentence: "Fame is ephemeral.".to_string(),
-                source_url: "https://example.com".to_string(),
-            }],
-        };
+        let storage = storage_with(vec![Vocabulary {
+            word: "ephemeral".to_string(),
+            definition: "Lasting for a short time".to_string(),
+            context_sentence: "Fame is ephemeral.".to_string(),
+            source_url: "https://example.com".to_string(),
+            ..Default::default()
+        }])
+        .await;
 
-        let result = get_random_quiz(&storage).await.unwrap();
+        let result = get_random_quiz(&storage, QuizArgs::default())
+            .await
+            .unwrap();
         assert!(result.content[0].text.contains("ephemeral"));
         assert!(result.content[0].text.contains("quiz"));
     }
 
+    #[tokio::test]
+    async fn test_get_random_quiz_multiple_choice() {
+        let word = |word: &str, definition: &str| Vocabulary {
+            word: word.to_string(),
+            definition: definition.to_string(),
+            context_sentence: format!("It was {}.", word),
+            source_url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+        let storage = storage_with(vec![
+            word("ephemeral", "Short-lived"),
+            word("tenuous", "Very weak"),
+        ])
+        .await;
+        let args = QuizArgs {
+            kind: Some(QuestionKind::MultipleChoice),
+            seed: Some(1),
+        };
+
+        let result = get_random_quiz(&storage, args).await.unwrap();
+        let quiz: Value = serde_json::from_str(&result.content[0].text).unwrap();
+        assert_eq!(quiz["question_type"], "multiple_choice");
+        assert_eq!(quiz["choices"].as_array().unwrap().len(), 2);
+        let index = quiz["answer_index"].as_u64().unwrap() as usize;
+        assert_eq!(quiz["choices"][index], quiz["answer"]);
+
+        // A single word cannot have distractors
+        let storage = storage_with(vec![word("ephemeral", "Short-lived")]).await;
+        let args = QuizArgs {
+            kind: Some(QuestionKind::MultipleChoice),
+            seed: None,
+        };
+        let result = get_random_quiz(&storage, args).await.unwrap();
+        assert!(result.content[0].text.contains("Not enough vocabulary"));
+    }
+
     #[tokio::test]
     async fn test_get_random_quiz_empty() {
-        let storage = MockStorage { vocabs: vec![] };
+        let storage = storage_with(vec![]).await;
 
-        let result = get_random_quiz(&storage).await.unwrap();
+        let result = get_random_quiz(&storage, QuizArgs::default())
+            .await
+            .unwrap();
         assert!(result.content[0].text.contains("No vocabulary available"));
     }
+
+    #[tokio::test]
+    async fn test_grade_answer_records_a_review() {
+        let storage = storage_with(vec![Vocabulary {
+            word: "ephemeral".to_string(),
+            definition: "Short-lived".to_string(),
+            context_sentence: "Fame is ephemeral.".to_string(),
+            source_url: "https://example.com".to_string(),
+            ..Default::default()
+        }])
+        .await;
+        let args = QuizArgs {
+            kind: Some(QuestionKind::Reverse),
+            seed: Some(1),
+        };
+        let quiz = get_random_quiz(&storage, args).await.unwrap();
+        let quiz: Value = serde_json::from_str(&quiz.content[0].text).unwrap();
+
+        let args = GradeAnswerArgs {
+            question: serde_json::from_value(quiz).unwrap(),
+            answer: "Ephemeral".to_string(),
+        };
+        let result = grade_answer(&storage, &AnswerGrader::new(), args)
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, None);
+        let graded: Value = serde_json::from_str(&result.content[0].text).unwrap();
+        assert_eq!(graded["correct"], true);
+        assert_eq!(graded["grade"], "Good");
+        assert!(graded["next_due"].is_string());
+
+        // The word has been reviewed, so it is no longer new
+        let new = storage
+            .get_new_vocab(10, &Default::default())
+            .await
+            .unwrap();
+        assert!(new.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_grade_answer_unknown_word() {
+        let storage = storage_with(vec![]).await;
+        let args: GradeAnswerArgs = serde_json::from_value(json!({
+            "question": {
+                "question_type": "reverse",
+                "word": "ephemeral",
+                "question": "Which word means: Short-lived",
+                "answer": "ephemeral"
+            },
+            "answer": "ephemeral"
+        }))
+        .unwrap();
+
+        let result = grade_answer(&storage, &AnswerGrader::new(), args)
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert!(result.content[0].text.contains("not in the word bank"));
+    }
 }
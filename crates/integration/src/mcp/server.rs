@@ -1,11 +1,17 @@
 use serde_json::{json, Value};
 use spread_core::port::StoragePort;
+use spread_core::timezone::Tz;
 use std::io::{BufRead, Write};
 use tracing::{debug, error, info};
 
 use crate::error::IntegrationError;
 
-use super::handlers::{get_daily_words, get_random_quiz, search_voca, SearchVocaArgs};
+use crate::grading::AnswerGrader;
+
+use super::handlers::{
+    get_daily_words, get_random_quiz, grade_answer, list_voca, search_voca, GradeAnswerArgs,
+    ListVocaArgs, QuizArgs, SearchVocaArgs,
+};
 use super::protocol::{
     InitializeResult, JsonRpcRequest, JsonRpcResponse, Resource, ResourcesCapability,
     ResourcesListResult, ServerCapabilities, ServerInfo, Tool, ToolCallParams, ToolsCapability,
@@ -18,11 +24,29 @@ const SERVER_VERSION: &str = "0.1.0";
 
 pub struct McpServer<S: StoragePort> {
     storage: S,
+    timezone: Tz,
+    grader: AnswerGrader,
 }
 
 impl<S: StoragePort> McpServer<S> {
     pub fn new(storage: S) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            timezone: Tz::UTC,
+            grader: AnswerGrader::new(),
+        }
+    }
+
+    /// Grader for answers submitted with `grade_answer`
+    pub fn with_grader(mut self, grader: AnswerGrader) -> Self {
+        self.grader = grader;
+        self
+    }
+
+    /// Timezone for "today" and the dates passed to `list_voca`
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
     }
 
     pub async fn run(&self) -> Result<(), IntegrationError> {
@@ -109,24 +133,76 @@ impl<S: StoragePort> McpServer<S> {
         let tools = vec![
             Tool {
                 name: "search_voca".to_string(),
-                description: "Search vocabulary in my word bank by word or definition".to_string(),
+                description: "Full-text search of my word bank (words, definitions, context sentences) and collected articles".to_string(),
                 input_schema: json!({
                     "type": "object",
                     "properties": {
                         "query": {
                             "type": "string",
-                            "description": "Search query for word or definition"
+                            "description": "Terms match as prefixes; wrap text in double quotes for a phrase"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum word and article results each (default 10)"
                         }
                     },
                     "required": ["query"]
                 }),
             },
+            Tool {
+                name: "list_voca".to_string(),
+                description: "List vocabulary with filters, sorting and pagination".to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "limit": { "type": "integer", "description": "Words per page (default 20)" },
+                        "offset": { "type": "integer", "description": "Words to skip" },
+                        "cursor": { "type": "string", "description": "Cursor returned by the previous page" },
+                        "from": { "type": "string", "format": "date", "description": "First local collection date (YYYY-MM-DD)" },
+                        "to": { "type": "string", "format": "date", "description": "Last local collection date (YYYY-MM-DD)" },
+                        "feed": { "type": "string", "description": "Feed URL the words came from" },
+                        "source": { "type": "string", "enum": ["RSS", "Manual", "Youtube"] },
+                        "tag": { "type": "string" },
+                        "deck": { "type": "string", "description": "Deck name; matches words with any of its tags" },
+                        "status": { "type": "string", "enum": ["new", "due", "learning", "mastered"] },
+                        "sort": { "type": "string", "enum": ["oldest", "newest", "alphabetical"] }
+                    }
+                }),
+            },
             Tool {
                 name: "get_random_quiz".to_string(),
                 description: "Get a random vocabulary quiz question".to_string(),
                 input_schema: json!({
                     "type": "object",
-                    "properties": {}
+                    "properties": {
+                        "type": {
+                            "type": "string",
+                            "enum": ["cloze", "multiple_choice", "reverse", "spelling"],
+                            "description": "Question type (random if omitted)"
+                        },
+                        "seed": {
+                            "type": "integer",
+                            "description": "Seed for a reproducible question"
+                        }
+                    }
+                }),
+            },
+            Tool {
+                name: "grade_answer".to_string(),
+                description: "Grade my answer to a quiz question and record it in my spaced-repetition reviews".to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "question": {
+                            "type": "object",
+                            "description": "The quiz object returned by get_random_quiz, unchanged"
+                        },
+                        "answer": {
+                            "type": "string",
+                            "description": "The learner's answer; a letter or the text for multiple choice"
+                        }
+                    },
+                    "required": ["question", "answer"]
                 }),
             },
         ];
@@ -179,12 +255,82 @@ impl<S: StoragePort> McpServer<S> {
                     }
                 }
             }
-            "get_random_quiz" => match get_random_quiz(&self.storage).await {
-                Ok(result) => JsonRpcResponse::success(id, serde_json::to_value(result).unwrap()),
-                Err(e) => {
-                    JsonRpcResponse::error(id, INTERNAL_ERROR, &format!("Quiz failed: {}", e))
+            "list_voca" => {
+                let args: ListVocaArgs = match params.arguments {
+                    Some(a) => match serde_json::from_value(a) {
+                        Ok(args) => args,
+                        Err(e) => {
+                            return JsonRpcResponse::error(
+                                id,
+                                INVALID_PARAMS,
+                                &format!("Invalid arguments: {}", e),
+                            );
+                        }
+                    },
+                    None => ListVocaArgs::default(),
+                };
+
+                match list_voca(&self.storage, args, self.timezone).await {
+                    Ok(result) => {
+                        JsonRpcResponse::success(id, serde_json::to_value(result).unwrap())
+                    }
+                    Err(e) => {
+                        JsonRpcResponse::error(id, INTERNAL_ERROR, &format!("List failed: {}", e))
+                    }
                 }
-            },
+            }
+            "get_random_quiz" => {
+                let args: QuizArgs = match params.arguments {
+                    Some(a) => match serde_json::from_value(a) {
+                        Ok(args) => args,
+                        Err(e) => {
+                            return JsonRpcResponse::error(
+                                id,
+                                INVALID_PARAMS,
+                                &format!("Invalid arguments: {}", e),
+                            );
+                        }
+                    },
+                    None => QuizArgs::default(),
+                };
+
+                match get_random_quiz(&self.storage, args).await {
+                    Ok(result) => {
+                        JsonRpcResponse::success(id, serde_json::to_value(result).unwrap())
+                    }
+                    Err(e) => {
+                        JsonRpcResponse::error(id, INTERNAL_ERROR, &format!("Quiz failed: {}", e))
+                    }
+                }
+            }
+            "grade_answer" => {
+                let args: GradeAnswerArgs = match params.arguments {
+                    Some(a) => match serde_json::from_value(a) {
+                        Ok(args) => args,
+                        Err(e) => {
+                            return JsonRpcResponse::error(
+                                id,
+                                INVALID_PARAMS,
+                                &format!("Invalid arguments: {}", e),
+                            );
+                        }
+                    },
+                    None => {
+                        return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing arguments");
+                    }
+                };
+
+                match grade_answer(&self.storage, &self.grader, args).await {
+                    Ok(result) => {
+                        JsonRpcResponse::success(id, serde_json::to_value(result).unwrap())
+                    }
+                    Err(e) => JsonRpcResponse::error(
+                        id,
+                        INTERNAL_ERROR,
+                        &format!("Grading failed: {}", e),
+                    ),
+                }
+            }
             tool => {
                 JsonRpcResponse::error(id, METHOD_NOT_FOUND, &format!("Unknown tool: {}", tool))
             }
@@ -221,7 +367,7 @@ impl<S: StoragePort> McpServer<S> {
         };
 
         match uri.as_str() {
-            "voca://daily-words" => match get_daily_words(&self.storage).await {
+            "voca://daily-words" => match get_daily_words(&self.storage, self.timezone).await {
                 Ok(result) => JsonRpcResponse::success(id, result),
                 Err(e) => {
                     JsonRpcResponse::error(id, INTERNAL_ERROR, &format!("Failed to read: {}", e))
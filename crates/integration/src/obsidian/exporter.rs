@@ -1,28 +1,49 @@
 use std::path::{Path, PathBuf};
 
-use chrono::Local;
+use chrono::NaiveDate;
 use spread_core::model::Vocabulary;
+use spread_core::timezone::{self, Tz};
 use tera::{Context, Tera};
 use tracing::info;
 
 use crate::error::IntegrationError;
 
+/// Tag on every exported note, ahead of the word's own tags
+const BASE_TAG: &str = "voca";
+
 const VOCABULARY_TEMPLATE: &str = r#"---
-tag: #toefl #voca
+tags: [{{ tags | join(sep=", ") }}]
 date: {{ today }}
 source: {{ article_url }}
----
+{% if id %}id: {{ id }}
+{% endif %}{% if cefr_level %}cefr: {{ cefr_level }}
+{% endif %}---
 # {{ word }}
-**Definition:** {{ definition }}
-
-> {{ context_sentence }}
+{% if part_of_speech or ipa %}*{% if part_of_speech %}{{ part_of_speech }}{% endif %}{% if part_of_speech and ipa %} · {% endif %}{% if ipa %}{{ ipa }}{% endif %}*
+{% endif %}**Definition:** {{ definition }}
 
+{% if occurrences | length > 1 %}## Contexts ({{ occurrences | length }})
+{% for occurrence in occurrences %}
+> {{ occurrence.context_sentence }}
+> — [source]({{ occurrence.source_url }})
+{% endfor %}{% else %}> {{ context_sentence }}
+{% endif %}{% if synonyms %}
+**Synonyms:** {{ synonyms | join(sep=", ") }}
+{% endif %}{% if antonyms %}
+**Antonyms:** {{ antonyms | join(sep=", ") }}
+{% endif %}{% if collocations %}
+**Collocations:** {{ collocations | join(sep=", ") }}
+{% endif %}{% if example_sentence %}
+**Example:** {{ example_sentence }}
+{% endif %}
 [YouGlish로 발음 듣기](https://youglish.com/pronounce/{{ word }}/english?)
 "#;
 
 pub struct MarkdownExporter {
     output_path: PathBuf,
     tera: Tera,
+    timezone: Tz,
+    date: Option<NaiveDate>,
 }
 
 impl MarkdownExporter {
@@ -33,16 +54,50 @@ impl MarkdownExporter {
         Ok(Self {
             output_path: obsidian_path.to_path_buf(),
             tera,
+            timezone: Tz::UTC,
+            date: None,
         })
     }
 
+    /// Timezone that decides today's date in the frontmatter
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Write this date into the frontmatter instead of today's
+    pub fn with_date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
     pub fn export(&self, vocab: &Vocabulary) -> Result<PathBuf, IntegrationError> {
         let mut context = Context::new();
-        context.insert("today", &Local::now().format("%Y-%m-%d").to_string());
+        let date = self.date.unwrap_or_else(|| timezone::today(self.timezone));
+        context.insert("today", &date.format("%Y-%m-%d").to_string());
         context.insert("article_url", &vocab.source_url);
+        context.insert("id", &vocab.id);
+        let tags: Vec<&str> = std::iter::once(BASE_TAG)
+            .chain(
+                vocab
+                    .tags
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|tag| *tag != BASE_TAG),
+            )
+            .collect();
+        context.insert("tags", &tags);
         context.insert("word", &vocab.word);
         context.insert("definition", &vocab.definition);
         context.insert("context_sentence", &vocab.context_sentence);
+        context.insert("part_of_speech", &vocab.part_of_speech);
+        context.insert("ipa", &vocab.ipa);
+        context.insert("cefr_level", &vocab.cefr_level);
+        context.insert("synonyms", &vocab.synonyms);
+        context.insert("antonyms", &vocab.antonyms);
+        context.insert("collocations", &vocab.collocations);
+        context.insert("example_sentence", &vocab.example_sentence);
+        context.insert("occurrences", &vocab.occurrences);
 
         let content = self.tera.render("vocabulary.md", &context)?;
 
@@ -88,6 +143,7 @@ mod tests {
             definition: "The occurrence of events by chance in a happy way".to_string(),
             context_sentence: "It was pure serendipity that we met.".to_string(),
             source_url: "https://example.com/article".to_string(),
+            ..Default::default()
         };
 
         let path = exporter.export(&vocab).unwrap();
@@ -99,7 +155,100 @@ mod tests {
         assert!(content.contains("**Definition:** The occurrence of events"));
         assert!(content.contains("> It was pure serendipity"));
         assert!(content.contains("youglish.com/pronounce/serendipity"));
-        assert!(content.contains("tag: #toefl #voca"));
+        assert!(content.contains("tags: [voca]\n"));
+        assert!(content.contains(&format!("date: {}", timezone::today(Tz::UTC))));
+        assert!(!content.contains("**Synonyms:**"));
+        assert!(!content.contains("cefr:"));
+        assert!(!content.contains("id:"));
+    }
+
+    #[test]
+    fn test_export_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let exporter = MarkdownExporter::new(temp_dir.path())
+            .unwrap()
+            .with_timezone(Tz::Asia__Seoul)
+            .with_date(date);
+
+        let vocab = Vocabulary {
+            id: Some(7),
+            word: "lucid".to_string(),
+            tags: vec!["rust-blog".to_string(), "voca".to_string()],
+            ..Default::default()
+        };
+        let path = exporter.export(&vocab).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("tags: [voca, rust-blog]\n"));
+        assert!(content.contains("date: 2026-03-01\n"));
+        assert!(content.contains("id: 7\n"));
+    }
+
+    #[test]
+    fn test_export_lexical_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let exporter = MarkdownExporter::new(temp_dir.path()).unwrap();
+
+        let vocab = Vocabulary {
+            word: "ubiquitous".to_string(),
+            definition: "Present everywhere".to_string(),
+            context_sentence: "Smartphones are ubiquitous.".to_string(),
+            source_url: "https://example.com".to_string(),
+            part_of_speech: Some("adjective".to_string()),
+            ipa: Some("/juːˈbɪkwɪtəs/".to_string()),
+            cefr_level: Some("C1".to_string()),
+            synonyms: vec!["omnipresent".to_string(), "pervasive".to_string()],
+            antonyms: vec![],
+            collocations: vec!["ubiquitous presence".to_string()],
+            example_sentence: Some("Coffee shops are ubiquitous here.".to_string()),
+            ..Default::default()
+        };
+
+        let path = exporter.export(&vocab).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("cefr: C1"));
+        assert!(content.contains("*adjective · /juːˈbɪkwɪtəs/*"));
+        assert!(content.contains("**Synonyms:** omnipresent, pervasive"));
+        assert!(!content.contains("**Antonyms:**"));
+        assert!(content.contains("**Collocations:** ubiquitous presence"));
+        assert!(content.contains("**Example:** Coffee shops are ubiquitous here."));
+    }
+
+    #[test]
+    fn test_export_multiple_occurrences() {
+        use spread_core::model::Occurrence;
+
+        let temp_dir = TempDir::new().unwrap();
+        let exporter = MarkdownExporter::new(temp_dir.path()).unwrap();
+
+        let occurrences = vec![
+            Occurrence {
+                source_url: "https://a.example".to_string(),
+                context_sentence: "Fame is ephemeral.".to_string(),
+                definition: "Short-lived".to_string(),
+                ..Default::default()
+            },
+            Occurrence {
+                source_url: "https://b.example".to_string(),
+                context_sentence: "Trends are ephemeral.".to_string(),
+                definition: "Brief".to_string(),
+                ..Default::default()
+            },
+        ];
+        let vocab = Vocabulary {
+            word: "ephemeral".to_string(),
+            definition: "Short-lived".to_string(),
+            context_sentence: "Fame is ephemeral.".to_string(),
+            source_url: "https://a.example".to_string(),
+            occurrences,
+            ..Default::default()
+        };
+
+        let path = exporter.export(&vocab).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("## Contexts (2)"));
+        assert!(content.contains("> Fame is ephemeral.\n> — [source](https://a.example)"));
+        assert!(content.contains("> Trends are ephemeral.\n> — [source](https://b.example)"));
     }
 
     #[test]
@@ -113,12 +262,14 @@ mod tests {
                 definition: "Lasting for a very short time".to_string(),
                 context_sentence: "Fame is ephemeral.".to_string(),
                 source_url: "https://example.com".to_string(),
+                ..Default::default()
             },
             Vocabulary {
                 word: "ubiquitous".to_string(),
                 definition: "Present everywhere".to_string(),
                 context_sentence: "Smartphones are ubiquitous.".to_string(),
                 source_url: "https://example.com".to_string(),
+                ..Default::default()
             },
         ];
 
@@ -138,6 +289,7 @@ mod tests {
             definition: "Test".to_string(),
             context_sentence: "Test.".to_string(),
             source_url: "https://example.com".to_string(),
+            ..Default::default()
         };
 
         let path = exporter.export(&vocab).unwrap();
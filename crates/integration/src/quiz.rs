@@ -0,0 +1,395 @@
+//! Quiz generation shared by the CLI, MCP server and Telegram notifier.
+
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use spread_core::quiz::{Question, QuestionKind};
+use spread_core::Vocabulary;
+use spread_llm::validation::is_inflection_of;
+
+const MASK: &str = "_____";
+
+/// Number of options in a multiple-choice question, including the answer.
+const DEFAULT_CHOICES: usize = 4;
+
+/// Builds quiz questions from stored vocabulary.
+///
+/// Output is deterministic for a given seed and input order.
+pub struct QuizGenerator {
+    rng: StdRng,
+    choices: usize,
+}
+
+impl QuizGenerator {
+    pub fn new() -> Self {
+        Self::from_rng(StdRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(rng: StdRng) -> Self {
+        Self {
+            rng,
+            choices: DEFAULT_CHOICES,
+        }
+    }
+
+    /// Set the number of options in multiple-choice questions (at least 2)
+    pub fn with_choices(mut self, choices: usize) -> Self {
+        self.choices = choices.max(2);
+        self
+    }
+
+    /// Build a question of the given kind about `target`.
+    ///
+    /// `pool` supplies multiple-choice distractors. Returns `None` when the word
+    /// cannot be asked this way, e.g. a cloze whose sentence does not contain it.
+    pub fn question(
+        &mut self,
+        target: &Vocabulary,
+        pool: &[Vocabulary],
+        kind: QuestionKind,
+    ) -> Option<Question> {
+        let mut question = Question {
+            kind,
+            word: target.word.clone(),
+            prompt: String::new(),
+            choices: Vec::new(),
+            answer_index: None,
+            answer: target.word.clone(),
+            context_sentence: target.context_sentence.clone(),
+            source_url: target.source_url.clone(),
+        };
+
+        match kind {
+            QuestionKind::Cloze => {
+                let masked = mask_word(&target.context_sentence, &target.word);
+                if masked == target.context_sentence {
+                    return None;
+                }
+                question.prompt = format!("Fill in the blank: {}", masked);
+            }
+            QuestionKind::MultipleChoice => {
+                let distractors = self.distractors(target, pool);
+                if distractors.is_empty() {
+                    return None;
+                }
+                let mut choices = distractors;
+                choices.push(target.definition.clone());
+                choices.shuffle(&mut self.rng);
+
+                question.prompt = format!("What does '{}' mean?", target.word);
+                question.answer_index = choices.iter().position(|c| *c == target.definition);
+                question.answer = target.definition.clone();
+                question.choices = choices;
+            }
+            QuestionKind::Reverse => {
+                question.prompt = format!("Which word means: {}", target.definition);
+            }
+            QuestionKind::Spelling => {
+                question.prompt = format!(
+                    "Spell the word that means: {} ({})",
+                    target.definition,
+                    spelling_hint(&target.word)
+                );
+            }
+        }
+
+        Some(question)
+    }
+
+    /// Build a question of a random kind among `kinds` that suits the word.
+    pub fn random_question(
+        &mut self,
+        target: &Vocabulary,
+        pool: &[Vocabulary],
+        kinds: &[QuestionKind],
+    ) -> Option<Question> {
+        let mut kinds = kinds.to_vec();
+        kinds.shuffle(&mut self.rng);
+        kinds
+            .into_iter()
+            .find_map(|kind| self.question(target, pool, kind))
+    }
+
+    /// Build up to `count` questions about distinct random words from `pool`.
+    pub fn quiz(
+        &mut self,
+        pool: &[Vocabulary],
+        count: usize,
+        kinds: &[QuestionKind],
+    ) -> Vec<Question> {
+        self.quiz_for(pool, pool, count, kinds)
+    }
+
+    /// Like [`quiz`](Self::quiz), but only asking about `targets` while drawing
+    /// distractors from the whole `pool`.
+    pub fn quiz_for(
+        &mut self,
+        targets: &[Vocabulary],
+        pool: &[Vocabulary],
+        count: usize,
+        kinds: &[QuestionKind],
+    ) -> Vec<Question> {
+        let mut order: Vec<usize> = (0..targets.len()).collect();
+        order.shuffle(&mut self.rng);
+
+        order
+            .into_iter()
+            .filter_map(|i| self.random_question(&targets[i], pool, kinds))
+            .take(count)
+            .collect()
+    }
+
+    /// Definitions of other words, preferring ones with the same part of speech.
+    fn distractors(&mut self, target: &Vocabulary, pool: &[Vocabulary]) -> Vec<String> {
+        let target_word = target.word.to_lowercase();
+        let mut seen = HashSet::from([target.definition.to_lowercase()]);
+        let mut same_pos = Vec::new();
+        let mut other_pos = Vec::new();
+
+        for vocab in pool {
+            if vocab.word.to_lowercase() == target_word || vocab.definition.trim().is_empty() {
+                continue;
+            }
+            if !seen.insert(vocab.definition.to_lowercase()) {
+                continue;
+            }
+            let matches_pos = match (&target.part_of_speech, &vocab.part_of_speech) {
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                _ => false,
+            };
+            if matches_pos {
+                same_pos.push(vocab.definition.clone());
+            } else {
+                other_pos.push(vocab.definition.clone());
+            }
+        }
+
+        let wanted = self.choices - 1;
+        same_pos.shuffle(&mut self.rng);
+        other_pos.shuffle(&mut self.rng);
+        same_pos.into_iter().chain(other_pos).take(wanted).collect()
+    }
+
+    /// Random seed for callers that want to report the seed they used
+    pub fn random_seed() -> u64 {
+        rand::thread_rng().gen()
+    }
+}
+
+impl Default for QuizGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Replace the word and its inflections in a sentence with a blank.
+pub fn mask_word(sentence: &str, word: &str) -> String {
+    let lemma = word.trim().to_lowercase();
+    if lemma.contains(' ') {
+        return mask_phrase(sentence, &lemma);
+    }
+
+    let mut masked = String::with_capacity(sentence.len());
+    let mut token = String::new();
+    let flush = |token: &mut String, masked: &mut String| {
+        if !token.is_empty() {
+            if is_inflection_of(&token.to_lowercase(), &lemma) {
+                masked.push_str(MASK);
+            } else {
+                masked.push_str(token);
+            }
+            token.clear();
+        }
+    };
+
+    for c in sentence.chars() {
+        if c.is_alphanumeric() || c == '-' {
+            token.push(c);
+        } else {
+            flush(&mut token, &mut masked);
+            masked.push(c);
+        }
+    }
+    flush(&mut token, &mut masked);
+    masked
+}
+
+/// Case-insensitive replacement for multi-word expressions.
+fn mask_phrase(sentence: &str, phrase: &str) -> String {
+    let lower = sentence.to_lowercase();
+    // Lowercasing can change byte lengths outside ASCII; leave such sentences alone
+    if lower.len() != sentence.len() {
+        return sentence.to_string();
+    }
+
+    let mut masked = String::with_capacity(sentence.len());
+    let mut rest = 0;
+    for (start, _) in lower.match_indices(phrase) {
+        masked.push_str(&sentence[rest..start]);
+        masked.push_str(MASK);
+        rest = start + phrase.len();
+    }
+    masked.push_str(&sentence[rest..]);
+    masked
+}
+
+/// First and last letters with the rest blanked, e.g. `e _ _ _ _ _ _ _ l`.
+fn spelling_hint(word: &str) -> String {
+    let chars: Vec<char> = word.chars().collect();
+    let last = chars.len().saturating_sub(1);
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            if i == 0 || i == last || !c.is_alphabetic() {
+                c.to_string()
+            } else {
+                "_".to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab(word: &str, pos: &str, definition: &str, sentence: &str) -> Vocabulary {
+        Vocabulary {
+            word: word.to_string(),
+            definition: definition.to_string(),
+            context_sentence: sentence.to_string(),
+            source_url: "https://example.com".to_string(),
+            part_of_speech: Some(pos.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn pool() -> Vec<Vocabulary> {
+        vec![
+            vocab(
+                "ephemeral",
+                "adjective",
+                "Short-lived",
+                "Fame is ephemeral.",
+            ),
+            vocab("tenuous", "adjective", "Very weak", "A tenuous link."),
+            vocab("lucid", "adjective", "Clearly expressed", "A lucid essay."),
+            vocab("candid", "adjective", "Frank", "A candid reply."),
+            vocab(
+                "mitigate",
+                "verb",
+                "To lessen",
+                "Engineers mitigated the risk.",
+            ),
+            vocab("curtail", "verb", "To reduce", "They curtailed spending."),
+        ]
+    }
+
+    #[test]
+    fn test_mask_word() {
+        assert_eq!(
+            mask_word("Fame is Ephemeral, truly.", "ephemeral"),
+            "Fame is _____, truly."
+        );
+        assert_eq!(
+            mask_word("Engineers mitigated the risk.", "mitigate"),
+            "Engineers _____ the risk."
+        );
+        assert_eq!(
+            mask_word("They took it into account.", "take into account"),
+            "They took it into account."
+        );
+        assert_eq!(
+            mask_word("Always Take Into Account the cost.", "take into account"),
+            "Always _____ the cost."
+        );
+    }
+
+    #[test]
+    fn test_cloze_question() {
+        let pool = pool();
+        let mut generator = QuizGenerator::with_seed(1);
+
+        let question = generator
+            .question(&pool[4], &pool, QuestionKind::Cloze)
+            .unwrap();
+        assert_eq!(
+            question.prompt,
+            "Fill in the blank: Engineers _____ the risk."
+        );
+        assert_eq!(question.answer, "mitigate");
+
+        // The sentence must contain the word
+        let unrelated = vocab("salient", "adjective", "Noticeable", "A key point.");
+        assert!(generator
+            .question(&unrelated, &pool, QuestionKind::Cloze)
+            .is_none());
+    }
+
+    #[test]
+    fn test_multiple_choice_prefers_same_part_of_speech() {
+        let pool = pool();
+        let mut generator = QuizGenerator::with_seed(7);
+
+        let question = generator
+            .question(&pool[0], &pool, QuestionKind::MultipleChoice)
+            .unwrap();
+        assert_eq!(question.choices.len(), 4);
+        let index = question.answer_index.unwrap();
+        assert_eq!(question.choices[index], "Short-lived");
+        // Three other adjectives are available, so no verb definitions
+        assert!(!question.choices.contains(&"To lessen".to_string()));
+        assert!(!question.choices.contains(&"To reduce".to_string()));
+
+        // Falls back to other parts of speech when needed
+        let question = generator
+            .question(&pool[4], &pool, QuestionKind::MultipleChoice)
+            .unwrap();
+        assert_eq!(question.choices.len(), 4);
+        assert!(question.choices.contains(&"To reduce".to_string()));
+
+        // No other words, no question
+        assert!(generator
+            .question(&pool[0], &pool[..1], QuestionKind::MultipleChoice)
+            .is_none());
+    }
+
+    #[test]
+    fn test_reverse_and_spelling_questions() {
+        let pool = pool();
+        let mut generator = QuizGenerator::with_seed(3);
+
+        let reverse = generator
+            .question(&pool[1], &pool, QuestionKind::Reverse)
+            .unwrap();
+        assert_eq!(reverse.prompt, "Which word means: Very weak");
+        assert_eq!(reverse.answer, "tenuous");
+
+        let spelling = generator
+            .question(&pool[1], &pool, QuestionKind::Spelling)
+            .unwrap();
+        assert!(spelling.prompt.contains("(t _ _ _ _ _ s)"));
+        assert_eq!(spelling.answer, "tenuous");
+    }
+
+    #[test]
+    fn test_quiz_is_deterministic_for_seed() {
+        let pool = pool();
+        let first = QuizGenerator::with_seed(42).quiz(&pool, 5, &QuestionKind::ALL);
+        let second = QuizGenerator::with_seed(42).quiz(&pool, 5, &QuestionKind::ALL);
+
+        assert_eq!(first.len(), 5);
+        assert_eq!(first, second);
+
+        let words: HashSet<&str> = first.iter().map(|q| q.word.as_str()).collect();
+        assert_eq!(words.len(), 5);
+    }
+}
@@ -0,0 +1,49 @@
+#!/usr/bin/env python3
+"""Fill the frequency column of wordlist.tsv from wordfreq.
+
+    pip install wordfreq==3.1.1
+    python3 crates/llm/data/build_wordlist.py
+
+Adds (or refreshes) a third column holding the Zipf frequency that
+wordfreq (https://github.com/rspeer/wordfreq) gives each lemma: log10 of its
+uses per billion words, blended from SUBTLEX, Wikipedia, OpenSubtitles and
+other corpora. The offline extractor puts rarer words first within a CEFR
+level. wordfreq's data is licensed CC BY-SA 4.0, so a list with the column
+filled in must keep the attribution in its header.
+"""
+
+from pathlib import Path
+
+from wordfreq import zipf_frequency
+
+WORD_LIST = Path(__file__).with_name("wordlist.tsv")
+
+HEADER = """\
+# Embedded word list for the offline frequency extractor.
+# Columns: lemma, CEFR level (B2, C1 or C2), Zipf frequency.
+# Levels are editorial estimates, not taken from a published CEFR list;
+# common words below B2 are intentionally omitted.
+# Zipf frequencies come from wordfreq {version} (Robyn Speer,
+# https://github.com/rspeer/wordfreq), licensed CC BY-SA 4.0; lemmas wordfreq
+# does not know have no frequency.
+"""
+
+
+def main():
+    from importlib.metadata import version
+
+    rows = []
+    for line in WORD_LIST.read_text(encoding="utf-8").splitlines():
+        if line.startswith("#") or not line.strip():
+            continue
+        lemma, level = line.split("\t")[:2]
+        zipf = zipf_frequency(lemma, "en")
+        rows.append((lemma, level, f"{zipf:.2f}" if zipf > 0 else ""))
+
+    lines = [HEADER.format(version=version("wordfreq"))]
+    lines += ["\t".join(row).rstrip("\t") + "\n" for row in rows]
+    WORD_LIST.write_text("".join(lines), encoding="utf-8")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,410 @@
+//! Offline vocabulary extraction based on an embedded CEFR word list.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use spread_core::{CoreError, LlmPort, Vocabulary};
+
+use crate::validation::{split_sentences, tokenize};
+
+/// Embedded word list: `lemma<TAB>cefr[<TAB>zipf]`, `#` lines are comments.
+/// See `data/build_wordlist.py` for the optional frequency column.
+const WORD_LIST: &str = include_str!("../data/wordlist.tsv");
+
+/// Maximum number of words extracted per article (same as the LLM prompt asks for).
+const DEFAULT_MAX_WORDS: usize = 5;
+
+/// Definition used when no local dictionary is configured or the word is missing from it.
+const NO_DEFINITION: &str = "(no offline definition available)";
+
+/// Inflectional suffixes and the endings to restore when stripping them.
+const SUFFIX_RULES: &[(&str, &[&str])] = &[
+    ("ies", &["y"]),
+    ("ied", &["y"]),
+    ("ier", &["y"]),
+    ("iest", &["y"]),
+    ("ing", &["", "e"]),
+    ("ed", &["", "e"]),
+    ("er", &["", "e"]),
+    ("est", &["", "e"]),
+    ("es", &[""]),
+    ("s", &[""]),
+];
+
+#[derive(Debug, Clone, Copy)]
+struct WordEntry {
+    level: &'static str,
+    /// wordfreq Zipf frequency in hundredths (log10 of uses per billion words), if known
+    zipf: Option<u16>,
+}
+
+impl WordEntry {
+    /// Higher for more advanced levels
+    fn difficulty(&self) -> u8 {
+        match self.level {
+            "C2" => 6,
+            "C1" => 5,
+            "B2" => 4,
+            "B1" => 3,
+            "A2" => 2,
+            _ => 1,
+        }
+    }
+
+    /// Ordering key putting more advanced, then rarer words first. Words without
+    /// a frequency come after those with one at the same level.
+    fn rarity_key(&self) -> (std::cmp::Reverse<u8>, u16) {
+        (
+            std::cmp::Reverse(self.difficulty()),
+            self.zipf.unwrap_or(u16::MAX),
+        )
+    }
+}
+
+/// Parse a word list in the format of [`WORD_LIST`].
+fn parse_word_list(raw: &'static str) -> HashMap<&'static str, WordEntry> {
+    raw.lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .filter_map(|line| {
+            let mut cols = line.split('\t');
+            let lemma = cols.next()?.trim();
+            let level = cols.next()?.trim();
+            let zipf = cols
+                .next()
+                .and_then(|zipf| zipf.trim().parse::<f32>().ok())
+                .map(|zipf| (zipf * 100.0).round() as u16);
+            Some((lemma, WordEntry { level, zipf }))
+        })
+        .collect()
+}
+
+/// Local dictionary used to fill definitions offline.
+///
+/// Loaded from either a JSON dump (`{"word": "definition"}`, `{"word": ["sense", ...]}`
+/// or `[{"word": ..., "definition": ...}]`) or a tab-separated `word<TAB>definition`
+/// file such as a WordNet export.
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    entries: HashMap<String, String>,
+}
+
+impl Dictionary {
+    pub fn load(path: &Path) -> Result<Self, CoreError> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| CoreError::Unknown(format!("Failed to read dictionary: {}", e)))?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            Self::from_json(&raw)
+        } else {
+            Ok(Self::from_tsv(&raw))
+        }
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self, CoreError> {
+        let value: Value = serde_json::from_str(raw)
+            .map_err(|e| CoreError::Parse(format!("Invalid dictionary JSON: {}", e)))?;
+
+        let mut entries = HashMap::new();
+        match value {
+            Value::Object(map) => {
+                for (word, senses) in map {
+                    if let Some(definition) = first_sense(&senses) {
+                        entries.insert(word.to_lowercase(), definition);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    let word = item.get("word").and_then(Value::as_str);
+                    let definition = item.get("definition").and_then(first_sense);
+                    if let (Some(word), Some(definition)) = (word, definition) {
+                        entries.insert(word.to_lowercase(), definition);
+                    }
+                }
+            }
+            _ => {
+                return Err(CoreError::Parse(
+                    "Dictionary JSON must be an object or an array".to_string(),
+                ))
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    pub fn from_tsv(raw: &str) -> Self {
+        let mut entries = HashMap::new();
+        for line in raw.lines() {
+            if let Some((word, definition)) = line.split_once('\t') {
+                entries
+                    .entry(word.trim().to_lowercase())
+                    .or_insert_with(|| definition.trim().to_string());
+            }
+        }
+        Self { entries }
+    }
+
+    pub fn lookup(&self, word: &str) -> Option<&str> {
+        self.entries.get(&word.to_lowercase()).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn first_sense(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(items) => items.iter().find_map(first_sense),
+        _ => None,
+    }
+}
+
+/// Offline extractor that picks the most advanced listed words in the text.
+///
+/// Tokens are lemmatized with simple suffix rules and looked up in the embedded
+/// word list; the lemmas of the highest CEFR level that the learner does not
+/// already know are returned, rarest first where the list has frequencies and
+/// otherwise in text order, with the sentence they appear in.
+pub struct FrequencyExtractor {
+    words: HashMap<&'static str, WordEntry>,
+    dictionary: Option<Dictionary>,
+    known_words: HashSet<String>,
+    max_words: usize,
+}
+
+impl FrequencyExtractor {
+    pub fn new() -> Self {
+        Self {
+            words: parse_word_list(WORD_LIST),
+            dictionary: None,
+            known_words: HashSet::new(),
+            max_words: DEFAULT_MAX_WORDS,
+        }
+    }
+
+    /// Fill definitions from a local dictionary
+    pub fn with_dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.dictionary = Some(dictionary);
+        self
+    }
+
+    /// Skip lemmas the learner already knows
+    pub fn with_known_words<I>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        self.known_words
+            .extend(words.into_iter().map(|w| w.trim().to_lowercase()));
+        self
+    }
+
+    /// Set the maximum number of words extracted per text
+    pub fn with_max_words(mut self, max_words: usize) -> Self {
+        self.max_words = max_words;
+        self
+    }
+
+    /// Map a token to the lemma in the word list, if any.
+    fn lemmatize(&self, token: &str) -> Option<&'static str> {
+        lemma_candidates(token)
+            .iter()
+            .find_map(|candidate| self.words.get_key_value(candidate.as_str()))
+            .map(|(lemma, _)| *lemma)
+    }
+
+    fn extract_words(&self, text: &str) -> Vec<Vocabulary> {
+        // lemma -> (entry, first sentence, surface form, position of first use)
+        let mut found: HashMap<&'static str, (WordEntry, &str, String, usize)> = HashMap::new();
+
+        for sentence in split_sentences(text) {
+            for token in tokenize(sentence) {
+                if token.chars().any(|c| !c.is_alphabetic()) {
+                    continue;
+                }
+                let Some(lemma) = self.lemmatize(&token) else {
+                    continue;
+                };
+                if self.known_words.contains(lemma) {
+                    continue;
+                }
+                let position = found.len();
+                found
+                    .entry(lemma)
+                    .or_insert_with(|| (self.words[lemma], sentence, token, position));
+            }
+        }
+
+        let mut candidates: Vec<_> = found.into_iter().collect();
+        candidates.sort_by_key(|(_, (entry, _, _, position))| (entry.rarity_key(), *position));
+
+        candidates
+            .into_iter()
+            .take(self.max_words)
+            .map(|(lemma, (entry, sentence, surface, _))| {
+                let definition = self
+                    .dictionary
+                    .as_ref()
+                    .and_then(|d| d.lookup(lemma).or_else(|| d.lookup(&surface)))
+                    .unwrap_or(NO_DEFINITION);
+
+                Vocabulary {
+                    word: lemma.to_string(),
+                    definition: definition.to_string(),
+                    context_sentence: sentence.to_string(),
+                    cefr_level: Some(entry.level.to_string()),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for FrequencyExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LlmPort for FrequencyExtractor {
+    async fn extract(&self, text: &str) -> Result<Vec<Vocabulary>, CoreError> {
+        Ok(self.extract_words(text))
+    }
+}
+
+/// Possible lemmas for a lowercase token, most literal first.
+fn lemma_candidates(token: &str) -> Vec<String> {
+    let mut candidates = vec![token.to_string()];
+
+    for (suffix, endings) in SUFFIX_RULES {
+        let Some(stem) = token.strip_suffix(suffix) else {
+            continue;
+        };
+        if stem.len() < 3 {
+            continue;
+        }
+        for ending in *endings {
+            candidates.push(format!("{}{}", stem, ending));
+        }
+        // Doubled final consonant: "committed" -> "commit"
+        let mut chars = stem.chars().rev();
+        if let (Some(a), Some(b)) = (chars.next(), chars.next()) {
+            if a == b && !"aeiou".contains(a) {
+                candidates.push(stem[..stem.len() - a.len_utf8()].to_string());
+            }
+        }
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "The phenomenon of cognitive dissonance remains a quintessential concept. \
+        Smartphones are ubiquitous. Engineers mitigated the risk. The weather was nice.";
+
+    #[test]
+    fn test_lemma_candidates() {
+        assert!(lemma_candidates("mitigated").contains(&"mitigate".to_string()));
+        assert!(lemma_candidates("amplifies").contains(&"amplify".to_string()));
+        assert!(lemma_candidates("catalysts").contains(&"catalyst".to_string()));
+        assert!(lemma_candidates("curtailing").contains(&"curtail".to_string()));
+    }
+
+    #[test]
+    fn test_extracts_most_advanced_words_with_real_sentences() {
+        let extractor = FrequencyExtractor::new().with_max_words(3);
+        let vocabs = extractor.extract_words(TEXT);
+
+        let words: Vec<&str> = vocabs.iter().map(|v| v.word.as_str()).collect();
+        assert_eq!(words.len(), 3);
+        // C2 words first, then C1 in the order they appear
+        assert_eq!(words, vec!["quintessential", "ubiquitous", "dissonance"]);
+
+        let ubiquitous = vocabs.iter().find(|v| v.word == "ubiquitous").unwrap();
+        assert_eq!(ubiquitous.context_sentence, "Smartphones are ubiquitous.");
+        assert_eq!(ubiquitous.cefr_level.as_deref(), Some("C2"));
+        assert_eq!(ubiquitous.definition, NO_DEFINITION);
+    }
+
+    #[test]
+    fn test_lemmatizes_inflected_forms() {
+        let vocabs = FrequencyExtractor::new().extract_words("Engineers mitigated the risk.");
+        assert_eq!(vocabs.len(), 1);
+        assert_eq!(vocabs[0].word, "mitigate");
+    }
+
+    #[test]
+    fn test_skips_known_words() {
+        let extractor = FrequencyExtractor::new()
+            .with_known_words(vec!["Ubiquitous".to_string(), " mitigate\n".to_string()]);
+        let vocabs = extractor.extract_words(TEXT);
+        assert!(vocabs.iter().all(|v| v.word != "ubiquitous"));
+        assert!(vocabs.iter().all(|v| v.word != "mitigate"));
+    }
+
+    #[test]
+    fn test_rarer_words_come_first_within_a_level() {
+        let extractor = FrequencyExtractor {
+            words: parse_word_list(
+                "# lemma, level, zipf\nphenomenon\tC1\t4.21\ncognitive\tC1\nconcept\tC1\t4.5\ndissonance\tC1\t2.93\nubiquitous\tC2\t3.3\n",
+            ),
+            ..FrequencyExtractor::new()
+        };
+        let words: Vec<String> = extractor
+            .extract_words(TEXT)
+            .into_iter()
+            .map(|v| v.word)
+            .collect();
+        // Level first, then frequency; words without one last, in text order
+        assert_eq!(
+            words,
+            vec![
+                "ubiquitous",
+                "dissonance",
+                "phenomenon",
+                "concept",
+                "cognitive"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dictionary_formats() {
+        let json = Dictionary::from_json(
+            r#"{"ubiquitous": ["present everywhere", "omnipresent"], "mitigate": "to lessen"}"#,
+        )
+        .unwrap();
+        assert_eq!(json.lookup("Ubiquitous"), Some("present everywhere"));
+        assert_eq!(json.lookup("mitigate"), Some("to lessen"));
+
+        let list =
+            Dictionary::from_json(r#"[{"word": "tenuous", "definition": "very weak"}]"#).unwrap();
+        assert_eq!(list.lookup("tenuous"), Some("very weak"));
+
+        let tsv = Dictionary::from_tsv("lucid\tclearly expressed\nlucid\tsecond sense\n");
+        assert_eq!(tsv.lookup("lucid"), Some("clearly expressed"));
+        assert_eq!(tsv.len(), 1);
+    }
+
+    #[test]
+    fn test_definitions_from_dictionary() {
+        let dictionary = Dictionary::from_tsv("ubiquitous\tpresent everywhere\n");
+        let extractor = FrequencyExtractor::new().with_dictionary(dictionary);
+        let vocabs = extractor.extract_words("Smartphones are ubiquitous.");
+        assert_eq!(vocabs[0].definition, "present everywhere");
+    }
+}
@@ -1,21 +1,60 @@
+mod frequency;
+mod parse;
+pub mod validation;
+
+use std::future::Future;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
 use spread_core::{CoreError, LlmPort, Vocabulary};
 
+pub use frequency::{Dictionary, FrequencyExtractor};
+use validation::is_candidate_word;
+pub use validation::{RejectReason, ValidationReport, VocabularyValidator};
+
 const SYSTEM_PROMPT: &str = r#"You are a strict TOEFL exam creator. Identify 3-5 distinct English words from the text that are CEFR Level C1 or C2. Ignore common words. Output a JSON list of objects with the following keys:
 - 'word': The lemma of the word.
 - 'definition': A concise academic definition.
-- 'context_sentence': The sentence from the text containing the word."#;
+- 'context_sentence': The sentence from the text containing the word.
+- 'part_of_speech': The part of speech as used in the sentence (e.g. noun, verb, adjective).
+- 'ipa': The IPA pronunciation, e.g. "/ˈjuːbɪkwɪtəs/".
+- 'cefr_level': The CEFR level, "C1" or "C2".
+- 'synonyms': A list of up to 3 synonyms.
+- 'antonyms': A list of up to 3 antonyms (may be empty).
+- 'collocations': A list of up to 3 common collocations.
+- 'example_sentence': One additional example sentence not taken from the text."#;
+
+/// Maximum number of known words listed in the prompt, to keep it compact.
+const KNOWN_WORDS_PROMPT_LIMIT: usize = 50;
+
+const REPAIR_PROMPT: &str = r#"The following output was supposed to be a JSON array of vocabulary objects but could not be parsed. Return only the corrected JSON array, with no commentary. Keep the same words and values."#;
+
+const GRADING_PROMPT: &str = r#"You are grading a vocabulary quiz. Compare the learner's definition with the reference definition of the word. Judge meaning, not wording: paraphrases and synonyms are correct, vague or partially correct answers get partial credit. Output a JSON object {"score": <number from 0.0 to 1.0>}."#;
 
 const GEMINI_API_URL: &str =
     "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent";
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct ExtractedWord {
     word: String,
     definition: String,
     context_sentence: String,
+    #[serde(default)]
+    part_of_speech: Option<String>,
+    #[serde(default)]
+    ipa: Option<String>,
+    #[serde(default)]
+    cefr_level: Option<String>,
+    #[serde(default)]
+    synonyms: Vec<String>,
+    #[serde(default)]
+    antonyms: Vec<String>,
+    #[serde(default)]
+    collocations: Vec<String>,
+    #[serde(default)]
+    example_sentence: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -39,6 +78,41 @@ struct GeminiPart {
 struct GenerationConfig {
     #[serde(rename = "responseMimeType")]
     response_mime_type: String,
+    #[serde(rename = "responseSchema", skip_serializing_if = "Option::is_none")]
+    response_schema: Option<Value>,
+}
+
+/// Response schema for definition grading.
+fn grading_schema() -> Value {
+    json!({
+        "type": "OBJECT",
+        "properties": { "score": { "type": "NUMBER" } },
+        "required": ["score"]
+    })
+}
+
+/// Gemini `responseSchema` describing the `ExtractedWord` list.
+fn extraction_schema() -> Value {
+    let string_list = json!({ "type": "ARRAY", "items": { "type": "STRING" } });
+    json!({
+        "type": "ARRAY",
+        "items": {
+            "type": "OBJECT",
+            "properties": {
+                "word": { "type": "STRING" },
+                "definition": { "type": "STRING" },
+                "context_sentence": { "type": "STRING" },
+                "part_of_speech": { "type": "STRING" },
+                "ipa": { "type": "STRING" },
+                "cefr_level": { "type": "STRING", "enum": ["A1", "A2", "B1", "B2", "C1", "C2"] },
+                "synonyms": string_list,
+                "antonyms": string_list,
+                "collocations": string_list,
+                "example_sentence": { "type": "STRING" }
+            },
+            "required": ["word", "definition", "context_sentence"]
+        }
+    })
 }
 
 #[derive(Debug, Deserialize)]
@@ -71,6 +145,7 @@ pub struct GeminiLlmEngine {
     api_key: String,
     model: String,
     client: reqwest::Client,
+    known_words: Vec<String>,
 }
 
 impl GeminiLlmEngine {
@@ -84,6 +159,7 @@ impl GeminiLlmEngine {
             api_key,
             model: "gemini-2.5-flash".to_string(),
             client: reqwest::Client::new(),
+            known_words: Vec::new(),
         })
     }
 
@@ -92,30 +168,46 @@ impl GeminiLlmEngine {
         self
     }
 
-    fn filter_words(&self, words: Vec<ExtractedWord>, source_url: &str) -> Vec<Vocabulary> {
-        const STOP_WORDS: &[&str] = &[
-            "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has",
-            "had", "do", "does", "did", "will", "would", "could", "should", "may", "might", "must",
-            "can", "this", "that", "these", "those", "i", "you", "he", "she", "it", "we", "they",
-            "what", "which", "who", "whom", "when", "where", "why", "how", "all", "each", "every",
-            "both", "few", "more", "most", "other", "some", "such", "no", "nor", "not", "only",
-            "own", "same", "so", "than", "too", "very", "just", "but", "and", "or", "if", "for",
-            "with", "about", "against", "between", "into", "through", "during", "before", "after",
-            "above", "below", "to", "from", "up", "down", "in", "out", "on", "off", "over",
-            "under",
-        ];
+    /// Tell the model which words the learner already knows (a sample is sent with each prompt)
+    pub fn with_known_words(mut self, words: Vec<String>) -> Self {
+        self.known_words = words;
+        self
+    }
+
+    fn build_prompt(&self, text: &str) -> String {
+        let mut prompt = SYSTEM_PROMPT.to_string();
+        if !self.known_words.is_empty() {
+            let sample: Vec<&str> = self
+                .known_words
+                .iter()
+                .take(KNOWN_WORDS_PROMPT_LIMIT)
+                .map(String::as_str)
+                .collect();
+            prompt.push_str(&format!(
+                "\nThe learner already knows these words; choose different ones: {}",
+                sample.join(", ")
+            ));
+        }
+        format!("{}\n\nTarget Text:\n{}", prompt, text)
+    }
 
+    fn filter_words(&self, words: Vec<ExtractedWord>, source_url: &str) -> Vec<Vocabulary> {
         words
             .into_iter()
-            .filter(|w| {
-                let word_lower = w.word.to_lowercase();
-                w.word.len() > 3 && !STOP_WORDS.contains(&word_lower.as_str())
-            })
+            .filter(|w| is_candidate_word(&w.word))
             .map(|w| Vocabulary {
                 word: w.word,
                 definition: w.definition,
                 context_sentence: w.context_sentence,
                 source_url: source_url.to_string(),
+                part_of_speech: w.part_of_speech,
+                ipa: w.ipa,
+                cefr_level: w.cefr_level,
+                synonyms: w.synonyms,
+                antonyms: w.antonyms,
+                collocations: w.collocations,
+                example_sentence: w.example_sentence,
+                ..Default::default()
             })
             .collect()
     }
@@ -124,14 +216,77 @@ impl GeminiLlmEngine {
 #[async_trait]
 impl LlmPort for GeminiLlmEngine {
     async fn extract(&self, text: &str) -> Result<Vec<Vocabulary>, CoreError> {
-        let prompt = format!("{}\n\nTarget Text:\n{}", SYSTEM_PROMPT, text);
+        let text_response = self
+            .generate(self.build_prompt(text), extraction_schema())
+            .await?;
+
+        let extracted = parse_with_repair(&text_response, |prompt| {
+            self.generate(prompt, extraction_schema())
+        })
+        .await?;
+
+        Ok(self.filter_words(extracted, ""))
+    }
+
+    async fn grade_definition(
+        &self,
+        word: &str,
+        reference: &str,
+        answer: &str,
+    ) -> Result<Option<f64>, CoreError> {
+        let prompt = format!(
+            "{}\n\nWord: {}\nReference definition: {}\nLearner's definition: {}",
+            GRADING_PROMPT, word, reference, answer
+        );
+        let response = self.generate(prompt, grading_schema()).await?;
+        parse_score(&response).map(Some)
+    }
+}
 
+/// Parse the words in an extraction response. JSON that does not parse gets
+/// one repair round-trip: `generate` asks the model to fix its own output.
+async fn parse_with_repair<F, Fut>(
+    response: &str,
+    generate: F,
+) -> Result<Vec<ExtractedWord>, CoreError>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String, CoreError>>,
+{
+    let first_error = match parse::parse_extracted_words(response) {
+        Ok(words) => return Ok(words),
+        Err(e) => e,
+    };
+    let repair = format!(
+        "{}\n\nParse error: {}\n\nOutput:\n{}",
+        REPAIR_PROMPT, first_error, response
+    );
+    let repaired = generate(repair).await?;
+    parse::parse_extracted_words(&repaired)
+        .map_err(|e| CoreError::Parse(format!("Failed to parse vocabulary JSON: {}", e)))
+}
+
+/// Read the `score` from a grading response, clamped to 0.0..=1.0.
+fn parse_score(text: &str) -> Result<f64, CoreError> {
+    let value = parse::parse_json_lenient(text)
+        .map_err(|e| CoreError::Parse(format!("Failed to parse grading JSON: {}", e)))?;
+    value
+        .get("score")
+        .and_then(Value::as_f64)
+        .map(|score| score.clamp(0.0, 1.0))
+        .ok_or_else(|| CoreError::Parse("Grading response has no score".to_string()))
+}
+
+impl GeminiLlmEngine {
+    /// Send a prompt with a response schema and return the text of the first candidate.
+    async fn generate(&self, prompt: String, schema: Value) -> Result<String, CoreError> {
         let request_body = GeminiRequest {
             contents: vec![GeminiContent {
                 parts: vec![GeminiPart { text: prompt }],
             }],
             generation_config: GenerationConfig {
                 response_mime_type: "application/json".to_string(),
+                response_schema: Some(schema),
             },
         };
 
@@ -169,17 +324,12 @@ impl LlmPort for GeminiLlmEngine {
             )));
         }
 
-        let text_response = gemini_response
+        gemini_response
             .candidates
             .and_then(|c| c.into_iter().next())
             .and_then(|c| c.content.parts.into_iter().next())
             .map(|p| p.text)
-            .ok_or_else(|| CoreError::Parse("No content in Gemini response".to_string()))?;
-
-        let extracted: Vec<ExtractedWord> = serde_json::from_str(&text_response)
-            .map_err(|e| CoreError::Parse(format!("Failed to parse vocabulary JSON: {}", e)))?;
-
-        Ok(self.filter_words(extracted, ""))
+            .ok_or_else(|| CoreError::Parse("No content in Gemini response".to_string()))
     }
 }
 
@@ -212,6 +362,7 @@ impl LlmPort for MockLlmEngine {
                 definition: format!("Mock definition for '{}'", word),
                 context_sentence: format!("This is a sample context sentence containing {}.", word),
                 source_url: String::new(),
+                ..Default::default()
             })
             .collect();
 
@@ -239,6 +390,7 @@ mod tests {
             api_key: "test_key".to_string(),
             model: "gemini-2.5-flash".to_string(),
             client: reqwest::Client::new(),
+            known_words: Vec::new(),
         }
     }
 
@@ -251,11 +403,13 @@ mod tests {
                 word: "cat".to_string(),
                 definition: "A small feline".to_string(),
                 context_sentence: "The cat sat.".to_string(),
+                ..Default::default()
             },
             ExtractedWord {
                 word: "ephemeral".to_string(),
                 definition: "Lasting for a very short time".to_string(),
                 context_sentence: "The ephemeral beauty of cherry blossoms.".to_string(),
+                ..Default::default()
             },
         ];
 
@@ -274,11 +428,13 @@ mod tests {
                 word: "about".to_string(),
                 definition: "On the subject of".to_string(),
                 context_sentence: "About the matter.".to_string(),
+                ..Default::default()
             },
             ExtractedWord {
                 word: "ubiquitous".to_string(),
                 definition: "Present everywhere".to_string(),
                 context_sentence: "Smartphones are ubiquitous.".to_string(),
+                ..Default::default()
             },
         ];
 
@@ -287,6 +443,20 @@ mod tests {
         assert_eq!(filtered[0].word, "ubiquitous");
     }
 
+    #[test]
+    fn test_prompt_includes_known_words_sample() {
+        let engine = create_test_engine();
+        assert!(!engine.build_prompt("Text").contains("already knows"));
+
+        let known: Vec<String> = (0..80).map(|i| format!("word{}", i)).collect();
+        let engine = create_test_engine().with_known_words(known);
+        let prompt = engine.build_prompt("Text");
+        assert!(prompt.contains("already knows these words; choose different ones: word0, word1"));
+        assert!(prompt.contains("word49"));
+        assert!(!prompt.contains("word50"));
+        assert!(prompt.ends_with("Target Text:\nText"));
+    }
+
     #[test]
     fn test_json_parsing() {
         let json = r#"[
@@ -303,6 +473,37 @@ mod tests {
         let words = parsed.unwrap();
         assert_eq!(words.len(), 1);
         assert_eq!(words[0].word, "ephemeral");
+        assert!(words[0].part_of_speech.is_none());
+        assert!(words[0].synonyms.is_empty());
+    }
+
+    #[test]
+    fn test_json_parsing_lexical_fields() {
+        let json = r#"[
+            {
+                "word": "ubiquitous",
+                "definition": "Present everywhere",
+                "context_sentence": "Smartphones are ubiquitous.",
+                "part_of_speech": "adjective",
+                "ipa": "/juːˈbɪkwɪtəs/",
+                "cefr_level": "C1",
+                "synonyms": ["omnipresent", "pervasive"],
+                "antonyms": ["rare"],
+                "collocations": ["ubiquitous presence"],
+                "example_sentence": "Coffee shops are ubiquitous in this city."
+            }
+        ]"#;
+
+        let words: Vec<ExtractedWord> = serde_json::from_str(json).unwrap();
+        let engine = create_test_engine();
+        let vocabs = engine.filter_words(words, "https://example.com");
+
+        assert_eq!(vocabs[0].part_of_speech.as_deref(), Some("adjective"));
+        assert_eq!(vocabs[0].cefr_level.as_deref(), Some("C1"));
+        assert_eq!(vocabs[0].synonyms, vec!["omnipresent", "pervasive"]);
+        assert_eq!(vocabs[0].antonyms, vec!["rare"]);
+        assert_eq!(vocabs[0].collocations, vec!["ubiquitous presence"]);
+        assert!(vocabs[0].example_sentence.is_some());
     }
 
     #[test]
@@ -315,12 +516,76 @@ mod tests {
             }],
             generation_config: GenerationConfig {
                 response_mime_type: "application/json".to_string(),
+                response_schema: Some(extraction_schema()),
             },
         };
 
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains("responseMimeType"));
         assert!(json.contains("application/json"));
+        assert!(json.contains("responseSchema"));
+        assert!(json.contains(r#""required":["word","definition","context_sentence"]"#));
+    }
+
+    const BROKEN_JSON: &str = r#"[{"word": "ephemeral", "definition": "Short-lived""#;
+    const REPAIRED_JSON: &str = r#"[{"word": "ephemeral", "definition": "Short-lived", "context_sentence": "Fame is ephemeral."}]"#;
+
+    #[tokio::test]
+    async fn test_parse_with_repair_skips_repair_for_valid_json() {
+        let words = parse_with_repair(REPAIRED_JSON, |_| async {
+            panic!("valid JSON must not be repaired")
+        })
+        .await
+        .unwrap();
+        assert_eq!(words.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_parse_with_repair_uses_repaired_response() {
+        let mut prompt = String::new();
+        let words = parse_with_repair(BROKEN_JSON, |repair| {
+            prompt = repair;
+            async { Ok(REPAIRED_JSON.to_string()) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].word, "ephemeral");
+        assert_eq!(words[0].context_sentence, "Fame is ephemeral.");
+        assert!(prompt.starts_with(REPAIR_PROMPT));
+        assert!(prompt.ends_with(BROKEN_JSON));
+    }
+
+    #[tokio::test]
+    async fn test_parse_with_repair_fails_when_repair_does_not_parse() {
+        let result =
+            parse_with_repair(BROKEN_JSON, |_| async { Ok(BROKEN_JSON.to_string()) }).await;
+        assert!(matches!(result, Err(CoreError::Parse(_))));
+
+        // Errors from the repair request itself are passed on
+        let result = parse_with_repair(BROKEN_JSON, |_| async {
+            Err(CoreError::Network("offline".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(CoreError::Network(_))));
+    }
+
+    #[test]
+    fn test_parse_score() {
+        assert_eq!(parse_score(r#"{"score": 0.75}"#).unwrap(), 0.75);
+        assert_eq!(parse_score("```json\n{\"score\": 1.5}\n```").unwrap(), 1.0);
+        assert!(parse_score(r#"{"verdict": "correct"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_llm_cannot_grade() {
+        let llm = MockLlmEngine::new();
+        let score = llm
+            .grade_definition("ephemeral", "Short-lived", "Brief")
+            .await
+            .unwrap();
+        assert!(score.is_none());
     }
 
     #[tokio::test]
@@ -0,0 +1,211 @@
+//! Lenient parsing of model output into extracted words.
+//!
+//! Even with a response schema, models occasionally wrap the list in an
+//! object, add a Markdown fence or prose, or leave trailing commas. This
+//! module recovers the word list from those shapes before giving up.
+
+use serde_json::Value;
+
+use crate::ExtractedWord;
+
+/// Object keys under which models commonly nest the word list.
+const WRAPPER_KEYS: &[&str] = &[
+    "words",
+    "vocabulary",
+    "vocabularies",
+    "items",
+    "results",
+    "data",
+];
+
+/// Parse a model response into extracted words, tolerating common wrapper shapes.
+pub(crate) fn parse_extracted_words(text: &str) -> Result<Vec<ExtractedWord>, String> {
+    let value = parse_json_lenient(text)?;
+    let items =
+        find_word_list(value).ok_or_else(|| "JSON does not contain a list of words".to_string())?;
+
+    let total = items.len();
+    let mut words = Vec::with_capacity(total);
+    let mut last_error = None;
+    for item in items {
+        match serde_json::from_value::<ExtractedWord>(item) {
+            Ok(word) => words.push(word),
+            Err(e) => last_error = Some(e.to_string()),
+        }
+    }
+
+    match last_error {
+        Some(e) if words.is_empty() && total > 0 => Err(format!("Invalid word entry: {}", e)),
+        _ => Ok(words),
+    }
+}
+
+pub(crate) fn parse_json_lenient(text: &str) -> Result<Value, String> {
+    let stripped = strip_code_fence(text.trim());
+    if let Ok(value) = serde_json::from_str(stripped) {
+        return Ok(value);
+    }
+
+    // Drop any prose around the JSON payload
+    let payload = json_span(stripped).unwrap_or(stripped);
+    serde_json::from_str(payload)
+        .or_else(|_| serde_json::from_str(&strip_trailing_commas(payload)))
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a surrounding Markdown code fence such as ```` ```json ... ``` ````.
+fn strip_code_fence(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    let body = rest.split_once('\n').map(|(_, body)| body).unwrap_or("");
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+/// Slice from the first `[`/`{` to the last `]`/`}`.
+fn json_span(text: &str) -> Option<&str> {
+    let start = text.find(['[', '{'])?;
+    let end = text.rfind([']', '}'])?;
+    (end > start).then(|| &text[start..=end])
+}
+
+/// Remove commas that directly precede a closing bracket, ignoring string contents.
+fn strip_trailing_commas(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let chars: Vec<char> = text.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some(']') | Some('}')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// Locate the array of word objects inside the parsed value.
+fn find_word_list(value: Value) -> Option<Vec<Value>> {
+    match value {
+        Value::Array(items) => Some(items),
+        Value::Object(mut map) => {
+            if map.contains_key("word") {
+                return Some(vec![Value::Object(map)]);
+            }
+            for key in WRAPPER_KEYS {
+                if let Some(inner) = map.remove(*key) {
+                    if let Some(items) = find_word_list(inner) {
+                        return Some(items);
+                    }
+                }
+            }
+            // Fall back to the only value of a single-key wrapper, e.g. {"extracted": [...]}
+            if map.len() == 1 {
+                return map.into_iter().next().and_then(|(_, v)| find_word_list(v));
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Responses seen from the model in the wild, with the words they should yield.
+    const CORPUS: &[(&str, &str, &[&str])] = &[
+        (
+            "plain array",
+            r#"[{"word": "ephemeral", "definition": "Short-lived", "context_sentence": "Fame is ephemeral."}]"#,
+            &["ephemeral"],
+        ),
+        (
+            "words wrapper",
+            r#"{"words": [{"word": "ubiquitous", "definition": "Everywhere", "context_sentence": "Phones are ubiquitous."}]}"#,
+            &["ubiquitous"],
+        ),
+        (
+            "nested data wrapper",
+            r#"{"data": {"vocabulary": [{"word": "mitigate", "definition": "Lessen", "context_sentence": "We mitigate risk."}]}}"#,
+            &["mitigate"],
+        ),
+        (
+            "unknown single-key wrapper",
+            r#"{"extracted_words": [{"word": "salient", "definition": "Most noticeable", "context_sentence": "A salient point."}]}"#,
+            &["salient"],
+        ),
+        (
+            "single object",
+            r#"{"word": "candid", "definition": "Frank", "context_sentence": "A candid reply."}"#,
+            &["candid"],
+        ),
+        (
+            "markdown fence",
+            "```json\n[{\"word\": \"arduous\", \"definition\": \"Difficult\", \"context_sentence\": \"An arduous climb.\"}]\n```",
+            &["arduous"],
+        ),
+        (
+            "leading prose",
+            "Here is the JSON you asked for:\n[{\"word\": \"tenuous\", \"definition\": \"Weak\", \"context_sentence\": \"A tenuous link.\"}]\nLet me know if you need more.",
+            &["tenuous"],
+        ),
+        (
+            "trailing commas",
+            r#"[{"word": "pragmatic", "definition": "Practical, sensible", "context_sentence": "A pragmatic fix.",},]"#,
+            &["pragmatic"],
+        ),
+        (
+            "one invalid entry",
+            r#"[{"word": "lucid", "definition": "Clear", "context_sentence": "A lucid essay."}, {"definition": "missing word"}]"#,
+            &["lucid"],
+        ),
+        ("empty array", "[]", &[]),
+    ];
+
+    #[test]
+    fn test_parse_corpus() {
+        for (name, response, expected) in CORPUS {
+            let words = parse_extracted_words(response)
+                .unwrap_or_else(|e| panic!("{}: failed to parse: {}", name, e));
+            let got: Vec<&str> = words.iter().map(|w| w.word.as_str()).collect();
+            assert_eq!(&got, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_parse_unrecoverable() {
+        // Truncated output needs a repair round-trip
+        assert!(parse_extracted_words(r#"[{"word": "ephemeral", "defin"#).is_err());
+        assert!(parse_extracted_words("I could not find any difficult words.").is_err());
+        assert!(parse_extracted_words(r#"{"status": "ok", "count": 0}"#).is_err());
+        assert!(parse_extracted_words(r#"[{"definition": "no word"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_strip_trailing_commas_keeps_strings() {
+        assert_eq!(
+            strip_trailing_commas(r#"{"a": "x, ]", "b": [1, 2,],}"#),
+            r#"{"a": "x, ]", "b": [1, 2]}"#
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! Provider-agnostic validation of extracted vocabulary against the source text.
+
+use std::collections::HashSet;
+
+use spread_core::Vocabulary;
+
+/// Function words that are never worth studying, regardless of provider.
+const STOP_WORDS: &[&str] = &[
+    "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
+    "do", "does", "did", "will", "would", "could", "should", "may", "might", "must", "can", "this",
+    "that", "these", "those", "i", "you", "he", "she", "it", "we", "they", "what", "which", "who",
+    "whom", "when", "where", "why", "how", "all", "each", "every", "both", "few", "more", "most",
+    "other", "some", "such", "no", "nor", "not", "only", "own", "same", "so", "than", "too",
+    "very", "just", "but", "and", "or", "if", "for", "with", "about", "against", "between", "into",
+    "through", "during", "before", "after", "above", "below", "to", "from", "up", "down", "in",
+    "out", "on", "off", "over", "under",
+];
+
+/// Whether a lowercase token is a function word.
+pub fn is_stop_word(token: &str) -> bool {
+    STOP_WORDS.contains(&token)
+}
+
+/// Minimum word length (in bytes) for a word to be considered study material.
+const MIN_WORD_LEN: usize = 4;
+
+/// Check whether a word is worth keeping at all (long enough and not a stop word).
+pub fn is_candidate_word(word: &str) -> bool {
+    let word_lower = word.to_lowercase();
+    word.len() >= MIN_WORD_LEN && !STOP_WORDS.contains(&word_lower.as_str())
+}
+
+/// Why a vocabulary item was dropped by the validator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// Too short or a stop word
+    NotCandidate,
+    /// Neither the word nor an inflected form occurs in the source text
+    NotInText,
+    /// The learner already knows the word
+    Known,
+}
+
+/// Outcome of validating one article's vocabulary
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// Items that passed, with `context_sentence` taken verbatim from the text
+    pub accepted: Vec<Vocabulary>,
+    /// Items that were dropped, with the reason
+    pub rejected: Vec<(Vocabulary, RejectReason)>,
+    /// Number of accepted items whose context sentence had to be replaced
+    pub snapped: usize,
+}
+
+/// Checks extracted vocabulary against the text it was extracted from.
+///
+/// Every word must occur in the text (as-is or inflected), and its
+/// `context_sentence` is replaced by the real sentence from the text when the
+/// provider paraphrased or invented one. Words on the learner's known-words
+/// list are dropped.
+#[derive(Debug, Default)]
+pub struct VocabularyValidator {
+    known_words: HashSet<String>,
+}
+
+impl VocabularyValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop words (or inflections of words) the learner already knows
+    pub fn with_known_words<I>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        self.known_words
+            .extend(words.into_iter().map(|w| w.trim().to_lowercase()));
+        self
+    }
+
+    fn is_known(&self, word_lower: &str) -> bool {
+        self.known_words.contains(word_lower)
+            || self
+                .known_words
+                .iter()
+                .any(|known| is_inflection_of(word_lower, known))
+    }
+
+    pub fn validate(&self, vocabularies: Vec<Vocabulary>, text: &str) -> ValidationReport {
+        let sentences = split_sentences(text);
+        let mut report = ValidationReport::default();
+
+        for mut vocab in vocabularies {
+            if !is_candidate_word(&vocab.word) {
+                report.rejected.push((vocab, RejectReason::NotCandidate));
+                continue;
+            }
+
+            let word_lower = vocab.word.trim().to_lowercase();
+            if self.is_known(&word_lower) {
+                report.rejected.push((vocab, RejectReason::Known));
+                continue;
+            }
+
+            let containing: Vec<&str> = sentences
+                .iter()
+                .copied()
+                .filter(|s| sentence_contains(s, &word_lower))
+                .collect();
+
+            let Some(best) = best_matching_sentence(&containing, &vocab.context_sentence) else {
+                report.rejected.push((vocab, RejectReason::NotInText));
+                continue;
+            };
+
+            if normalize_sentence(best) != normalize_sentence(&vocab.context_sentence) {
+                report.snapped += 1;
+            }
+            vocab.context_sentence = best.to_string();
+            report.accepted.push(vocab);
+        }
+
+        report
+    }
+}
+
+/// Split text into sentences on `.`, `!` or `?` followed by whitespace, and on newlines.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let boundary = match c {
+            '\n' => Some(i),
+            '.' | '!' | '?' => match chars.peek() {
+                Some((_, next)) if next.is_whitespace() => Some(i + c.len_utf8()),
+                None => Some(i + c.len_utf8()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(end) = boundary {
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+
+    sentences
+}
+
+/// Split a sentence into lowercase word tokens.
+pub fn tokenize(sentence: &str) -> Vec<String> {
+    sentence
+        .split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '-'))
+        .map(|t| t.trim_matches(|c| c == '\'' || c == '-'))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// Whether `token` is `lemma` or a regular inflection of it (plural, past, -ing, comparative).
+pub fn is_inflection_of(token: &str, lemma: &str) -> bool {
+    if token == lemma {
+        return true;
+    }
+
+    const SUFFIXES: &[&str] = &["s", "es", "d", "ed", "ing", "er", "est"];
+    if let Some(rest) = token.strip_prefix(lemma) {
+        if SUFFIXES.contains(&rest) {
+            return true;
+        }
+        // Doubled final consonant: "commit" -> "committed"
+        if let (Some(last), Some(suffix)) = (lemma.chars().last(), rest.get(1..)) {
+            if rest.starts_with(last) && ["ed", "ing", "er", "est"].contains(&suffix) {
+                return true;
+            }
+        }
+    }
+
+    // Silent e: "mitigate" -> "mitigating"
+    if let Some(stem) = lemma.strip_suffix('e') {
+        if let Some(rest) = token.strip_prefix(stem) {
+            if ["ing", "ed", "er", "est"].contains(&rest) {
+                return true;
+            }
+        }
+    }
+
+    // Consonant + y: "amplify" -> "amplifies"
+    if let Some(stem) = lemma.strip_suffix('y') {
+        if let Some(rest) = token.strip_prefix(stem) {
+            if ["ies", "ied", "ier", "iest"].contains(&rest) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+fn sentence_contains(sentence: &str, word_lower: &str) -> bool {
+    if word_lower.contains(' ') {
+        // Multi-word expressions are matched as a phrase
+        return tokenize(sentence).join(" ").contains(word_lower);
+    }
+    tokenize(sentence)
+        .iter()
+        .any(|token| is_inflection_of(token, word_lower))
+}
+
+/// Pick the candidate sentence closest to the one the provider returned.
+fn best_matching_sentence<'a>(candidates: &[&'a str], given: &str) -> Option<&'a str> {
+    let given_normalized = normalize_sentence(given);
+    if let Some(exact) = candidates
+        .iter()
+        .find(|s| normalize_sentence(s) == given_normalized)
+    {
+        return Some(exact);
+    }
+
+    let given_tokens = tokenize(given);
+    candidates.iter().copied().max_by_key(|s| {
+        tokenize(s)
+            .iter()
+            .filter(|t| given_tokens.contains(t))
+            .count()
+    })
+}
+
+/// Lowercase, collapse whitespace and drop trailing punctuation for comparison.
+fn normalize_sentence(sentence: &str) -> String {
+    sentence
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(|c: char| c.is_ascii_punctuation())
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Smartphones are ubiquitous today. Engineers mitigated the risk \
+        quickly! The committee committed to a plan.";
+
+    fn vocab(word: &str, context: &str) -> Vocabulary {
+        Vocabulary {
+            word: word.to_string(),
+            definition: "def".to_string(),
+            context_sentence: context.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        let sentences = split_sentences(TEXT);
+        assert_eq!(
+            sentences,
+            vec![
+                "Smartphones are ubiquitous today.",
+                "Engineers mitigated the risk quickly!",
+                "The committee committed to a plan.",
+            ]
+        );
+        assert!(split_sentences("").is_empty());
+        assert_eq!(
+            split_sentences("Version 1.75 shipped"),
+            vec!["Version 1.75 shipped"]
+        );
+    }
+
+    #[test]
+    fn test_is_inflection_of() {
+        assert!(is_inflection_of("mitigated", "mitigate"));
+        assert!(is_inflection_of("mitigating", "mitigate"));
+        assert!(is_inflection_of("committed", "commit"));
+        assert!(is_inflection_of("amplifies", "amplify"));
+        assert!(is_inflection_of("catalysts", "catalyst"));
+        assert!(!is_inflection_of("mitigation", "mitigate"));
+        assert!(!is_inflection_of("cat", "catalyst"));
+    }
+
+    #[test]
+    fn test_validate_keeps_exact_context() {
+        let report = VocabularyValidator::new().validate(
+            vec![vocab("ubiquitous", "Smartphones are ubiquitous today.")],
+            TEXT,
+        );
+        assert_eq!(report.accepted.len(), 1);
+        assert_eq!(report.snapped, 0);
+        assert_eq!(
+            report.accepted[0].context_sentence,
+            "Smartphones are ubiquitous today."
+        );
+    }
+
+    #[test]
+    fn test_validate_snaps_paraphrased_context() {
+        let report = VocabularyValidator::new().validate(
+            vec![vocab("mitigate", "The engineers worked to mitigate risks.")],
+            TEXT,
+        );
+        assert_eq!(report.accepted.len(), 1);
+        assert_eq!(report.snapped, 1);
+        assert_eq!(
+            report.accepted[0].context_sentence,
+            "Engineers mitigated the risk quickly!"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_hallucinated_word() {
+        let report = VocabularyValidator::new().validate(
+            vec![
+                vocab("serendipity", "It was serendipity."),
+                vocab("about", "About the matter."),
+            ],
+            TEXT,
+        );
+        assert!(report.accepted.is_empty());
+        assert_eq!(report.rejected[0].1, RejectReason::NotInText);
+        assert_eq!(report.rejected[1].1, RejectReason::NotCandidate);
+    }
+
+    #[test]
+    fn test_validate_rejects_known_words() {
+        let validator = VocabularyValidator::new().with_known_words(vec!["Mitigate".to_string()]);
+        let report = validator.validate(
+            vec![
+                vocab("mitigated", "Engineers mitigated the risk quickly!"),
+                vocab("ubiquitous", "Smartphones are ubiquitous today."),
+            ],
+            TEXT,
+        );
+        assert_eq!(report.accepted.len(), 1);
+        assert_eq!(report.accepted[0].word, "ubiquitous");
+        assert_eq!(report.rejected[0].1, RejectReason::Known);
+    }
+}
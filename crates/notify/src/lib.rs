@@ -1,6 +1,9 @@
+use chrono::NaiveDate;
 use rand::seq::SliceRandom;
 use serde::Serialize;
 use spread_core::model::Vocabulary;
+use spread_core::quiz::Question;
+use spread_core::timezone::{self, Tz};
 use thiserror::Error;
 use tracing::{info, warn};
 
@@ -102,6 +105,8 @@ impl TelegramClient {
 pub struct Notifier {
     telegram: TelegramClient,
     word_count: usize,
+    timezone: Tz,
+    date: Option<NaiveDate>,
 }
 
 impl Notifier {
@@ -113,6 +118,8 @@ impl Notifier {
         Some(Self {
             telegram,
             word_count: DEFAULT_WORD_COUNT,
+            timezone: Tz::UTC,
+            date: None,
         })
     }
 
@@ -121,6 +128,8 @@ impl Notifier {
         Self {
             telegram,
             word_count: DEFAULT_WORD_COUNT,
+            timezone: Tz::UTC,
+            date: None,
         }
     }
 
@@ -130,6 +139,35 @@ impl Notifier {
         self
     }
 
+    /// Timezone that decides which day is today
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Title the message with this local day instead of today
+    pub fn with_date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// Message title and the line shown when no words were collected
+    fn heading(&self) -> (String, String) {
+        match self.date {
+            Some(date) if date != timezone::today(self.timezone) => {
+                let date = escape_markdown(&date.format("%Y-%m-%d").to_string());
+                (
+                    format!("📚 *Vocabulary of {}*", date),
+                    format!("No words collected on {}\\!", date),
+                )
+            }
+            _ => (
+                "📚 *Today's Vocabulary*".to_string(),
+                "No words collected today\\!".to_string(),
+            ),
+        }
+    }
+
     /// Select random words from a list
     ///
     /// Returns up to `word_count` words. If fewer words are available,
@@ -149,11 +187,12 @@ impl Notifier {
     ///
     /// Uses MarkdownV2 format for rich text display
     pub fn format_message(&self, vocabularies: &[&Vocabulary]) -> String {
+        let (title, empty) = self.heading();
         if vocabularies.is_empty() {
-            return "📚 *Today's Vocabulary*\n\nNo words collected today\\!".to_string();
+            return format!("{}\n\n{}", title, empty);
         }
 
-        let mut message = String::from("📚 *Today's Vocabulary*\n\n");
+        let mut message = format!("{}\n\n", title);
 
         for (i, vocab) in vocabularies.iter().enumerate() {
             // Escape special characters for MarkdownV2
@@ -162,11 +201,13 @@ impl Notifier {
             let sentence = escape_markdown(&vocab.context_sentence);
 
             message.push_str(&format!(
-                "{}\\. *{}*\n   📖 _{}_\n   > \"{}\"\n\n",
+                "{}\\. *{}*{}\n   📖 _{}_\n   > \"{}\"\n{}\n",
                 i + 1,
                 word,
+                format_headword_details(vocab),
                 definition,
-                sentence
+                sentence,
+                format_lexical_details(vocab)
             ));
         }
 
@@ -200,6 +241,62 @@ impl Notifier {
             skipped: false,
         })
     }
+
+    /// Format quiz questions into a Telegram message, answers hidden as spoilers
+    pub fn format_quiz(&self, questions: &[Question]) -> String {
+        let mut message = String::from("🧩 *Vocabulary Quiz*\n\n");
+
+        for (i, question) in questions.iter().enumerate() {
+            message.push_str(&format!(
+                "{}\\. {}\n",
+                i + 1,
+                escape_markdown(&question.prompt)
+            ));
+            for (j, choice) in question.choices.iter().enumerate() {
+                message.push_str(&format!(
+                    "   {}\\) {}\n",
+                    (b'A' + j as u8) as char,
+                    escape_markdown(choice)
+                ));
+            }
+            let answer = match question.answer_index {
+                Some(j) => format!("{}) {}", (b'A' + j as u8) as char, question.answer),
+                None => question.answer.clone(),
+            };
+            message.push_str(&format!("   ||{}||\n\n", escape_markdown(&answer)));
+        }
+
+        message
+    }
+
+    /// Send quiz questions
+    pub async fn send_quiz(&self, questions: &[Question]) -> Result<NotifyResult, NotifyError> {
+        if questions.is_empty() {
+            warn!("No quiz questions to send");
+            return Ok(NotifyResult {
+                words_sent: 0,
+                skipped: true,
+            });
+        }
+
+        info!(
+            question_count = questions.len(),
+            "Sending quiz notification"
+        );
+        self.telegram
+            .send_message(&self.format_quiz(questions))
+            .await?;
+
+        Ok(NotifyResult {
+            words_sent: questions.len(),
+            skipped: false,
+        })
+    }
+
+    /// Number of words (or quiz questions) per notification
+    pub fn word_count(&self) -> usize {
+        self.word_count
+    }
 }
 
 /// Result of a notification attempt
@@ -211,6 +308,45 @@ pub struct NotifyResult {
     pub skipped: bool,
 }
 
+/// Format part of speech, IPA and CEFR level shown next to the word
+fn format_headword_details(vocab: &Vocabulary) -> String {
+    let mut details = String::new();
+    if let Some(pos) = &vocab.part_of_speech {
+        details.push_str(&format!(" _{}_", escape_markdown(pos)));
+    }
+    if let Some(ipa) = &vocab.ipa {
+        details.push_str(&format!(" {}", escape_markdown(ipa)));
+    }
+    if let Some(level) = &vocab.cefr_level {
+        details.push_str(&format!(" \\[{}\\]", escape_markdown(level)));
+    }
+    details
+}
+
+/// Format synonyms, antonyms, collocations and the extra example, one line each
+fn format_lexical_details(vocab: &Vocabulary) -> String {
+    let mut lines = String::new();
+    let lists = [
+        ("🔁 Synonyms", &vocab.synonyms),
+        ("↔️ Antonyms", &vocab.antonyms),
+        ("🔗 Collocations", &vocab.collocations),
+    ];
+    for (label, items) in lists {
+        if !items.is_empty() {
+            let joined = items
+                .iter()
+                .map(|item| escape_markdown(item))
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push_str(&format!("   {}: {}\n", label, joined));
+        }
+    }
+    if let Some(example) = &vocab.example_sentence {
+        lines.push_str(&format!("   ✏️ {}\n", escape_markdown(example)));
+    }
+    lines
+}
+
 /// Escape special characters for Telegram MarkdownV2 format
 fn escape_markdown(text: &str) -> String {
     text.chars()
@@ -253,6 +389,7 @@ mod tests {
             definition: "Lasting for a very short time.".to_string(),
             context_sentence: "Fashions are ephemeral, changing with every season.".to_string(),
             source_url: "https://example.com/article".to_string(),
+            ..Default::default()
         }
     }
 
@@ -321,6 +458,48 @@ mod tests {
         assert!(message.contains("Today's Vocabulary"));
         assert!(message.contains("ephemeral"));
         assert!(message.contains("Lasting for a very short time"));
+        assert!(!message.contains("Synonyms"));
+    }
+
+    #[test]
+    fn test_format_message_for_date() {
+        let telegram = TelegramClient::new("token".to_string(), "chat".to_string());
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let notifier = Notifier::new(telegram)
+            .with_timezone(Tz::Asia__Seoul)
+            .with_date(date);
+
+        let message = notifier.format_message(&[]);
+        assert!(message.starts_with("📚 *Vocabulary of 2026\\-03\\-01*"));
+        assert!(message.contains("No words collected on 2026\\-03\\-01"));
+
+        // Today's date keeps the usual title
+        let telegram = TelegramClient::new("token".to_string(), "chat".to_string());
+        let notifier = Notifier::new(telegram)
+            .with_timezone(Tz::Asia__Seoul)
+            .with_date(timezone::today(Tz::Asia__Seoul));
+        assert!(notifier.format_message(&[]).contains("Today's Vocabulary"));
+    }
+
+    #[test]
+    fn test_format_message_with_lexical_details() {
+        let telegram = TelegramClient::new("token".to_string(), "chat".to_string());
+        let notifier = Notifier::new(telegram);
+
+        let vocab = Vocabulary {
+            part_of_speech: Some("adjective".to_string()),
+            cefr_level: Some("C1".to_string()),
+            synonyms: vec!["fleeting".to_string(), "transient".to_string()],
+            example_sentence: Some("Youth is ephemeral.".to_string()),
+            ..sample_vocabulary()
+        };
+        let message = notifier.format_message(&[&vocab]);
+
+        assert!(message.contains("_adjective_"));
+        assert!(message.contains("\\[C1\\]"));
+        assert!(message.contains("Synonyms: fleeting, transient"));
+        assert!(message.contains("Youth is ephemeral\\."));
+        assert!(!message.contains("Antonyms"));
     }
 
     #[test]
@@ -341,4 +520,41 @@ mod tests {
         let notifier = Notifier::from_env();
         assert!(notifier.is_none());
     }
+
+    #[test]
+    fn test_format_quiz_hides_answers() {
+        use spread_core::quiz::QuestionKind;
+
+        let telegram = TelegramClient::new("token".to_string(), "chat".to_string());
+        let notifier = Notifier::new(telegram);
+
+        let questions = vec![
+            Question {
+                kind: QuestionKind::Cloze,
+                word: "ephemeral".to_string(),
+                prompt: "Fill in the blank: Fame is _____.".to_string(),
+                choices: vec![],
+                answer_index: None,
+                answer: "ephemeral".to_string(),
+                context_sentence: "Fame is ephemeral.".to_string(),
+                source_url: "https://example.com".to_string(),
+            },
+            Question {
+                kind: QuestionKind::MultipleChoice,
+                word: "tenuous".to_string(),
+                prompt: "What does 'tenuous' mean?".to_string(),
+                choices: vec!["Frank".to_string(), "Very weak".to_string()],
+                answer_index: Some(1),
+                answer: "Very weak".to_string(),
+                context_sentence: "A tenuous link.".to_string(),
+                source_url: "https://example.com".to_string(),
+            },
+        ];
+
+        let message = notifier.format_quiz(&questions);
+        assert!(message.contains("1\\. Fill in the blank: Fame is \\_\\_\\_\\_\\_\\."));
+        assert!(message.contains("||ephemeral||"));
+        assert!(message.contains("   B\\) Very weak"));
+        assert!(message.contains("||B\\) Very weak||"));
+    }
 }
@@ -0,0 +1,267 @@
+//! Storage picked by the scheme of a database URL.
+
+use async_trait::async_trait;
+use spread_core::error::CoreError;
+use spread_core::model::{Article, Deck, ReviewCard, SearchHit, Vocabulary};
+use spread_core::port::StoragePort;
+use spread_core::query::{TagFilter, VocabPage, VocabQuery};
+use spread_core::run::{PipelineRun, QueuedItem};
+use spread_core::srs::{Algorithm, Grade, ReviewState};
+
+#[cfg(feature = "postgres")]
+use crate::PostgresStorage;
+use crate::SqliteStorage;
+
+/// A `sqlite:` database, or with the `postgres` feature a `postgres://` one.
+pub enum AnyStorage {
+    Sqlite(SqliteStorage),
+    #[cfg(feature = "postgres")]
+    Postgres(PostgresStorage),
+}
+
+/// Call `$call` on whichever storage `$storage` holds, bound to `$inner`.
+macro_rules! dispatch {
+    ($storage:expr, $inner:ident => $call:expr) => {
+        match $storage {
+            AnyStorage::Sqlite($inner) => $call,
+            #[cfg(feature = "postgres")]
+            AnyStorage::Postgres($inner) => $call,
+        }
+    };
+}
+
+impl AnyStorage {
+    /// Open the database at `db_url` and apply any pending schema migrations.
+    pub async fn new(db_url: &str) -> Result<Self, CoreError> {
+        let storage = Self::connect(db_url).await?;
+        dispatch!(&storage, s => s.migrate().await)?;
+        Ok(storage)
+    }
+
+    /// Open the database at `db_url` without touching its schema.
+    pub async fn connect(db_url: &str) -> Result<Self, CoreError> {
+        Self::open(db_url, false).await
+    }
+
+    /// Open an existing database at `db_url` read-only. `NotFound` when a
+    /// SQLite file does not exist.
+    pub async fn open_read_only(db_url: &str) -> Result<Self, CoreError> {
+        Self::open(db_url, true).await
+    }
+
+    async fn open(db_url: &str, read_only: bool) -> Result<Self, CoreError> {
+        if db_url.starts_with("sqlite:") {
+            return Ok(Self::Sqlite(if read_only {
+                SqliteStorage::open_read_only(db_url).await?
+            } else {
+                SqliteStorage::connect(db_url).await?
+            }));
+        }
+        if db_url.starts_with("postgres://") || db_url.starts_with("postgresql://") {
+            #[cfg(feature = "postgres")]
+            return Ok(Self::Postgres(if read_only {
+                PostgresStorage::open_read_only(db_url).await?
+            } else {
+                PostgresStorage::connect(db_url).await?
+            }));
+            #[cfg(not(feature = "postgres"))]
+            return Err(CoreError::Database(
+                "PostgreSQL support is not built in; rebuild with `--features postgres`"
+                    .to_string(),
+            ));
+        }
+        Err(CoreError::Database(format!(
+            "Unsupported database URL '{}'; expected sqlite: or postgres://",
+            db_url
+        )))
+    }
+
+    /// Set the spaced-repetition algorithm used by `record_review`
+    pub fn with_algorithm(self, algorithm: Algorithm) -> Self {
+        match self {
+            Self::Sqlite(storage) => Self::Sqlite(storage.with_algorithm(algorithm)),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(storage) => Self::Postgres(storage.with_algorithm(algorithm)),
+        }
+    }
+
+    /// Number of schema migrations `new` would apply.
+    pub async fn pending_migrations(&self) -> Result<usize, CoreError> {
+        dispatch!(self, s => s.pending_migrations().await.map(|pending| pending.len()))
+    }
+
+    /// The SQLite storage, for what only SQLite supports such as backups.
+    pub fn as_sqlite(&self) -> Option<&SqliteStorage> {
+        match self {
+            Self::Sqlite(storage) => Some(storage),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(_) => None,
+        }
+    }
+}
+
+#[async_trait]
+impl StoragePort for AnyStorage {
+    async fn exists(&self, url: &str) -> Result<bool, CoreError> {
+        dispatch!(self, s => s.exists(url).await)
+    }
+
+    async fn save_article(&self, article: &Article) -> Result<(), CoreError> {
+        dispatch!(self, s => s.save_article(article).await)
+    }
+
+    async fn save_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
+        dispatch!(self, s => s.save_vocab(vocab).await)
+    }
+
+    async fn save_article_with_vocab(
+        &self,
+        article: &Article,
+        vocabularies: &[Vocabulary],
+    ) -> Result<(), CoreError> {
+        dispatch!(self, s => s.save_article_with_vocab(article, vocabularies).await)
+    }
+
+    async fn get_vocab_by_id(&self, id: i64) -> Result<Option<Vocabulary>, CoreError> {
+        dispatch!(self, s => s.get_vocab_by_id(id).await)
+    }
+
+    async fn get_vocab_by_word(&self, word: &str) -> Result<Option<Vocabulary>, CoreError> {
+        dispatch!(self, s => s.get_vocab_by_word(word).await)
+    }
+
+    async fn update_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
+        dispatch!(self, s => s.update_vocab(vocab).await)
+    }
+
+    async fn delete_vocab(&self, id: i64) -> Result<bool, CoreError> {
+        dispatch!(self, s => s.delete_vocab(id).await)
+    }
+
+    async fn merge_vocab(&self, from: i64, into: i64) -> Result<Vocabulary, CoreError> {
+        dispatch!(self, s => s.merge_vocab(from, into).await)
+    }
+
+    async fn query_vocab(&self, query: &VocabQuery) -> Result<VocabPage, CoreError> {
+        dispatch!(self, s => s.query_vocab(query).await)
+    }
+
+    async fn search_vocab(&self, query: &str) -> Result<Vec<Vocabulary>, CoreError> {
+        dispatch!(self, s => s.search_vocab(query).await)
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>, CoreError> {
+        dispatch!(self, s => s.search(query, limit).await)
+    }
+
+    async fn get_random_vocab(&self) -> Result<Option<Vocabulary>, CoreError> {
+        dispatch!(self, s => s.get_random_vocab().await)
+    }
+
+    async fn add_known_words(&self, words: &[String]) -> Result<usize, CoreError> {
+        dispatch!(self, s => s.add_known_words(words).await)
+    }
+
+    async fn remove_known_word(&self, word: &str) -> Result<bool, CoreError> {
+        dispatch!(self, s => s.remove_known_word(word).await)
+    }
+
+    async fn get_known_words(&self) -> Result<Vec<String>, CoreError> {
+        dispatch!(self, s => s.get_known_words().await)
+    }
+
+    async fn get_mastered_words(&self) -> Result<Vec<String>, CoreError> {
+        dispatch!(self, s => s.get_mastered_words().await)
+    }
+
+    async fn add_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError> {
+        dispatch!(self, s => s.add_tags(word_id, tags).await)
+    }
+
+    async fn remove_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError> {
+        dispatch!(self, s => s.remove_tags(word_id, tags).await)
+    }
+
+    async fn get_tags(&self) -> Result<Vec<(String, usize)>, CoreError> {
+        dispatch!(self, s => s.get_tags().await)
+    }
+
+    async fn save_deck(&self, deck: &Deck) -> Result<(), CoreError> {
+        dispatch!(self, s => s.save_deck(deck).await)
+    }
+
+    async fn delete_deck(&self, name: &str) -> Result<bool, CoreError> {
+        dispatch!(self, s => s.delete_deck(name).await)
+    }
+
+    async fn get_decks(&self) -> Result<Vec<Deck>, CoreError> {
+        dispatch!(self, s => s.get_decks().await)
+    }
+
+    async fn get_due_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError> {
+        dispatch!(self, s => s.get_due_vocab(limit, filter).await)
+    }
+
+    async fn get_new_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError> {
+        dispatch!(self, s => s.get_new_vocab(limit, filter).await)
+    }
+
+    async fn record_review(&self, word_id: i64, grade: Grade) -> Result<ReviewState, CoreError> {
+        dispatch!(self, s => s.record_review(word_id, grade).await)
+    }
+
+    async fn start_run(&self, run: &PipelineRun) -> Result<i64, CoreError> {
+        dispatch!(self, s => s.start_run(run).await)
+    }
+
+    async fn update_run(&self, run: &PipelineRun) -> Result<(), CoreError> {
+        dispatch!(self, s => s.update_run(run).await)
+    }
+
+    async fn get_run(&self, id: i64) -> Result<Option<PipelineRun>, CoreError> {
+        dispatch!(self, s => s.get_run(id).await)
+    }
+
+    async fn get_runs(&self, limit: Option<usize>) -> Result<Vec<PipelineRun>, CoreError> {
+        dispatch!(self, s => s.get_runs(limit).await)
+    }
+
+    async fn enqueue_items(&self, items: &[QueuedItem]) -> Result<usize, CoreError> {
+        dispatch!(self, s => s.enqueue_items(items).await)
+    }
+
+    async fn get_queued_items(&self) -> Result<Vec<QueuedItem>, CoreError> {
+        dispatch!(self, s => s.get_queued_items().await)
+    }
+
+    async fn dequeue_item(&self, url: &str) -> Result<bool, CoreError> {
+        dispatch!(self, s => s.dequeue_item(url).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_url_scheme_picks_storage() {
+        let storage = AnyStorage::new("sqlite::memory:").await.unwrap();
+        assert!(storage.as_sqlite().is_some());
+        assert!(storage.get_known_words().await.unwrap().is_empty());
+
+        assert!(AnyStorage::new("mysql://localhost/spread").await.is_err());
+        #[cfg(not(feature = "postgres"))]
+        assert!(matches!(
+            AnyStorage::new("postgres://localhost/spread").await,
+            Err(CoreError::Database(e)) if e.contains("--features postgres")
+        ));
+    }
+}
@@ -0,0 +1,362 @@
+//! Consistent copies of a database in use, and restoring them.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use spread_core::error::CoreError;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::SqlitePool;
+use tracing::info;
+
+use crate::migrations::latest_version;
+
+/// Backups kept by `spread backup` unless told otherwise
+pub const DEFAULT_KEEP_BACKUPS: usize = 7;
+
+const BACKUP_PREFIX: &str = "spread-";
+const BACKUP_SUFFIX: &str = ".db";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// Outcome of [`crate::SqliteStorage::backup`].
+#[derive(Debug)]
+pub struct BackupReport {
+    pub path: PathBuf,
+    /// Size of the backup file in bytes
+    pub size: u64,
+    /// Older backups deleted by rotation
+    pub removed: Vec<PathBuf>,
+}
+
+/// Outcome of [`crate::SqliteStorage::restore`].
+#[derive(Debug)]
+pub struct RestoreReport {
+    /// Schema version of the restored file; older versions are migrated on next open
+    pub schema_version: i64,
+    /// Copy of the database that was replaced, if it held any data
+    pub previous: Option<PathBuf>,
+}
+
+/// File behind the main database, `None` for an in-memory database.
+pub(crate) async fn database_path(pool: &SqlitePool) -> Result<Option<PathBuf>, CoreError> {
+    let (path,): (String,) =
+        sqlx::query_as("SELECT file FROM pragma_database_list WHERE name = 'main'")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+    Ok((!path.is_empty()).then(|| PathBuf::from(path)))
+}
+
+/// Whether the database has any tables besides the bookkeeping ones.
+pub(crate) async fn has_data(pool: &SqlitePool) -> Result<bool, CoreError> {
+    let (tables,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT IN ('schema_version', 'sqlite_sequence')",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    Ok(tables > 0)
+}
+
+/// Write a consistent snapshot of the database to a new file at `path`.
+///
+/// `VACUUM INTO` reads inside a transaction, so other connections may keep
+/// writing while the copy is taken.
+pub(crate) async fn vacuum_into(pool: &SqlitePool, path: &Path) -> Result<u64, CoreError> {
+    if path.exists() {
+        return Err(CoreError::Database(format!(
+            "{} already exists",
+            path.display()
+        )));
+    }
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().as_ref())
+        .execute(pool)
+        .await
+        .map_err(|e| CoreError::Database(format!("Failed to back up database: {}", e)))?;
+
+    let metadata = std::fs::metadata(path).map_err(|e| CoreError::Database(e.to_string()))?;
+    Ok(metadata.len())
+}
+
+/// Back up into `dir` as `spread-<timestamp>.db`, keeping the newest `keep` backups.
+pub(crate) async fn backup(
+    pool: &SqlitePool,
+    dir: &Path,
+    keep: usize,
+    now: DateTime<Utc>,
+) -> Result<BackupReport, CoreError> {
+    std::fs::create_dir_all(dir).map_err(|e| CoreError::Database(e.to_string()))?;
+    let path = dir.join(format!(
+        "{}{}{}",
+        BACKUP_PREFIX,
+        now.format(TIMESTAMP_FORMAT),
+        BACKUP_SUFFIX
+    ));
+    let size = vacuum_into(pool, &path).await?;
+    let removed = rotate(dir, keep.max(1))?;
+
+    info!(path = %path.display(), size, removed = removed.len(), "Backed up database");
+    Ok(BackupReport {
+        path,
+        size,
+        removed,
+    })
+}
+
+fn is_backup_name(name: &str) -> bool {
+    name.strip_prefix(BACKUP_PREFIX)
+        .and_then(|rest| rest.strip_suffix(BACKUP_SUFFIX))
+        .is_some_and(|stamp| stamp.len() == 14 && stamp.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Delete all but the newest `keep` backups in `dir`; other files are left alone.
+fn rotate(dir: &Path, keep: usize) -> Result<Vec<PathBuf>, CoreError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| CoreError::Database(e.to_string()))?;
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(is_backup_name)
+        })
+        .collect();
+    // Timestamps sort lexically; newest first
+    backups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let removed = backups.split_off(keep.min(backups.len()));
+    for path in &removed {
+        std::fs::remove_file(path).map_err(|e| CoreError::Database(e.to_string()))?;
+    }
+    Ok(removed)
+}
+
+/// Schema version of a backup file, after checking it is an intact spread
+/// database this build can open.
+pub async fn backup_schema_version(path: &Path) -> Result<i64, CoreError> {
+    if !path.is_file() {
+        return Err(CoreError::NotFound(path.display().to_string()));
+    }
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    let version = check_backup(&pool).await;
+    pool.close().await;
+
+    let version = version.map_err(|e| match e {
+        CoreError::Database(msg) => CoreError::Database(format!(
+            "{} is not a usable backup: {}",
+            path.display(),
+            msg
+        )),
+        e => e,
+    })?;
+    if version < 1 || version > latest_version() {
+        return Err(CoreError::Database(format!(
+            "{} has schema version {}; this build supports 1 to {}",
+            path.display(),
+            version,
+            latest_version()
+        )));
+    }
+    Ok(version)
+}
+
+async fn check_backup(pool: &SqlitePool) -> Result<i64, CoreError> {
+    let (check,): (String,) = sqlx::query_as("PRAGMA quick_check")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    if check != "ok" {
+        return Err(CoreError::Database(check));
+    }
+
+    let (versioned,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    if versioned == 0 {
+        return Err(CoreError::Database("no schema version".to_string()));
+    }
+
+    let (version,): (Option<i64>,) = sqlx::query_as("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Replace the database behind `pool` with the backup at `from`. The pool is
+/// closed, since its file is swapped out underneath it.
+pub(crate) async fn restore(
+    pool: &SqlitePool,
+    from: &Path,
+    now: DateTime<Utc>,
+) -> Result<RestoreReport, CoreError> {
+    let schema_version = backup_schema_version(from).await?;
+    let Some(live) = database_path(pool).await? else {
+        return Err(CoreError::Database(
+            "Cannot restore into an in-memory database".to_string(),
+        ));
+    };
+    let same_file = std::fs::canonicalize(from)
+        .and_then(|from| Ok(from == std::fs::canonicalize(&live)?))
+        .unwrap_or(false);
+    if same_file {
+        return Err(CoreError::Database(format!(
+            "{} is the live database",
+            from.display()
+        )));
+    }
+
+    let previous = if has_data(pool).await? {
+        let path = PathBuf::from(format!(
+            "{}.pre-restore-{}.bak",
+            live.display(),
+            now.format(TIMESTAMP_FORMAT)
+        ));
+        vacuum_into(pool, &path).await?;
+        Some(path)
+    } else {
+        None
+    };
+    pool.close().await;
+
+    // Copy next to the live file first so the swap itself is a rename
+    let staged = PathBuf::from(format!("{}.restoring", live.display()));
+    std::fs::copy(from, &staged).map_err(|e| CoreError::Database(e.to_string()))?;
+    std::fs::rename(&staged, &live).map_err(|e| CoreError::Database(e.to_string()))?;
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", live.display(), suffix));
+    }
+
+    info!(from = %from.display(), schema_version, "Restored database");
+    Ok(RestoreReport {
+        schema_version,
+        previous,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::save_word;
+    use crate::SqliteStorage;
+    use chrono::Duration;
+    use spread_core::port::StoragePort;
+
+    fn db_url(dir: &Path) -> String {
+        format!("sqlite:{}?mode=rwc", dir.join("spread.db").display())
+    }
+
+    #[tokio::test]
+    async fn test_backup_rotation_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SqliteStorage::new(&db_url(dir.path())).await.unwrap();
+        save_word(&storage, "lucid").await;
+
+        let backups = dir.path().join("backups");
+        std::fs::create_dir_all(&backups).unwrap();
+        std::fs::write(backups.join("notes.txt"), "keep me").unwrap();
+
+        let start = Utc::now();
+        let mut reports = Vec::new();
+        for hours in 0..3 {
+            let now = start + Duration::hours(hours);
+            reports.push(backup(&storage.pool, &backups, 2, now).await.unwrap());
+        }
+        assert!(reports[0].removed.is_empty());
+        assert!(reports[0].size > 0);
+        assert_eq!(reports[2].removed, [reports[0].path.clone()]);
+
+        assert!(!reports[0].path.exists());
+        assert!(reports[1].path.exists());
+        assert!(reports[2].path.exists());
+        assert!(backups.join("notes.txt").exists());
+
+        // Same timestamp twice is refused rather than overwritten
+        let now = start + Duration::hours(2);
+        assert!(backup(&storage.pool, &backups, 2, now).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_backup_schema_version_rejects_unusable_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SqliteStorage::new(&db_url(dir.path())).await.unwrap();
+        let good = dir.path().join("good.db");
+        vacuum_into(&storage.pool, &good).await.unwrap();
+        assert_eq!(
+            backup_schema_version(&good).await.unwrap(),
+            latest_version()
+        );
+
+        let missing = dir.path().join("missing.db");
+        assert!(matches!(
+            backup_schema_version(&missing).await,
+            Err(CoreError::NotFound(_))
+        ));
+
+        let text = dir.path().join("text.db");
+        std::fs::write(&text, "not a database at all").unwrap();
+        assert!(backup_schema_version(&text).await.is_err());
+
+        let newer = dir.path().join("newer.db");
+        vacuum_into(&storage.pool, &newer).await.unwrap();
+        let newer_storage = SqliteStorage::connect(&format!("sqlite:{}", newer.display()))
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO schema_version (version, name, applied_at) VALUES (999, 'future', CURRENT_TIMESTAMP)")
+            .execute(&newer_storage.pool)
+            .await
+            .unwrap();
+        newer_storage.pool.close().await;
+        assert!(backup_schema_version(&newer).await.is_err());
+
+        let unversioned = SqliteStorage::connect(&format!(
+            "sqlite:{}?mode=rwc",
+            dir.path().join("other.db").display()
+        ))
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE notes (body TEXT)")
+            .execute(&unversioned.pool)
+            .await
+            .unwrap();
+        unversioned.pool.close().await;
+        assert!(backup_schema_version(&dir.path().join("other.db"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restore_replaces_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = db_url(dir.path());
+        let storage = SqliteStorage::new(&url).await.unwrap();
+        save_word(&storage, "lucid").await;
+        let snapshot = dir.path().join("snapshot.db");
+        vacuum_into(&storage.pool, &snapshot).await.unwrap();
+        save_word(&storage, "terse").await;
+
+        let report = storage.restore(&snapshot).await.unwrap();
+        assert_eq!(report.schema_version, latest_version());
+
+        let storage = SqliteStorage::new(&url).await.unwrap();
+        assert!(storage.get_vocab_by_word("lucid").await.unwrap().is_some());
+        assert!(storage.get_vocab_by_word("terse").await.unwrap().is_none());
+
+        // The replaced database was kept
+        let previous = report.previous.unwrap();
+        let previous = SqliteStorage::connect(&format!("sqlite:{}", previous.display()))
+            .await
+            .unwrap();
+        assert!(previous.get_vocab_by_word("terse").await.unwrap().is_some());
+
+        // Restoring the live file onto itself is refused
+        let live = dir.path().join("spread.db");
+        assert!(storage.restore(&live).await.is_err());
+    }
+}
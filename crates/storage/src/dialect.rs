@@ -0,0 +1,44 @@
+//! SQL that differs between the supported databases, so query builders can
+//! be shared by every storage.
+
+use sqlx::{Database, Sqlite};
+
+pub(crate) trait Dialect: Database {
+    /// Opening and closing text wrapped around a timestamp so timestamps
+    /// compare as instants rather than as text.
+    const TIMESTAMP: (&'static str, &'static str);
+    /// The current time, comparable with wrapped timestamps.
+    const NOW: &'static str;
+    /// `LIMIT` value that returns every row.
+    const NO_LIMIT: Option<i64>;
+
+    /// `expr` wrapped so it compares as an instant; see [`Dialect::TIMESTAMP`].
+    fn timestamp(expr: &str) -> String {
+        format!("{}{}{}", Self::TIMESTAMP.0, expr, Self::TIMESTAMP.1)
+    }
+
+    /// Aggregate of `expr` into a JSON array, as text.
+    fn json_array(expr: &str) -> String;
+}
+
+impl Dialect for Sqlite {
+    // Timestamps are stored as text, not always in the same format
+    const TIMESTAMP: (&'static str, &'static str) = ("julianday(", ")");
+    const NOW: &'static str = "julianday('now')";
+    const NO_LIMIT: Option<i64> = Some(-1);
+
+    fn json_array(expr: &str) -> String {
+        format!("json_group_array({})", expr)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl Dialect for sqlx::Postgres {
+    const TIMESTAMP: (&'static str, &'static str) = ("", "");
+    const NOW: &'static str = "now()";
+    const NO_LIMIT: Option<i64> = None;
+
+    fn json_array(expr: &str) -> String {
+        format!("json_agg({})::text", expr)
+    }
+}
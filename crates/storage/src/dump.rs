@@ -0,0 +1,1042 @@
+//! Portable JSON/NDJSON documents of the whole dataset.
+//!
+//! Dumps carry no row ids: words are keyed by lemma and articles by URL, so a
+//! dump can be loaded into any database, including one that already has data.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use spread_core::error::CoreError;
+use spread_core::model::{Deck, Occurrence};
+use spread_core::srs::ReviewState;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::{
+    canonical_lemma, decode_list, encode_list, insert_tag, insert_tags, ReviewStateRow,
+    REVIEW_STATE_COLUMNS,
+};
+
+/// Value of [`Dump::format`]
+pub const DUMP_FORMAT: &str = "spread-dump";
+/// Document version written by this build; older versions can still be loaded
+pub const DUMP_VERSION: u32 = 1;
+
+/// Serialization of a [`Dump`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// One JSON document
+    Json,
+    /// A header line, then one record per line
+    Ndjson,
+}
+
+impl FromStr for DumpFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            other => Err(format!(
+                "Unknown dump format '{}' (expected json or ndjson)",
+                other
+            )),
+        }
+    }
+}
+
+/// What loading does with a word or article that is already stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// Keep the stored one untouched
+    Skip,
+    /// Replace it, including a word's contexts, tags and review history
+    Overwrite,
+    /// Keep the stored fields, adding contexts, tags, aliases and reviews it lacks
+    Merge,
+}
+
+impl FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "merge" => Ok(Self::Merge),
+            other => Err(format!(
+                "Unknown conflict strategy '{}' (expected skip, overwrite or merge)",
+                other
+            )),
+        }
+    }
+}
+
+/// Everything in the database, as written by `spread dump`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dump {
+    pub format: String,
+    pub version: u32,
+    /// Feeds the articles were collected from
+    #[serde(default)]
+    pub feeds: Vec<String>,
+    #[serde(default)]
+    pub articles: Vec<DumpArticle>,
+    #[serde(default)]
+    pub pruned_articles: Vec<PrunedArticle>,
+    #[serde(default)]
+    pub words: Vec<DumpWord>,
+    #[serde(default)]
+    pub known_words: Vec<KnownWord>,
+    #[serde(default)]
+    pub decks: Vec<Deck>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DumpArticle {
+    pub url: String,
+    pub title: String,
+    pub content: String,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed_url: Option<String>,
+    pub published_at: DateTime<Utc>,
+    pub collected_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compacted_at: Option<DateTime<Utc>>,
+}
+
+/// Fingerprint left by a pruned article
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrunedArticle {
+    pub url_hash: String,
+    pub pruned_at: DateTime<Utc>,
+}
+
+/// A word with its contexts, tags and review history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DumpWord {
+    pub lemma: String,
+    pub word: String,
+    pub definition: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part_of_speech: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipa: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cefr_level: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub antonyms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collocations: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example_sentence: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edited_fields: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub suspended: bool,
+    pub created_at: DateTime<Utc>,
+    /// Lemmas of words merged into this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub occurrences: Vec<Occurrence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<ReviewState>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub review_log: Vec<ReviewLogEntry>,
+}
+
+/// One recorded review
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewLogEntry {
+    /// 1 (Again) to 4 (Easy)
+    pub grade: u8,
+    pub algorithm: String,
+    pub reviewed_at: DateTime<Utc>,
+    pub interval_days: u32,
+    pub ease: f64,
+    pub stability: f64,
+    pub difficulty: f64,
+    pub due_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownWord {
+    pub word: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// One NDJSON line
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record {
+    Header { format: String, version: u32 },
+    Feed { url: String },
+    Article(DumpArticle),
+    PrunedArticle(PrunedArticle),
+    Word(Box<DumpWord>),
+    KnownWord(KnownWord),
+    Deck(Deck),
+}
+
+impl Default for Dump {
+    fn default() -> Self {
+        Self {
+            format: DUMP_FORMAT.to_string(),
+            version: DUMP_VERSION,
+            feeds: Vec::new(),
+            articles: Vec::new(),
+            pruned_articles: Vec::new(),
+            words: Vec::new(),
+            known_words: Vec::new(),
+            decks: Vec::new(),
+        }
+    }
+}
+
+impl Dump {
+    pub fn to_string(&self, format: DumpFormat) -> Result<String, CoreError> {
+        match format {
+            DumpFormat::Json => {
+                serde_json::to_string_pretty(self).map_err(|e| CoreError::Parse(e.to_string()))
+            }
+            DumpFormat::Ndjson => {
+                let records = std::iter::once(Record::Header {
+                    format: self.format.clone(),
+                    version: self.version,
+                })
+                .chain(
+                    self.feeds
+                        .iter()
+                        .map(|url| Record::Feed { url: url.clone() }),
+                )
+                .chain(self.articles.iter().cloned().map(Record::Article))
+                .chain(
+                    self.pruned_articles
+                        .iter()
+                        .cloned()
+                        .map(Record::PrunedArticle),
+                )
+                .chain(
+                    self.words
+                        .iter()
+                        .map(|word| Record::Word(Box::new(word.clone()))),
+                )
+                .chain(self.known_words.iter().cloned().map(Record::KnownWord))
+                .chain(self.decks.iter().cloned().map(Record::Deck));
+
+                let mut text = String::new();
+                for record in records {
+                    text.push_str(
+                        &serde_json::to_string(&record)
+                            .map_err(|e| CoreError::Parse(e.to_string()))?,
+                    );
+                    text.push('\n');
+                }
+                Ok(text)
+            }
+        }
+    }
+
+    /// Parse a dump in either format, rejecting versions newer than this build.
+    pub fn parse(text: &str) -> Result<Self, CoreError> {
+        let first_line = text.lines().find(|line| !line.trim().is_empty());
+        let is_ndjson = first_line
+            .is_some_and(|line| matches!(serde_json::from_str(line), Ok(Record::Header { .. })));
+
+        let dump = if is_ndjson {
+            parse_ndjson(text)?
+        } else {
+            serde_json::from_str(text).map_err(|e| CoreError::Parse(e.to_string()))?
+        };
+
+        if dump.format != DUMP_FORMAT {
+            return Err(CoreError::Parse(format!(
+                "Not a spread dump (format '{}')",
+                dump.format
+            )));
+        }
+        if dump.version > DUMP_VERSION {
+            return Err(CoreError::Parse(format!(
+                "Dump version {} is newer than this build supports ({})",
+                dump.version, DUMP_VERSION
+            )));
+        }
+        Ok(dump)
+    }
+}
+
+fn parse_ndjson(text: &str) -> Result<Dump, CoreError> {
+    let mut dump = Dump::default();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Record = serde_json::from_str(line)
+            .map_err(|e| CoreError::Parse(format!("Line {}: {}", number + 1, e)))?;
+        match record {
+            Record::Header { format, version } => {
+                dump.format = format;
+                dump.version = version;
+            }
+            Record::Feed { url } => dump.feeds.push(url),
+            Record::Article(article) => dump.articles.push(article),
+            Record::PrunedArticle(pruned) => dump.pruned_articles.push(pruned),
+            Record::Word(word) => dump.words.push(*word),
+            Record::KnownWord(known) => dump.known_words.push(known),
+            Record::Deck(deck) => dump.decks.push(deck),
+        }
+    }
+    Ok(dump)
+}
+
+/// Outcome of [`crate::SqliteStorage::load`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    pub articles_added: usize,
+    pub words_added: usize,
+    /// Stored words overwritten or merged into
+    pub words_updated: usize,
+    pub words_skipped: usize,
+    pub occurrences_added: usize,
+}
+
+#[derive(sqlx::FromRow)]
+struct ArticleRow {
+    url: String,
+    title: String,
+    content: String,
+    source: String,
+    feed_url: Option<String>,
+    published_at: DateTime<Utc>,
+    collected_at: DateTime<Utc>,
+    compacted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct WordRow {
+    id: i64,
+    lemma: String,
+    word: String,
+    definition: String,
+    part_of_speech: Option<String>,
+    ipa: Option<String>,
+    cefr_level: Option<String>,
+    synonyms: Option<String>,
+    antonyms: Option<String>,
+    collocations: Option<String>,
+    example_sentence: Option<String>,
+    edited_fields: Option<String>,
+    suspended: bool,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ReviewLogRow {
+    word_id: i64,
+    grade: i64,
+    algorithm: String,
+    reviewed_at: DateTime<Utc>,
+    interval_days: i64,
+    ease: f64,
+    stability: f64,
+    difficulty: f64,
+    due_at: DateTime<Utc>,
+}
+
+impl From<ReviewLogRow> for ReviewLogEntry {
+    fn from(row: ReviewLogRow) -> Self {
+        Self {
+            grade: row.grade as u8,
+            algorithm: row.algorithm,
+            reviewed_at: row.reviewed_at,
+            interval_days: row.interval_days as u32,
+            ease: row.ease,
+            stability: row.stability,
+            difficulty: row.difficulty,
+            due_at: row.due_at,
+        }
+    }
+}
+
+/// Group `(word_id, item)` rows by word, keeping their order.
+fn by_word<T>(rows: Vec<(i64, T)>) -> HashMap<i64, Vec<T>> {
+    let mut grouped: HashMap<i64, Vec<T>> = HashMap::new();
+    for (word_id, item) in rows {
+        grouped.entry(word_id).or_default().push(item);
+    }
+    grouped
+}
+
+/// Read the whole dataset in one transaction, in a stable order.
+pub(crate) async fn export(pool: &SqlitePool) -> Result<Dump, CoreError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let feeds: Vec<(String,)> = sqlx::query_as(
+        "SELECT DISTINCT feed_url FROM articles WHERE feed_url IS NOT NULL ORDER BY feed_url",
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let articles: Vec<ArticleRow> = sqlx::query_as(
+        r#"
+        SELECT url, title, content, source, feed_url, published_at, collected_at, compacted_at
+        FROM articles ORDER BY url
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let pruned_articles: Vec<(String, DateTime<Utc>)> =
+        sqlx::query_as("SELECT url_hash, pruned_at FROM pruned_articles ORDER BY url_hash")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let words: Vec<WordRow> = sqlx::query_as(
+        r#"
+        SELECT id, lemma, word, definition, part_of_speech, ipa, cefr_level,
+               synonyms, antonyms, collocations, example_sentence, edited_fields, suspended, created_at
+        FROM words ORDER BY lemma
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let aliases: Vec<(i64, String)> =
+        sqlx::query_as("SELECT word_id, lemma FROM word_aliases ORDER BY word_id, lemma")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+    let mut aliases = by_word(aliases);
+
+    let tags: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT wt.word_id, t.name FROM word_tags wt JOIN tags t ON t.id = wt.tag_id ORDER BY wt.word_id, t.name",
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    let mut tags = by_word(tags);
+
+    let occurrences: Vec<(i64, String, String, String, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        SELECT word_id, article_url, context_sentence, definition, extracted_at
+        FROM occurrences ORDER BY word_id, extracted_at, id
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    let mut occurrences = by_word(
+        occurrences
+            .into_iter()
+            .map(
+                |(word_id, source_url, context_sentence, definition, extracted_at)| {
+                    (
+                        word_id,
+                        Occurrence {
+                            source_url,
+                            context_sentence,
+                            definition,
+                            extracted_at,
+                        },
+                    )
+                },
+            )
+            .collect(),
+    );
+
+    let states: Vec<ReviewStateRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM review_states",
+        REVIEW_STATE_COLUMNS
+    ))
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    let mut states: HashMap<i64, ReviewState> = states
+        .into_iter()
+        .map(|row| (row.word_id, ReviewState::from(row)))
+        .collect();
+
+    let log: Vec<ReviewLogRow> = sqlx::query_as(
+        r#"
+        SELECT word_id, grade, algorithm, reviewed_at, interval_days, ease, stability, difficulty, due_at
+        FROM review_log ORDER BY word_id, reviewed_at, id
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    let mut log = by_word(
+        log.into_iter()
+            .map(|row| (row.word_id, ReviewLogEntry::from(row)))
+            .collect(),
+    );
+
+    let known_words: Vec<(String, DateTime<Utc>)> =
+        sqlx::query_as("SELECT lemma, added_at FROM known_words ORDER BY lemma")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let deck_tags: Vec<(String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT d.name, t.name FROM decks d
+        LEFT JOIN deck_tags dt ON dt.deck_id = d.id
+        LEFT JOIN tags t ON t.id = dt.tag_id
+        ORDER BY d.name, t.name
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    let mut decks: Vec<Deck> = Vec::new();
+    for (name, tag) in deck_tags {
+        if decks.last().is_none_or(|deck| deck.name != name) {
+            decks.push(Deck {
+                name,
+                tags: Vec::new(),
+            });
+        }
+        if let (Some(deck), Some(tag)) = (decks.last_mut(), tag) {
+            deck.tags.push(tag);
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(Dump {
+        feeds: feeds.into_iter().map(|(url,)| url).collect(),
+        articles: articles
+            .into_iter()
+            .map(|row| DumpArticle {
+                url: row.url,
+                title: row.title,
+                content: row.content,
+                source: row.source,
+                feed_url: row.feed_url,
+                published_at: row.published_at,
+                collected_at: row.collected_at,
+                compacted_at: row.compacted_at,
+            })
+            .collect(),
+        pruned_articles: pruned_articles
+            .into_iter()
+            .map(|(url_hash, pruned_at)| PrunedArticle {
+                url_hash,
+                pruned_at,
+            })
+            .collect(),
+        words: words
+            .into_iter()
+            .map(|row| DumpWord {
+                lemma: row.lemma,
+                word: row.word,
+                definition: row.definition,
+                part_of_speech: row.part_of_speech,
+                ipa: row.ipa,
+                cefr_level: row.cefr_level,
+                synonyms: decode_list(row.synonyms),
+                antonyms: decode_list(row.antonyms),
+                collocations: decode_list(row.collocations),
+                example_sentence: row.example_sentence,
+                edited_fields: decode_list(row.edited_fields),
+                suspended: row.suspended,
+                created_at: row.created_at,
+                aliases: aliases.remove(&row.id).unwrap_or_default(),
+                tags: tags.remove(&row.id).unwrap_or_default(),
+                occurrences: occurrences.remove(&row.id).unwrap_or_default(),
+                review: states.remove(&row.id),
+                review_log: log.remove(&row.id).unwrap_or_default(),
+            })
+            .collect(),
+        known_words: known_words
+            .into_iter()
+            .map(|(word, added_at)| KnownWord { word, added_at })
+            .collect(),
+        decks,
+        ..Dump::default()
+    })
+}
+
+/// Load a dump in one transaction; nothing is written if any part fails.
+pub(crate) async fn import(
+    pool: &SqlitePool,
+    dump: &Dump,
+    strategy: ConflictStrategy,
+) -> Result<LoadReport, CoreError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    let mut report = LoadReport::default();
+
+    for article in &dump.articles {
+        report.articles_added += import_article(&mut tx, article, strategy).await?;
+    }
+    for pruned in &dump.pruned_articles {
+        sqlx::query("INSERT OR IGNORE INTO pruned_articles (url_hash, pruned_at) VALUES (?, ?)")
+            .bind(&pruned.url_hash)
+            .bind(pruned.pruned_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+    }
+    for word in &dump.words {
+        import_word(&mut tx, word, strategy, &mut report).await?;
+    }
+    for known in &dump.known_words {
+        sqlx::query("INSERT OR IGNORE INTO known_words (lemma, added_at) VALUES (?, ?)")
+            .bind(&known.word)
+            .bind(known.added_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+    }
+    for deck in &dump.decks {
+        import_deck(&mut tx, deck, strategy).await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(report)
+}
+
+/// Returns 1 if the article was new.
+async fn import_article(
+    conn: &mut SqliteConnection,
+    article: &DumpArticle,
+    strategy: ConflictStrategy,
+) -> Result<usize, CoreError> {
+    let conflict = match strategy {
+        ConflictStrategy::Overwrite => {
+            r#"
+            ON CONFLICT(url) DO UPDATE SET
+                title = excluded.title, content = excluded.content, source = excluded.source,
+                feed_url = excluded.feed_url, published_at = excluded.published_at,
+                collected_at = excluded.collected_at, compacted_at = excluded.compacted_at
+            "#
+        }
+        ConflictStrategy::Skip | ConflictStrategy::Merge => "ON CONFLICT(url) DO NOTHING",
+    };
+    let (existing,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM articles WHERE url = ?")
+        .bind(&article.url)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    sqlx::query(&format!(
+        r#"
+        INSERT INTO articles (url, title, content, source, feed_url, published_at, collected_at, compacted_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        {}
+        "#,
+        conflict
+    ))
+    .bind(&article.url)
+    .bind(&article.title)
+    .bind(&article.content)
+    .bind(&article.source)
+    .bind(&article.feed_url)
+    .bind(article.published_at)
+    .bind(article.collected_at)
+    .bind(article.compacted_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(usize::from(existing == 0))
+}
+
+async fn import_word(
+    conn: &mut SqliteConnection,
+    word: &DumpWord,
+    strategy: ConflictStrategy,
+    report: &mut LoadReport,
+) -> Result<(), CoreError> {
+    let lemma = canonical_lemma(conn, &word.lemma).await?;
+    let existing: Option<(i64,)> = sqlx::query_as("SELECT id FROM words WHERE lemma = ?")
+        .bind(&lemma)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let word_id = match (existing, strategy) {
+        (None, _) => {
+            let (id,): (i64,) = sqlx::query_as(
+                r#"
+                INSERT INTO words (
+                    lemma, word, definition,
+                    part_of_speech, ipa, cefr_level, synonyms, antonyms, collocations, example_sentence,
+                    edited_fields, suspended, created_at
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                RETURNING id
+                "#,
+            )
+            .bind(&lemma)
+            .bind(&word.word)
+            .bind(&word.definition)
+            .bind(&word.part_of_speech)
+            .bind(&word.ipa)
+            .bind(&word.cefr_level)
+            .bind(encode_list(&word.synonyms))
+            .bind(encode_list(&word.antonyms))
+            .bind(encode_list(&word.collocations))
+            .bind(&word.example_sentence)
+            .bind(encode_list(&word.edited_fields))
+            .bind(word.suspended)
+            .bind(word.created_at)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+            report.words_added += 1;
+            id
+        }
+        (Some(_), ConflictStrategy::Skip) => {
+            report.words_skipped += 1;
+            return Ok(());
+        }
+        (Some((id,)), ConflictStrategy::Overwrite) => {
+            sqlx::query(
+                r#"
+                UPDATE words SET
+                    word = ?, definition = ?,
+                    part_of_speech = ?, ipa = ?, cefr_level = ?,
+                    synonyms = ?, antonyms = ?, collocations = ?, example_sentence = ?,
+                    edited_fields = ?, suspended = ?, created_at = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&word.word)
+            .bind(&word.definition)
+            .bind(&word.part_of_speech)
+            .bind(&word.ipa)
+            .bind(&word.cefr_level)
+            .bind(encode_list(&word.synonyms))
+            .bind(encode_list(&word.antonyms))
+            .bind(encode_list(&word.collocations))
+            .bind(&word.example_sentence)
+            .bind(encode_list(&word.edited_fields))
+            .bind(word.suspended)
+            .bind(word.created_at)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+            for table in ["word_tags", "review_states", "review_log", "occurrences"] {
+                sqlx::query(&format!("DELETE FROM {} WHERE word_id = ?", table))
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| CoreError::Database(e.to_string()))?;
+            }
+            report.words_updated += 1;
+            id
+        }
+        (Some((id,)), ConflictStrategy::Merge) => {
+            report.words_updated += 1;
+            id
+        }
+    };
+
+    for alias in &word.aliases {
+        sqlx::query("INSERT OR IGNORE INTO word_aliases (lemma, word_id) VALUES (?, ?)")
+            .bind(alias)
+            .bind(word_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+    }
+    insert_tags(conn, word_id, &word.tags).await?;
+
+    for occurrence in &word.occurrences {
+        report.occurrences_added += sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO occurrences (word_id, article_url, context_sentence, definition, extracted_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(word_id)
+        .bind(&occurrence.source_url)
+        .bind(&occurrence.context_sentence)
+        .bind(&occurrence.definition)
+        .bind(occurrence.extracted_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(format!("Word '{}': {}", word.word, e)))?
+        .rows_affected() as usize;
+    }
+
+    if let Some(review) = &word.review {
+        // When merging, the more recently reviewed state wins
+        let stored: Option<(Option<DateTime<Utc>>,)> =
+            sqlx::query_as("SELECT last_reviewed_at FROM review_states WHERE word_id = ?")
+                .bind(word_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        if stored.is_none_or(|(last,)| review.last_reviewed_at > last) {
+            sqlx::query(&format!(
+                "INSERT OR REPLACE INTO review_states ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                REVIEW_STATE_COLUMNS
+            ))
+            .bind(word_id)
+            .bind(review.reps as i64)
+            .bind(review.lapses as i64)
+            .bind(review.ease)
+            .bind(review.interval_days as i64)
+            .bind(review.stability)
+            .bind(review.difficulty)
+            .bind(review.due_at)
+            .bind(review.last_reviewed_at)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+    }
+
+    for entry in &word.review_log {
+        sqlx::query(
+            r#"
+            INSERT INTO review_log (
+                word_id, grade, algorithm, reviewed_at,
+                interval_days, ease, stability, difficulty, due_at
+            )
+            SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9
+            WHERE NOT EXISTS (SELECT 1 FROM review_log WHERE word_id = ?1 AND reviewed_at = ?4)
+            "#,
+        )
+        .bind(word_id)
+        .bind(entry.grade as i64)
+        .bind(&entry.algorithm)
+        .bind(entry.reviewed_at)
+        .bind(entry.interval_days as i64)
+        .bind(entry.ease)
+        .bind(entry.stability)
+        .bind(entry.difficulty)
+        .bind(entry.due_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+async fn import_deck(
+    conn: &mut SqliteConnection,
+    deck: &Deck,
+    strategy: ConflictStrategy,
+) -> Result<(), CoreError> {
+    let existing: Option<(i64,)> = sqlx::query_as("SELECT id FROM decks WHERE name = ?")
+        .bind(&deck.name)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let deck_id = match (existing, strategy) {
+        (None, _) => {
+            let (id,): (i64,) = sqlx::query_as("INSERT INTO decks (name) VALUES (?) RETURNING id")
+                .bind(&deck.name)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+            id
+        }
+        (Some(_), ConflictStrategy::Skip) => return Ok(()),
+        (Some((id,)), ConflictStrategy::Overwrite) => {
+            sqlx::query("DELETE FROM deck_tags WHERE deck_id = ?")
+                .bind(id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+            id
+        }
+        (Some((id,)), ConflictStrategy::Merge) => id,
+    };
+
+    for tag in &deck.tags {
+        let tag_id = insert_tag(conn, tag).await?;
+        sqlx::query("INSERT OR IGNORE INTO deck_tags (deck_id, tag_id) VALUES (?, ?)")
+            .bind(deck_id)
+            .bind(tag_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Fixture;
+    use crate::SqliteStorage;
+    use chrono::Duration;
+    use spread_core::port::StoragePort;
+    use spread_core::srs::Grade;
+
+    /// `word` seen at `url` in an article from the sample feed
+    fn sighting(word: &str, url: &str, tags: &[&str]) -> Fixture {
+        Fixture::new(url, &[word])
+            .with_feed("https://example.com/feed.xml")
+            .with_collected_at(Utc::now() - Duration::days(1))
+            .with_synonyms(&["clear"])
+            .with_tags(tags)
+    }
+
+    /// A database with every kind of record a dump holds
+    async fn sample_storage() -> SqliteStorage {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        let lucid = sighting("lucid", "https://example.com/a", &["toefl"])
+            .save(&storage)
+            .await[0];
+        sighting("lucid", "https://example.com/b", &[])
+            .save(&storage)
+            .await;
+        sighting("analyse", "https://example.com/b", &[])
+            .save(&storage)
+            .await;
+        let analyze = sighting("analyze", "https://example.com/c", &["science"])
+            .save(&storage)
+            .await[0];
+        let from = storage.get_vocab_by_word("analyse").await.unwrap().unwrap();
+        storage
+            .merge_vocab(from.id.unwrap(), analyze)
+            .await
+            .unwrap();
+
+        storage.record_review(lucid, Grade::Good).await.unwrap();
+        storage.record_review(lucid, Grade::Hard).await.unwrap();
+        storage
+            .add_known_words(&["the".to_string(), "essay".to_string()])
+            .await
+            .unwrap();
+        storage
+            .save_deck(&Deck {
+                name: "exam".to_string(),
+                tags: vec!["toefl".to_string(), "science".to_string()],
+            })
+            .await
+            .unwrap();
+        storage
+    }
+
+    #[tokio::test]
+    async fn test_dump_load_dump_is_identical() {
+        let source = sample_storage().await;
+        let dump = source.dump().await.unwrap();
+        assert_eq!(dump.feeds, ["https://example.com/feed.xml"]);
+        assert_eq!(dump.articles.len(), 3);
+        assert_eq!(dump.words.len(), 2);
+        let analyze = &dump.words[0];
+        assert_eq!(analyze.aliases, ["analyse"]);
+        assert_eq!(analyze.occurrences.len(), 2);
+        let lucid = &dump.words[1];
+        assert_eq!(lucid.review_log.len(), 2);
+        assert!(lucid.review.is_some());
+
+        for format in [DumpFormat::Json, DumpFormat::Ndjson] {
+            let text = dump.to_string(format).unwrap();
+            let parsed = Dump::parse(&text).unwrap();
+            assert_eq!(parsed, dump);
+
+            let target = SqliteStorage::new("sqlite::memory:").await.unwrap();
+            let report = target.load(&parsed, ConflictStrategy::Skip).await.unwrap();
+            assert_eq!(report.articles_added, 3);
+            assert_eq!(report.words_added, 2);
+            assert_eq!(report.occurrences_added, 4);
+
+            let reloaded = target.dump().await.unwrap();
+            assert_eq!(reloaded, dump);
+            assert_eq!(reloaded.to_string(format).unwrap(), text);
+
+            // Loaded words work like any other
+            let found = target.get_vocab_by_word("analyse").await.unwrap().unwrap();
+            assert_eq!(found.word, "analyze");
+            let hits = target.search_vocab("lucid").await.unwrap();
+            assert_eq!(hits.len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_conflict_strategies() {
+        let dump = sample_storage().await.dump().await.unwrap();
+
+        let local = || async {
+            let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+            let id = sighting("lucid", "https://example.com/z", &["own"])
+                .save(&storage)
+                .await[0];
+            let mut vocab = storage.get_vocab_by_id(id).await.unwrap().unwrap();
+            vocab.definition = "My own definition".to_string();
+            storage.update_vocab(&vocab).await.unwrap();
+            storage
+        };
+
+        let storage = local().await;
+        let report = storage.load(&dump, ConflictStrategy::Skip).await.unwrap();
+        assert_eq!(report.words_skipped, 1);
+        assert_eq!(report.words_added, 1);
+        let lucid = storage.get_vocab_by_word("lucid").await.unwrap().unwrap();
+        assert_eq!(lucid.definition, "My own definition");
+        assert_eq!(lucid.occurrences.len(), 1);
+
+        let storage = local().await;
+        let report = storage.load(&dump, ConflictStrategy::Merge).await.unwrap();
+        assert_eq!(report.words_updated, 1);
+        let lucid = storage.get_vocab_by_word("lucid").await.unwrap().unwrap();
+        assert_eq!(lucid.definition, "My own definition");
+        assert_eq!(lucid.occurrences.len(), 3);
+        assert_eq!(lucid.tags, ["own", "toefl"]);
+        let merged = storage.dump().await.unwrap();
+        assert_eq!(merged.words[1].review_log.len(), 2);
+        // Merging the same dump again adds nothing
+        let report = storage.load(&dump, ConflictStrategy::Merge).await.unwrap();
+        assert_eq!(report.occurrences_added, 0);
+        assert_eq!(storage.dump().await.unwrap(), merged);
+
+        let storage = local().await;
+        storage
+            .load(&dump, ConflictStrategy::Overwrite)
+            .await
+            .unwrap();
+        let lucid = storage.get_vocab_by_word("lucid").await.unwrap().unwrap();
+        assert_eq!(lucid.definition, "Definition of lucid");
+        assert_eq!(lucid.occurrences.len(), 2);
+        assert_eq!(lucid.tags, ["toefl"]);
+    }
+
+    #[test]
+    fn test_parse_rejects_foreign_and_newer_documents() {
+        assert!(Dump::parse(r#"{"format": "other", "version": 1}"#).is_err());
+        let newer = format!(
+            "{{\"type\":\"header\",\"format\":\"{}\",\"version\":{}}}\n",
+            DUMP_FORMAT,
+            DUMP_VERSION + 1
+        );
+        assert!(Dump::parse(&newer).is_err());
+        assert!(Dump::parse("not json").is_err());
+
+        let empty = Dump::parse(&Dump::default().to_string(DumpFormat::Ndjson).unwrap()).unwrap();
+        assert_eq!(empty, Dump::default());
+    }
+}
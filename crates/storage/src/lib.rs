@@ -1,110 +1,1328 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use spread_core::error::CoreError;
-use spread_core::model::{Article, Vocabulary};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use spread_core::model::{
+    Article, Deck, Occurrence, ReviewCard, SearchHit, Vocabulary, SNIPPET_HIGHLIGHT,
+};
 use spread_core::port::StoragePort;
-use sqlx::SqlitePool;
-
-const CREATE_ARTICLES_TABLE: &str = r#"
-CREATE TABLE IF NOT EXISTS articles (
-    url TEXT PRIMARY KEY,
-    title TEXT NOT NULL,
-    content TEXT NOT NULL,
-    source TEXT NOT NULL,
-    published_at DATETIME NOT NULL,
-    collected_at DATETIME NOT NULL
-)
-"#;
-
-const CREATE_VOCABULARIES_TABLE: &str = r#"
-CREATE TABLE IF NOT EXISTS vocabularies (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    word TEXT NOT NULL,
-    definition TEXT NOT NULL,
-    context_sentence TEXT NOT NULL,
-    source_url TEXT NOT NULL,
-    FOREIGN KEY (source_url) REFERENCES articles(url)
-)
-"#;
+use spread_core::query::{ReviewStatus, TagFilter, VocabPage, VocabQuery, VocabSort};
+use spread_core::run::{PipelineRun, QueuedItem};
+use spread_core::srs::{Algorithm, Grade, ReviewState, MASTERED_INTERVAL_DAYS};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Database, Encode, QueryBuilder, Sqlite, SqliteConnection, SqlitePool, Type};
+
+use dialect::Dialect;
+
+mod any;
+mod backup;
+mod dialect;
+mod dump;
+mod memory;
+mod migrations;
+mod porter;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod retention;
+mod runs;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+pub use any::AnyStorage;
+pub use backup::{backup_schema_version, BackupReport, RestoreReport, DEFAULT_KEEP_BACKUPS};
+pub use dump::{
+    ConflictStrategy, Dump, DumpArticle, DumpFormat, DumpWord, KnownWord, LoadReport,
+    PrunedArticle, ReviewLogEntry, DUMP_FORMAT, DUMP_VERSION,
+};
+pub use memory::InMemoryStorage;
+pub use migrations::{latest_version, Migration, MigrationReport};
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresStorage;
+pub use retention::{
+    CompactionReport, RetentionPolicy, VacuumReport, ENV_PRUNE_EMPTY_ARTICLES, ENV_RETENTION_DAYS,
+};
+
+/// Word columns joined with their occurrences, oldest occurrence first.
+/// `{}` is replaced with a filter on `w`.
+fn vocabulary_select<DB: Dialect>() -> String {
+    format!(
+        r#"
+SELECT w.id, w.word, w.definition, w.part_of_speech, w.ipa, w.cefr_level,
+       w.synonyms, w.antonyms, w.collocations, w.example_sentence, w.edited_fields, w.suspended,
+       (SELECT {} FROM (
+            SELECT t.name FROM word_tags wt JOIN tags t ON t.id = wt.tag_id
+            WHERE wt.word_id = w.id ORDER BY t.name
+        ) AS word_tag_names) AS tags,
+       o.article_url, o.context_sentence, o.definition AS occurrence_definition, o.extracted_at
+FROM words w
+JOIN occurrences o ON o.word_id = w.id
+WHERE {{}}
+ORDER BY w.id, o.extracted_at, o.id
+"#,
+        DB::json_array("name")
+    )
+}
+
+#[derive(sqlx::FromRow)]
+struct VocabularyRow {
+    id: i64,
+    word: String,
+    definition: String,
+    part_of_speech: Option<String>,
+    ipa: Option<String>,
+    cefr_level: Option<String>,
+    synonyms: Option<String>,
+    antonyms: Option<String>,
+    collocations: Option<String>,
+    example_sentence: Option<String>,
+    edited_fields: Option<String>,
+    suspended: bool,
+    tags: Option<String>,
+    article_url: String,
+    context_sentence: String,
+    occurrence_definition: String,
+    extracted_at: DateTime<Utc>,
+}
+
+/// Fold joined word/occurrence rows (ordered by word) into one `Vocabulary` per word,
+/// keyed by word id.
+fn group_rows(rows: Vec<VocabularyRow>) -> Vec<(i64, Vocabulary)> {
+    let mut vocabs: Vec<(i64, Vocabulary)> = Vec::new();
+    let mut current_id = None;
+
+    for row in rows {
+        let occurrence = Occurrence {
+            source_url: row.article_url,
+            context_sentence: row.context_sentence,
+            definition: row.occurrence_definition,
+            extracted_at: row.extracted_at,
+        };
+
+        if current_id == Some(row.id) {
+            if let Some((_, vocab)) = vocabs.last_mut() {
+                vocab.occurrences.push(occurrence);
+            }
+            continue;
+        }
+
+        current_id = Some(row.id);
+        vocabs.push((
+            row.id,
+            Vocabulary {
+                id: Some(row.id),
+                word: row.word,
+                definition: row.definition,
+                context_sentence: occurrence.context_sentence.clone(),
+                source_url: occurrence.source_url.clone(),
+                part_of_speech: row.part_of_speech,
+                ipa: row.ipa,
+                cefr_level: row.cefr_level,
+                synonyms: decode_list(row.synonyms),
+                antonyms: decode_list(row.antonyms),
+                collocations: decode_list(row.collocations),
+                example_sentence: row.example_sentence,
+                occurrences: vec![occurrence],
+                tags: decode_list(row.tags),
+                edited_fields: decode_list(row.edited_fields),
+                suspended: row.suspended,
+            },
+        ));
+    }
+
+    vocabs
+}
+
+/// A term of a search, all of which must match.
+enum SearchTerm<'a> {
+    /// A bare word, matched as a prefix
+    Prefix(&'a str),
+    /// `"quoted text"`, matched in order
+    Phrase(&'a str),
+}
+
+/// Split user input into search terms, ignoring those with nothing searchable.
+fn search_terms(input: &str) -> Vec<SearchTerm<'_>> {
+    let mut terms = Vec::new();
+    // Odd chunks are inside quotes; an unclosed quote runs to the end
+    for (i, chunk) in input.split('"').enumerate() {
+        if i % 2 == 1 {
+            if chunk.chars().any(char::is_alphanumeric) {
+                terms.push(SearchTerm::Phrase(chunk.trim()));
+            }
+            continue;
+        }
+        for term in chunk.split_whitespace() {
+            let term = term.trim_end_matches('*');
+            if term.chars().any(char::is_alphanumeric) {
+                terms.push(SearchTerm::Prefix(term));
+            }
+        }
+    }
+    terms
+}
+
+/// Turn user input into an FTS5 query; see [`search_terms`]. Returns `None`
+/// when nothing in the input is searchable.
+fn fts_query(input: &str) -> Option<String> {
+    let parts: Vec<String> = search_terms(input)
+        .into_iter()
+        .map(|term| match term {
+            SearchTerm::Prefix(term) => format!("\"{}\"*", term),
+            SearchTerm::Phrase(phrase) => format!("\"{}\"", phrase),
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+#[derive(sqlx::FromRow)]
+struct WordMatchRow {
+    word_id: i64,
+    snippet: String,
+    rank: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ArticleMatchRow {
+    article_id: i64,
+    url: String,
+    title: String,
+    snippet: String,
+    rank: f64,
+}
+
+/// Push a `SELECT w.id ...` of the words matching `query`, in order and limited
+/// to one more than the page size so the caller can tell if a next page exists.
+fn push_word_ids<'a, DB>(
+    sql: &mut QueryBuilder<'a, DB>,
+    query: &VocabQuery,
+) -> Result<(), CoreError>
+where
+    DB: Dialect,
+    i64: Encode<'a, DB> + Type<DB>,
+    Option<i64>: Encode<'a, DB> + Type<DB>,
+    String: Encode<'a, DB> + Type<DB>,
+    DateTime<Utc>: Encode<'a, DB> + Type<DB>,
+{
+    let (open, close) = DB::TIMESTAMP;
+    sql.push("SELECT w.id FROM words w WHERE 1 = 1");
+
+    // Article filters must all hold for the same article
+    if query.collected_from.is_some()
+        || query.collected_to.is_some()
+        || query.feed.is_some()
+        || query.source.is_some()
+    {
+        sql.push(
+            " AND EXISTS (SELECT 1 FROM occurrences o JOIN articles a ON a.url = o.article_url WHERE o.word_id = w.id",
+        );
+        if let Some(from) = query.collected_from {
+            sql.push(format!(
+                " AND {} >= {}",
+                DB::timestamp("a.collected_at"),
+                open
+            ))
+            .push_bind(from)
+            .push(close);
+        }
+        if let Some(to) = query.collected_to {
+            sql.push(format!(
+                " AND {} < {}",
+                DB::timestamp("a.collected_at"),
+                open
+            ))
+            .push_bind(to)
+            .push(close);
+        }
+        if let Some(feed) = &query.feed {
+            sql.push(" AND a.feed_url = ").push_bind(feed.clone());
+        }
+        if let Some(source) = &query.source {
+            sql.push(" AND a.source = ")
+                .push_bind(format!("{:?}", source));
+        }
+        sql.push(")");
+    }
+
+    push_tag_filter(sql, query.tag.as_deref(), query.deck.as_deref());
+
+    match query.review_status {
+        Some(ReviewStatus::New) => {
+            sql.push(" AND NOT EXISTS (SELECT 1 FROM review_states r WHERE r.word_id = w.id)");
+        }
+        Some(ReviewStatus::Due) => {
+            sql.push(format!(
+                " AND EXISTS (SELECT 1 FROM review_states r WHERE r.word_id = w.id AND {} <= {})",
+                DB::timestamp("r.due_at"),
+                DB::NOW
+            ));
+        }
+        Some(ReviewStatus::Learning) => {
+            sql.push(" AND EXISTS (SELECT 1 FROM review_states r WHERE r.word_id = w.id AND r.interval_days < ")
+                .push_bind(MASTERED_INTERVAL_DAYS as i64)
+                .push(")");
+        }
+        Some(ReviewStatus::Mastered) => {
+            sql.push(" AND EXISTS (SELECT 1 FROM review_states r WHERE r.word_id = w.id AND r.interval_days >= ")
+                .push_bind(MASTERED_INTERVAL_DAYS as i64)
+                .push(")");
+        }
+        None => {}
+    }
+
+    // The cursor is the id of the last word on the previous page
+    if let Some(cursor) = &query.cursor {
+        let id: i64 = cursor
+            .parse()
+            .map_err(|_| CoreError::Parse(format!("Invalid cursor '{}'", cursor)))?;
+        match query.sort {
+            VocabSort::Oldest => {
+                sql.push(" AND w.id > ").push_bind(id);
+            }
+            VocabSort::Newest => {
+                sql.push(" AND w.id < ").push_bind(id);
+            }
+            VocabSort::Alphabetical => {
+                sql.push(" AND (w.lemma, w.id) > ((SELECT lemma FROM words WHERE id = ")
+                    .push_bind(id)
+                    .push("), ")
+                    .push_bind(id)
+                    .push(")");
+            }
+        }
+    }
+
+    sql.push(match query.sort {
+        VocabSort::Oldest => " ORDER BY w.id",
+        VocabSort::Newest => " ORDER BY w.id DESC",
+        VocabSort::Alphabetical => " ORDER BY w.lemma, w.id",
+    });
+    sql.push(" LIMIT ")
+        .push_bind(query.limit.map(|limit| limit as i64 + 1).or(DB::NO_LIMIT));
+    sql.push(" OFFSET ").push_bind(query.offset as i64);
+
+    Ok(())
+}
+
+/// Turn the words selected with [`push_word_ids`], which come back in id
+/// order, into the requested page.
+fn page_vocab(mut vocabs: Vec<(i64, Vocabulary)>, query: &VocabQuery) -> VocabPage {
+    match query.sort {
+        VocabSort::Oldest => {}
+        VocabSort::Newest => vocabs.reverse(),
+        VocabSort::Alphabetical => {
+            vocabs.sort_by_cached_key(|(id, v)| (normalize_lemma(&v.word), *id))
+        }
+    }
+
+    let next_cursor = match query.limit {
+        Some(limit) if vocabs.len() > limit => {
+            vocabs.truncate(limit);
+            vocabs.last().map(|(id, _)| id.to_string())
+        }
+        _ => None,
+    };
+
+    VocabPage {
+        vocabulary: vocabs.into_iter().map(|(_, vocab)| vocab).collect(),
+        next_cursor,
+    }
+}
+
+/// Push a `SELECT` of the review states of due, unsuspended words matching
+/// `filter`, most overdue first.
+fn push_due_states<'a, DB>(sql: &mut QueryBuilder<'a, DB>, limit: usize, filter: &TagFilter)
+where
+    DB: Dialect,
+    i64: Encode<'a, DB> + Type<DB>,
+    String: Encode<'a, DB> + Type<DB>,
+{
+    sql.push(format!(
+        "SELECT {} FROM review_states r JOIN words w ON w.id = r.word_id \
+         WHERE {} <= {} AND NOT w.suspended",
+        REVIEW_STATE_COLUMNS
+            .split(", ")
+            .map(|column| format!("r.{}", column))
+            .collect::<Vec<_>>()
+            .join(", "),
+        DB::timestamp("r.due_at"),
+        DB::NOW
+    ));
+    push_tag_filter(sql, filter.tag.as_deref(), filter.deck.as_deref());
+    sql.push(format!(
+        " ORDER BY {}, r.word_id LIMIT ",
+        DB::timestamp("r.due_at")
+    ))
+    .push_bind(limit as i64);
+}
+
+/// Push a `SELECT w.id` of unsuspended words matching `filter` that were never
+/// reviewed, oldest first.
+fn push_new_word_ids<'a, DB>(sql: &mut QueryBuilder<'a, DB>, limit: usize, filter: &TagFilter)
+where
+    DB: Database,
+    i64: Encode<'a, DB> + Type<DB>,
+    String: Encode<'a, DB> + Type<DB>,
+{
+    sql.push(
+        "SELECT w.id FROM words w \
+         WHERE w.id NOT IN (SELECT word_id FROM review_states) AND NOT w.suspended",
+    );
+    push_tag_filter(sql, filter.tag.as_deref(), filter.deck.as_deref());
+    sql.push(" ORDER BY w.id LIMIT ").push_bind(limit as i64);
+}
+
+/// Push conditions limiting `w` to words with tag `tag` and any tag of deck `deck`.
+fn push_tag_filter<'a, DB>(sql: &mut QueryBuilder<'a, DB>, tag: Option<&str>, deck: Option<&str>)
+where
+    DB: Database,
+    String: Encode<'a, DB> + Type<DB>,
+{
+    if let Some(tag) = tag {
+        sql.push(
+            " AND EXISTS (SELECT 1 FROM word_tags wt JOIN tags t ON t.id = wt.tag_id WHERE wt.word_id = w.id AND t.name = ",
+        )
+        .push_bind(normalize_tag(tag))
+        .push(")");
+    }
+    if let Some(deck) = deck {
+        sql.push(
+            " AND EXISTS (SELECT 1 FROM word_tags wt JOIN deck_tags dt ON dt.tag_id = wt.tag_id \
+             JOIN decks d ON d.id = dt.deck_id WHERE wt.word_id = w.id AND d.name = ",
+        )
+        .push_bind(deck.trim().to_string())
+        .push(")");
+    }
+}
+
+/// Optional `words` columns filled in from later sightings, unless edited by hand.
+const WORD_LEXICAL_COLUMNS: &[&str] = &[
+    "part_of_speech",
+    "ipa",
+    "cefr_level",
+    "synonyms",
+    "antonyms",
+    "collocations",
+    "example_sentence",
+];
+
+/// Normalize a tag name, dropping a leading `#` and joining words with `-`
+/// so the tag also works in Obsidian.
+fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// Normalize a word into the key used for deduplication.
+fn normalize_lemma(word: &str) -> String {
+    word.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Error for renaming a word to the lemma of another saved word.
+fn lemma_taken(lemma: &str) -> CoreError {
+    CoreError::Database(format!(
+        "Another word is already saved as '{}'; combine them with `spread word merge`",
+        lemma
+    ))
+}
+
+/// Encode a word list as a JSON array; empty lists are stored as NULL.
+fn encode_list(items: &[String]) -> Option<String> {
+    if items.is_empty() {
+        None
+    } else {
+        serde_json::to_string(items).ok()
+    }
+}
+
+fn decode_list(raw: Option<String>) -> Vec<String> {
+    raw.and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Insert an article unless one with the same URL is already stored.
+async fn insert_article(conn: &mut SqliteConnection, article: &Article) -> Result<(), CoreError> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO articles (url, title, content, source, feed_url, published_at, collected_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&article.url)
+    .bind(&article.title)
+    .bind(&article.content)
+    .bind(format!("{:?}", article.source))
+    .bind(&article.feed_url)
+    .bind(article.published_at)
+    .bind(article.collected_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Upsert the word and record this sighting as an occurrence.
+///
+/// Words merged into another are recorded under that word. Lexical fields
+/// already stored or edited by hand are kept; missing ones are filled in from
+/// `vocab`.
+async fn insert_vocab(
+    conn: &mut SqliteConnection,
+    vocab: &Vocabulary,
+    extracted_at: DateTime<Utc>,
+) -> Result<(), CoreError> {
+    let lemma = canonical_lemma(conn, &normalize_lemma(&vocab.word)).await?;
+    let fill_missing = WORD_LEXICAL_COLUMNS
+        .iter()
+        .map(|column| {
+            format!(
+                "{0} = CASE WHEN '{0}' IN (SELECT value FROM json_each(COALESCE(words.edited_fields, '[]'))) \
+                 THEN words.{0} ELSE COALESCE(words.{0}, excluded.{0}) END",
+                column
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    sqlx::query(&format!(
+        r#"
+        INSERT INTO words (
+            lemma, word, definition,
+            part_of_speech, ipa, cefr_level, synonyms, antonyms, collocations, example_sentence,
+            created_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(lemma) DO UPDATE SET
+        {}
+        "#,
+        fill_missing
+    ))
+    .bind(&lemma)
+    .bind(vocab.word.trim())
+    .bind(&vocab.definition)
+    .bind(&vocab.part_of_speech)
+    .bind(&vocab.ipa)
+    .bind(&vocab.cefr_level)
+    .bind(encode_list(&vocab.synonyms))
+    .bind(encode_list(&vocab.antonyms))
+    .bind(encode_list(&vocab.collocations))
+    .bind(&vocab.example_sentence)
+    .bind(extracted_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let (word_id,): (i64,) = sqlx::query_as("SELECT id FROM words WHERE lemma = ?")
+        .bind(&lemma)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO occurrences (word_id, article_url, context_sentence, definition, extracted_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(word_id)
+    .bind(&vocab.source_url)
+    .bind(&vocab.context_sentence)
+    .bind(&vocab.definition)
+    .bind(extracted_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    insert_tags(conn, word_id, &vocab.tags).await?;
+
+    Ok(())
+}
+
+/// Tag a word, creating missing tags. Returns how many tags were new to the word.
+async fn insert_tags(
+    conn: &mut SqliteConnection,
+    word_id: i64,
+    tags: &[String],
+) -> Result<usize, CoreError> {
+    let mut added = 0;
+    for tag in tags {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            continue;
+        }
+        let tag_id = insert_tag(conn, &tag).await?;
+        added += sqlx::query("INSERT OR IGNORE INTO word_tags (word_id, tag_id) VALUES (?, ?)")
+            .bind(word_id)
+            .bind(tag_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?
+            .rows_affected() as usize;
+    }
+    Ok(added)
+}
+
+/// Id of the tag with the normalized name `tag`, created if missing.
+async fn insert_tag(conn: &mut SqliteConnection, tag: &str) -> Result<i64, CoreError> {
+    sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+        .bind(tag)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let (id,): (i64,) = sqlx::query_as("SELECT id FROM tags WHERE name = ?")
+        .bind(tag)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    Ok(id)
+}
+
+/// Lemma of the word `lemma` was merged into, or `lemma` itself.
+async fn canonical_lemma(conn: &mut SqliteConnection, lemma: &str) -> Result<String, CoreError> {
+    let merged: Option<(String,)> = sqlx::query_as(
+        "SELECT w.lemma FROM word_aliases a JOIN words w ON w.id = a.word_id WHERE a.lemma = ?",
+    )
+    .bind(lemma)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(merged.map_or_else(|| lemma.to_string(), |(lemma,)| lemma))
+}
+
+#[derive(sqlx::FromRow)]
+struct ReviewStateRow {
+    word_id: i64,
+    reps: i64,
+    lapses: i64,
+    ease: f64,
+    interval_days: i64,
+    stability: f64,
+    difficulty: f64,
+    due_at: DateTime<Utc>,
+    last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl From<ReviewStateRow> for ReviewState {
+    fn from(row: ReviewStateRow) -> Self {
+        Self {
+            reps: row.reps as u32,
+            lapses: row.lapses as u32,
+            ease: row.ease,
+            interval_days: row.interval_days as u32,
+            stability: row.stability,
+            difficulty: row.difficulty,
+            due_at: row.due_at,
+            last_reviewed_at: row.last_reviewed_at,
+        }
+    }
+}
+
+const REVIEW_STATE_COLUMNS: &str =
+    "word_id, reps, lapses, ease, interval_days, stability, difficulty, due_at, last_reviewed_at";
 
 pub struct SqliteStorage {
     pool: SqlitePool,
+    algorithm: Algorithm,
 }
 
 impl SqliteStorage {
+    /// Open the database and apply any pending schema migrations.
     pub async fn new(db_url: &str) -> Result<Self, CoreError> {
+        let storage = Self::connect(db_url).await?;
+        storage.migrate().await?;
+        Ok(storage)
+    }
+
+    /// Open the database without touching its schema.
+    pub async fn connect(db_url: &str) -> Result<Self, CoreError> {
         let pool = SqlitePool::connect(db_url)
             .await
             .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        sqlx::query(CREATE_ARTICLES_TABLE)
-            .execute(&pool)
+        Ok(Self {
+            pool,
+            algorithm: Algorithm::default(),
+        })
+    }
+
+    /// Open an existing database read-only, for looking without changing
+    /// anything. `NotFound` when the file does not exist.
+    pub async fn open_read_only(db_url: &str) -> Result<Self, CoreError> {
+        let options = SqliteConnectOptions::from_str(db_url)
+            .map_err(|e| CoreError::Database(e.to_string()))?
+            .read_only(true)
+            .create_if_missing(false);
+        let file = options.get_filename();
+        if file != Path::new(":memory:") && !file.exists() {
+            return Err(CoreError::NotFound(format!(
+                "Database file {}",
+                file.display()
+            )));
+        }
+        let pool = SqlitePool::connect_with(options)
             .await
             .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        sqlx::query(CREATE_VOCABULARIES_TABLE)
-            .execute(&pool)
+        Ok(Self {
+            pool,
+            algorithm: Algorithm::default(),
+        })
+    }
+
+    /// Set the spaced-repetition algorithm used by `record_review`
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Schema version recorded in the database (0 if never migrated).
+    pub async fn schema_version(&self) -> Result<i64, CoreError> {
+        migrations::current_version(&self.pool).await
+    }
+
+    /// Migrations that `migrate` would apply.
+    pub async fn pending_migrations(&self) -> Result<Vec<&'static Migration>, CoreError> {
+        migrations::pending(&self.pool).await
+    }
+
+    /// Back up the database and apply pending migrations.
+    pub async fn migrate(&self) -> Result<MigrationReport, CoreError> {
+        migrations::migrate(&self.pool).await
+    }
+
+    /// Cut down articles collected more than `policy.days` ago; see [`RetentionPolicy`].
+    pub async fn compact_articles(
+        &self,
+        policy: &RetentionPolicy,
+    ) -> Result<CompactionReport, CoreError> {
+        retention::compact(&self.pool, policy, Utc::now()).await
+    }
+
+    /// Rebuild the database file to give space freed by deletions back to the disk.
+    pub async fn vacuum(&self) -> Result<VacuumReport, CoreError> {
+        retention::vacuum(&self.pool).await
+    }
+
+    /// Copy the database into `dir` as `spread-<timestamp>.db`, deleting all
+    /// but the newest `keep` such copies. Safe while other processes write.
+    pub async fn backup(&self, dir: &Path, keep: usize) -> Result<BackupReport, CoreError> {
+        backup::backup(&self.pool, dir, keep, Utc::now()).await
+    }
+
+    /// Replace the database file with a backup after checking its schema
+    /// version. The current database is copied aside first.
+    pub async fn restore(self, from: &Path) -> Result<RestoreReport, CoreError> {
+        backup::restore(&self.pool, from, Utc::now()).await
+    }
+
+    /// Everything stored, as a portable document; see [`Dump`].
+    pub async fn dump(&self) -> Result<Dump, CoreError> {
+        dump::export(&self.pool).await
+    }
+
+    /// Import a dump, resolving words and articles already stored with `strategy`.
+    pub async fn load(
+        &self,
+        dump: &Dump,
+        strategy: ConflictStrategy,
+    ) -> Result<LoadReport, CoreError> {
+        dump::import(&self.pool, dump, strategy).await
+    }
+
+    async fn select_vocab(
+        &self,
+        filter: &str,
+        binds: &[&str],
+    ) -> Result<Vec<Vocabulary>, CoreError> {
+        let vocabs = self.select_vocab_with_ids(filter, binds).await?;
+        Ok(vocabs.into_iter().map(|(_, vocab)| vocab).collect())
+    }
+
+    async fn select_vocab_with_ids(
+        &self,
+        filter: &str,
+        binds: &[&str],
+    ) -> Result<Vec<(i64, Vocabulary)>, CoreError> {
+        let sql = vocabulary_select::<Sqlite>().replace("{}", filter);
+        let mut query = sqlx::query_as::<_, VocabularyRow>(&sql);
+        for value in binds {
+            query = query.bind(*value);
+        }
+
+        let rows = query
+            .fetch_all(&self.pool)
             .await
             .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        Ok(Self { pool })
+        Ok(group_rows(rows))
+    }
+
+    /// Runs selected by `clause`, in its order, with their feed results and errors.
+    async fn select_runs(&self, clause: &str) -> Result<Vec<PipelineRun>, CoreError> {
+        let rows: Vec<runs::RunRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM runs {}",
+            runs::RUN_COLUMNS,
+            clause
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = rows
+            .iter()
+            .map(|row| row.id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let feeds = sqlx::query_as(&format!(
+            "SELECT {} FROM run_feeds WHERE run_id IN ({}) ORDER BY run_id, position",
+            runs::RUN_FEED_COLUMNS,
+            ids
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+        let errors = sqlx::query_as(&format!(
+            "SELECT {} FROM run_errors WHERE run_id IN ({}) ORDER BY id",
+            runs::RUN_ERROR_COLUMNS,
+            ids
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        runs::assemble(rows, feeds, errors)
+    }
+
+    /// Words with the given ids, keyed by id.
+    async fn vocab_by_ids(&self, ids: &[i64]) -> Result<HashMap<i64, Vocabulary>, CoreError> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let ids = ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(self
+            .select_vocab_with_ids(&format!("w.id IN ({})", ids), &[])
+            .await?
+            .into_iter()
+            .collect())
+    }
+
+    /// Ranked matches in `words_fts`; a negative `limit` means no limit.
+    async fn match_words(&self, query: &str, limit: i64) -> Result<Vec<WordMatchRow>, CoreError> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        // Matches in the word itself outrank the definition, then contexts
+        sqlx::query_as(&format!(
+            r#"
+            SELECT rowid AS word_id,
+                   snippet(words_fts, -1, '{0}', '{0}', '…', 12) AS snippet,
+                   bm25(words_fts, 10.0, 4.0, 1.0) AS rank
+            FROM words_fts
+            WHERE words_fts MATCH ?
+            ORDER BY rank
+            LIMIT ?
+            "#,
+            SNIPPET_HIGHLIGHT
+        ))
+        .bind(query)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))
+    }
+
+    async fn match_articles(
+        &self,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<ArticleMatchRow>, CoreError> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        sqlx::query_as(&format!(
+            r#"
+            SELECT a.id AS article_id, articles_fts.url, articles_fts.title,
+                   snippet(articles_fts, -1, '{0}', '{0}', '…', 16) AS snippet,
+                   bm25(articles_fts, 0.0, 3.0, 1.0) AS rank
+            FROM articles_fts
+            JOIN articles a ON a.url = articles_fts.url
+            WHERE articles_fts MATCH ?
+            ORDER BY rank
+            LIMIT ?
+            "#,
+            SNIPPET_HIGHLIGHT
+        ))
+        .bind(query)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))
     }
 }
 
 #[async_trait]
 impl StoragePort for SqliteStorage {
     async fn exists(&self, url: &str) -> Result<bool, CoreError> {
-        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM articles WHERE url = ?")
-            .bind(url)
-            .fetch_one(&self.pool)
+        let (exists,): (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM articles WHERE url = ?)
+                OR EXISTS (SELECT 1 FROM pruned_articles WHERE url_hash = ?)
+            "#,
+        )
+        .bind(url)
+        .bind(retention::url_fingerprint(url))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(exists)
+    }
+
+    async fn save_article(&self, article: &Article) -> Result<(), CoreError> {
+        let mut conn = self
+            .pool
+            .acquire()
             .await
             .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        Ok(count.0 > 0)
+        insert_article(&mut conn, article).await
     }
 
-    async fn save_article(&self, article: &Article) -> Result<(), CoreError> {
-        let source = format!("{:?}", article.source);
+    async fn save_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        insert_vocab(&mut tx, vocab, Utc::now()).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn save_article_with_vocab(
+        &self,
+        article: &Article,
+        vocabularies: &[Vocabulary],
+    ) -> Result<(), CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        insert_article(&mut tx, article).await?;
+        let extracted_at = Utc::now();
+        for vocab in vocabularies {
+            let vocab = Vocabulary {
+                source_url: article.url.clone(),
+                ..vocab.clone()
+            };
+            insert_vocab(&mut tx, &vocab, extracted_at).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_vocab_by_id(&self, id: i64) -> Result<Option<Vocabulary>, CoreError> {
+        let vocabs = self.select_vocab("w.id = ?", &[&id.to_string()]).await?;
+        Ok(vocabs.into_iter().next())
+    }
+
+    async fn get_vocab_by_word(&self, word: &str) -> Result<Option<Vocabulary>, CoreError> {
+        let lemma = normalize_lemma(word);
+        let vocabs = self
+            .select_vocab(
+                "w.lemma = ? OR w.id = (SELECT word_id FROM word_aliases WHERE lemma = ?)",
+                &[&lemma, &lemma],
+            )
+            .await?;
+        Ok(vocabs.into_iter().next())
+    }
+
+    async fn update_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
+        let id = vocab
+            .id
+            .ok_or_else(|| CoreError::NotFound(format!("'{}' has not been saved", vocab.word)))?;
+        let lemma = normalize_lemma(&vocab.word);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM words WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        if exists.is_none() {
+            return Err(CoreError::NotFound(format!("Word {}", id)));
+        }
+
+        let taken: Option<(i64,)> =
+            sqlx::query_as("SELECT id FROM words WHERE lemma = ? AND id != ?")
+                .bind(&lemma)
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        if taken.is_some() {
+            return Err(lemma_taken(&lemma));
+        }
 
         sqlx::query(
             r#"
-            INSERT OR IGNORE INTO articles (url, title, content, source, published_at, collected_at)
-            VALUES (?, ?, ?, ?, ?, ?)
+            UPDATE words SET
+                lemma = ?, word = ?, definition = ?,
+                part_of_speech = ?, ipa = ?, cefr_level = ?,
+                synonyms = ?, antonyms = ?, collocations = ?, example_sentence = ?,
+                edited_fields = ?, suspended = ?
+            WHERE id = ?
             "#,
         )
-        .bind(&article.url)
-        .bind(&article.title)
-        .bind(&article.content)
-        .bind(&source)
-        .bind(article.published_at)
-        .bind(article.collected_at)
-        .execute(&self.pool)
+        .bind(&lemma)
+        .bind(vocab.word.trim())
+        .bind(&vocab.definition)
+        .bind(&vocab.part_of_speech)
+        .bind(&vocab.ipa)
+        .bind(&vocab.cefr_level)
+        .bind(encode_list(&vocab.synonyms))
+        .bind(encode_list(&vocab.antonyms))
+        .bind(encode_list(&vocab.collocations))
+        .bind(&vocab.example_sentence)
+        .bind(encode_list(&vocab.edited_fields))
+        .bind(vocab.suspended)
+        .bind(id)
+        .execute(&mut *tx)
         .await
         .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        Ok(())
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))
     }
 
-    async fn save_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
-        sqlx::query(
+    async fn delete_vocab(&self, id: i64) -> Result<bool, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        for table in [
+            "word_tags",
+            "word_aliases",
+            "review_states",
+            "review_log",
+            "occurrences",
+        ] {
+            sqlx::query(&format!("DELETE FROM {} WHERE word_id = ?", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+        let result = sqlx::query("DELETE FROM words WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn merge_vocab(&self, from: i64, into: i64) -> Result<Vocabulary, CoreError> {
+        if from == into {
+            return Err(CoreError::Database(format!(
+                "Cannot merge word {} into itself",
+                from
+            )));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        for id in [from, into] {
+            let (exists,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM words WHERE id = ?")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+            if exists == 0 {
+                return Err(CoreError::NotFound(format!("Word {}", id)));
+            }
+        }
+
+        let fill_missing = WORD_LEXICAL_COLUMNS
+            .iter()
+            .map(|column| {
+                format!(
+                    "{0} = COALESCE({0}, (SELECT {0} FROM words WHERE id = ?1))",
+                    column
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let statements = [
+            format!("UPDATE words SET {} WHERE id = ?2", fill_missing),
+            // Contexts already recorded for `into` stay as they are
+            "UPDATE OR IGNORE occurrences SET word_id = ?2 WHERE word_id = ?1".to_string(),
+            "DELETE FROM occurrences WHERE word_id = ?1".to_string(),
+            "INSERT OR IGNORE INTO word_tags (word_id, tag_id) SELECT ?2, tag_id FROM word_tags WHERE word_id = ?1".to_string(),
+            "DELETE FROM word_tags WHERE word_id = ?1".to_string(),
+            "UPDATE review_log SET word_id = ?2 WHERE word_id = ?1".to_string(),
+            // `into` keeps its own schedule if it has one
+            format!(
+                "INSERT OR IGNORE INTO review_states ({0}) SELECT ?2, {1} FROM review_states WHERE word_id = ?1",
+                REVIEW_STATE_COLUMNS,
+                REVIEW_STATE_COLUMNS.trim_start_matches("word_id, ")
+            ),
+            "DELETE FROM review_states WHERE word_id = ?1".to_string(),
+            "UPDATE word_aliases SET word_id = ?2 WHERE word_id = ?1".to_string(),
+            "INSERT OR REPLACE INTO word_aliases (lemma, word_id) SELECT lemma, ?2 FROM words WHERE id = ?1".to_string(),
+            "DELETE FROM words WHERE id = ?1".to_string(),
+        ];
+        for statement in &statements {
+            sqlx::query(statement)
+                .bind(from)
+                .bind(into)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        self.get_vocab_by_id(into)
+            .await?
+            .ok_or_else(|| CoreError::NotFound(format!("Word {}", into)))
+    }
+
+    async fn query_vocab(&self, query: &VocabQuery) -> Result<VocabPage, CoreError> {
+        // Load the matching words and their occurrences in one statement so
+        // large unpaged queries do not need an id list
+        let select = vocabulary_select::<Sqlite>();
+        let (prefix, suffix) = select.split_once("{}").unwrap_or((&select, ""));
+        let mut sql = QueryBuilder::<Sqlite>::new(prefix);
+        sql.push("w.id IN (");
+        push_word_ids(&mut sql, query)?;
+        sql.push(")");
+        sql.push(suffix);
+
+        let rows: Vec<VocabularyRow> = sql
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(page_vocab(group_rows(rows), query))
+    }
+
+    async fn search_vocab(&self, query: &str) -> Result<Vec<Vocabulary>, CoreError> {
+        let matches = self.match_words(query, -1).await?;
+        let ids: Vec<i64> = matches.iter().map(|m| m.word_id).collect();
+        let mut vocabs = self.vocab_by_ids(&ids).await?;
+
+        Ok(ids.iter().filter_map(|id| vocabs.remove(id)).collect())
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>, CoreError> {
+        let words = self.match_words(query, limit as i64).await?;
+        let ids: Vec<i64> = words.iter().map(|m| m.word_id).collect();
+        let mut vocabs = self.vocab_by_ids(&ids).await?;
+
+        let mut hits: Vec<SearchHit> = words
+            .into_iter()
+            .filter_map(|m| {
+                Some(SearchHit::Word {
+                    word_id: m.word_id,
+                    vocabulary: Box::new(vocabs.remove(&m.word_id)?),
+                    snippet: m.snippet,
+                    rank: m.rank,
+                })
+            })
+            .collect();
+
+        hits.extend(
+            self.match_articles(query, limit as i64)
+                .await?
+                .into_iter()
+                .map(|m| SearchHit::Article {
+                    article_id: m.article_id,
+                    url: m.url,
+                    title: m.title,
+                    snippet: m.snippet,
+                    rank: m.rank,
+                }),
+        );
+        Ok(hits)
+    }
+
+    async fn get_random_vocab(&self) -> Result<Option<Vocabulary>, CoreError> {
+        let vocabs = self
+            .select_vocab(
+                "w.id = (SELECT id FROM words ORDER BY RANDOM() LIMIT 1)",
+                &[],
+            )
+            .await?;
+
+        Ok(vocabs.into_iter().next())
+    }
+
+    async fn add_known_words(&self, words: &[String]) -> Result<usize, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let now = Utc::now();
+        let mut added = 0;
+        for word in words {
+            let lemma = word.trim().to_lowercase();
+            if lemma.is_empty() {
+                continue;
+            }
+            let result =
+                sqlx::query("INSERT OR IGNORE INTO known_words (lemma, added_at) VALUES (?, ?)")
+                    .bind(&lemma)
+                    .bind(now)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| CoreError::Database(e.to_string()))?;
+            added += result.rows_affected() as usize;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(added)
+    }
+
+    async fn remove_known_word(&self, word: &str) -> Result<bool, CoreError> {
+        let result = sqlx::query("DELETE FROM known_words WHERE lemma = ?")
+            .bind(word.trim().to_lowercase())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_known_words(&self) -> Result<Vec<String>, CoreError> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT lemma FROM known_words ORDER BY added_at DESC, lemma")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|(lemma,)| lemma).collect())
+    }
+
+    async fn get_mastered_words(&self) -> Result<Vec<String>, CoreError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
             r#"
-            INSERT INTO vocabularies (word, definition, context_sentence, source_url)
-            VALUES (?, ?, ?, ?)
+            SELECT w.lemma FROM words w
+            JOIN review_states r ON r.word_id = w.id
+            WHERE r.interval_days >= ?
+            ORDER BY w.lemma
             "#,
         )
-        .bind(&vocab.word)
-        .bind(&vocab.definition)
-        .bind(&vocab.context_sentence)
-        .bind(&vocab.source_url)
-        .execute(&self.pool)
+        .bind(MASTERED_INTERVAL_DAYS as i64)
+        .fetch_all(&self.pool)
         .await
         .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        Ok(())
+        Ok(rows.into_iter().map(|(lemma,)| lemma).collect())
     }
 
-    async fn get_all_vocab(&self) -> Result<Vec<Vocabulary>, CoreError> {
-        let rows: Vec<(String, String, String, String)> = sqlx::query_as(
-            "SELECT word, definition, context_sentence, source_url FROM vocabularies",
+    async fn add_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let (exists,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM words WHERE id = ?")
+            .bind(word_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        if exists == 0 {
+            return Err(CoreError::NotFound(format!("Word {}", word_id)));
+        }
+        let added = insert_tags(&mut tx, word_id, tags).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(added)
+    }
+
+    async fn remove_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError> {
+        let mut removed = 0;
+        for tag in tags {
+            removed += sqlx::query(
+                "DELETE FROM word_tags WHERE word_id = ? AND tag_id = (SELECT id FROM tags WHERE name = ?)",
+            )
+            .bind(word_id)
+            .bind(normalize_tag(tag))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?
+            .rows_affected() as usize;
+        }
+        Ok(removed)
+    }
+
+    async fn get_tags(&self) -> Result<Vec<(String, usize)>, CoreError> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT t.name, COUNT(*) FROM tags t
+            JOIN word_tags wt ON wt.tag_id = t.id
+            GROUP BY t.id
+            ORDER BY t.name
+            "#,
         )
         .fetch_all(&self.pool)
         .await
@@ -112,107 +1330,539 @@ impl StoragePort for SqliteStorage {
 
         Ok(rows
             .into_iter()
-            .map(
-                |(word, definition, context_sentence, source_url)| Vocabulary {
-                    word,
-                    definition,
-                    context_sentence,
-                    source_url,
-                },
-            )
+            .map(|(name, count)| (name, count as usize))
             .collect())
     }
 
-    async fn search_vocab(&self, query: &str) -> Result<Vec<Vocabulary>, CoreError> {
-        let pattern = format!("%{}%", query);
-        let rows: Vec<(String, String, String, String)> = sqlx::query_as(
+    async fn save_deck(&self, deck: &Deck) -> Result<(), CoreError> {
+        let name = deck.name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Parse("Deck name must not be empty".to_string()));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        sqlx::query("INSERT OR IGNORE INTO decks (name) VALUES (?)")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        let (deck_id,): (i64,) = sqlx::query_as("SELECT id FROM decks WHERE name = ?")
+            .bind(name)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        sqlx::query("DELETE FROM deck_tags WHERE deck_id = ?")
+            .bind(deck_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        for tag in &deck.tags {
+            let tag = normalize_tag(tag);
+            if tag.is_empty() {
+                continue;
+            }
+            let tag_id = insert_tag(&mut tx, &tag).await?;
+            sqlx::query("INSERT OR IGNORE INTO deck_tags (deck_id, tag_id) VALUES (?, ?)")
+                .bind(deck_id)
+                .bind(tag_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete_deck(&self, name: &str) -> Result<bool, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        sqlx::query("DELETE FROM deck_tags WHERE deck_id IN (SELECT id FROM decks WHERE name = ?)")
+            .bind(name.trim())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        let result = sqlx::query("DELETE FROM decks WHERE name = ?")
+            .bind(name.trim())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_decks(&self) -> Result<Vec<Deck>, CoreError> {
+        let rows: Vec<(String, Option<String>)> = sqlx::query_as(
             r#"
-            SELECT word, definition, context_sentence, source_url
-            FROM vocabularies
-            WHERE word LIKE ? OR definition LIKE ?
+            SELECT d.name, t.name FROM decks d
+            LEFT JOIN deck_tags dt ON dt.deck_id = d.id
+            LEFT JOIN tags t ON t.id = dt.tag_id
+            ORDER BY d.name, t.name
             "#,
         )
-        .bind(&pattern)
-        .bind(&pattern)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        Ok(rows
+        let mut decks: Vec<Deck> = Vec::new();
+        for (name, tag) in rows {
+            if decks.last().map(|d| &d.name) != Some(&name) {
+                decks.push(Deck {
+                    name,
+                    tags: Vec::new(),
+                });
+            }
+            if let (Some(deck), Some(tag)) = (decks.last_mut(), tag) {
+                deck.tags.push(tag);
+            }
+        }
+        Ok(decks)
+    }
+
+    async fn get_due_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError> {
+        let mut sql = QueryBuilder::<Sqlite>::new("");
+        push_due_states(&mut sql, limit, filter);
+        let states: Vec<ReviewStateRow> = sql
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        if states.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i64> = states.iter().map(|s| s.word_id).collect();
+        let mut vocabs = self.vocab_by_ids(&ids).await?;
+
+        Ok(states
             .into_iter()
-            .map(
-                |(word, definition, context_sentence, source_url)| Vocabulary {
-                    word,
-                    definition,
-                    context_sentence,
-                    source_url,
-                },
-            )
+            .filter_map(|row| {
+                let vocabulary = vocabs.remove(&row.word_id)?;
+                Some(ReviewCard {
+                    word_id: row.word_id,
+                    vocabulary,
+                    state: row.into(),
+                })
+            })
             .collect())
     }
 
-    async fn get_today_vocab(&self) -> Result<Vec<Vocabulary>, CoreError> {
-        let rows: Vec<(String, String, String, String)> = sqlx::query_as(
+    async fn get_new_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError> {
+        let mut sql = QueryBuilder::<Sqlite>::new("");
+        push_new_word_ids(&mut sql, limit, filter);
+        let ids: Vec<(i64,)> = sql
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = ids
+            .iter()
+            .map(|(id,)| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let now = Utc::now();
+        Ok(self
+            .select_vocab_with_ids(&format!("w.id IN ({})", ids), &[])
+            .await?
+            .into_iter()
+            .map(|(word_id, vocabulary)| ReviewCard {
+                word_id,
+                vocabulary,
+                state: ReviewState::new(now),
+            })
+            .collect())
+    }
+
+    async fn record_review(&self, word_id: i64, grade: Grade) -> Result<ReviewState, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let (exists,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM words WHERE id = ?")
+            .bind(word_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        if exists == 0 {
+            return Err(CoreError::NotFound(format!("Word {}", word_id)));
+        }
+
+        let now = Utc::now();
+        let current: Option<ReviewStateRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM review_states WHERE word_id = ?",
+            REVIEW_STATE_COLUMNS
+        ))
+        .bind(word_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+        let current = current.map_or_else(|| ReviewState::new(now), ReviewState::from);
+
+        let next = self.algorithm.scheduler().schedule(&current, grade, now);
+
+        sqlx::query(&format!(
+            "INSERT OR REPLACE INTO review_states ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            REVIEW_STATE_COLUMNS
+        ))
+        .bind(word_id)
+        .bind(next.reps as i64)
+        .bind(next.lapses as i64)
+        .bind(next.ease)
+        .bind(next.interval_days as i64)
+        .bind(next.stability)
+        .bind(next.difficulty)
+        .bind(next.due_at)
+        .bind(next.last_reviewed_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        sqlx::query(
             r#"
-            SELECT v.word, v.definition, v.context_sentence, v.source_url
-            FROM vocabularies v
-            JOIN articles a ON v.source_url = a.url
-            WHERE date(a.collected_at) = date('now')
+            INSERT INTO review_log (
+                word_id, grade, algorithm, reviewed_at,
+                interval_days, ease, stability, difficulty, due_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(word_id)
+        .bind(grade.as_u8() as i64)
+        .bind(self.algorithm.name())
+        .bind(now)
+        .bind(next.interval_days as i64)
+        .bind(next.ease)
+        .bind(next.stability)
+        .bind(next.difficulty)
+        .bind(next.due_at)
+        .execute(&mut *tx)
         .await
         .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        Ok(rows
-            .into_iter()
-            .map(
-                |(word, definition, context_sentence, source_url)| Vocabulary {
-                    word,
-                    definition,
-                    context_sentence,
-                    source_url,
-                },
-            )
-            .collect())
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(next)
     }
 
-    async fn get_random_vocab(&self) -> Result<Option<Vocabulary>, CoreError> {
-        let row: Option<(String, String, String, String)> = sqlx::query_as(
-            "SELECT word, definition, context_sentence, source_url FROM vocabularies ORDER BY RANDOM() LIMIT 1",
+    async fn start_run(&self, run: &PipelineRun) -> Result<i64, CoreError> {
+        let (id,): (i64,) = sqlx::query_as(
+            "INSERT INTO runs (triggered_by, started_at, stats) VALUES (?, ?, ?) RETURNING id",
         )
-        .fetch_optional(&self.pool)
+        .bind(run.trigger.to_string())
+        .bind(run.started_at)
+        .bind(runs::encode_stats(&run.stats))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(id)
+    }
+
+    async fn update_run(&self, run: &PipelineRun) -> Result<(), CoreError> {
+        let id = run
+            .id
+            .ok_or_else(|| CoreError::NotFound("Run without an id".to_string()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let result =
+            sqlx::query("UPDATE runs SET finished_at = ?, cancelled = ?, stats = ? WHERE id = ?")
+                .bind(run.finished_at)
+                .bind(run.cancelled)
+                .bind(runs::encode_stats(&run.stats))
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(CoreError::NotFound(format!("Run {}", id)));
+        }
+
+        for table in ["run_feeds", "run_errors"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE run_id = ?", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+        for (position, feed) in run.feeds.iter().enumerate() {
+            sqlx::query(&format!(
+                "INSERT INTO run_feeds (position, {}) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                runs::RUN_FEED_COLUMNS
+            ))
+            .bind(position as i64)
+            .bind(id)
+            .bind(&feed.feed_url)
+            .bind(feed.items as i64)
+            .bind(feed.articles_saved as i64)
+            .bind(feed.vocabularies_saved as i64)
+            .bind(&feed.error)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+        for error in &run.errors {
+            sqlx::query(&format!(
+                "INSERT INTO run_errors ({}) VALUES (?, ?, ?, ?)",
+                runs::RUN_ERROR_COLUMNS
+            ))
+            .bind(id)
+            .bind(error.stage.to_string())
+            .bind(&error.url)
+            .bind(&error.message)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_run(&self, id: i64) -> Result<Option<PipelineRun>, CoreError> {
+        Ok(self.select_runs(&format!("WHERE id = {}", id)).await?.pop())
+    }
+
+    async fn get_runs(&self, limit: Option<usize>) -> Result<Vec<PipelineRun>, CoreError> {
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        self.select_runs(&format!("ORDER BY id DESC LIMIT {}", limit))
+            .await
+    }
+
+    async fn enqueue_items(&self, items: &[QueuedItem]) -> Result<usize, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let now = Utc::now();
+        let mut added = 0;
+        for item in items {
+            let result = sqlx::query(&format!(
+                "INSERT OR IGNORE INTO queued_items ({}, queued_at) VALUES (?, ?, ?, ?, ?)",
+                runs::QUEUED_ITEM_COLUMNS
+            ))
+            .bind(&item.feed_url)
+            .bind(&item.url)
+            .bind(&item.title)
+            .bind(item.published_at)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+            added += result.rows_affected() as usize;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(added)
+    }
+
+    async fn get_queued_items(&self) -> Result<Vec<QueuedItem>, CoreError> {
+        let rows: Vec<runs::QueuedItemRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM queued_items ORDER BY id",
+            runs::QUEUED_ITEM_COLUMNS
+        ))
+        .fetch_all(&self.pool)
         .await
         .map_err(|e| CoreError::Database(e.to_string()))?;
 
-        Ok(row.map(
-            |(word, definition, context_sentence, source_url)| Vocabulary {
-                word,
-                definition,
-                context_sentence,
-                source_url,
-            },
-        ))
+        Ok(rows.into_iter().map(QueuedItem::from).collect())
+    }
+
+    async fn dequeue_item(&self, url: &str) -> Result<bool, CoreError> {
+        let result = sqlx::query("DELETE FROM queued_items WHERE url = ?")
+            .bind(url)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use crate::testing::{save_word, Fixture};
+    use chrono::TimeZone;
     use spread_core::model::SourceType;
+    use spread_core::timezone::Tz;
+
+    /// Raw database access the tests below use to set up what the port cannot.
+    #[async_trait]
+    pub(crate) trait TestStorage: StoragePort + Sized {
+        /// SQL timestamp `days` days before now
+        fn days_ago(days: i64) -> String;
+        fn with_algorithm(self, algorithm: Algorithm) -> Self;
+        async fn execute(&self, sql: &str);
+        async fn fetch_i64(&self, sql: &str) -> i64;
+        async fn fetch_string(&self, sql: &str) -> String;
+        /// Make saving the word `lemma` fail, or succeed again
+        async fn reject_word(&self, lemma: &str, reject: bool);
+    }
+
+    #[async_trait]
+    impl TestStorage for SqliteStorage {
+        fn days_ago(days: i64) -> String {
+            format!("datetime('now', '-{} days')", days)
+        }
+
+        fn with_algorithm(self, algorithm: Algorithm) -> Self {
+            SqliteStorage::with_algorithm(self, algorithm)
+        }
+
+        async fn execute(&self, sql: &str) {
+            sqlx::query(sql).execute(&self.pool).await.unwrap();
+        }
+
+        async fn fetch_i64(&self, sql: &str) -> i64 {
+            sqlx::query_scalar(sql).fetch_one(&self.pool).await.unwrap()
+        }
+
+        async fn fetch_string(&self, sql: &str) -> String {
+            sqlx::query_scalar(sql).fetch_one(&self.pool).await.unwrap()
+        }
 
-    #[tokio::test]
-    async fn test_article_crud() {
-        let storage = SqliteStorage::new("sqlite::memory:")
+        async fn reject_word(&self, lemma: &str, reject: bool) {
+            let sql = if reject {
+                format!(
+                    "CREATE TRIGGER reject_word BEFORE INSERT ON words WHEN NEW.lemma = '{}'
+                     BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+                    lemma
+                )
+            } else {
+                "DROP TRIGGER reject_word".to_string()
+            };
+            self.execute(&sql).await;
+        }
+    }
+
+    async fn sqlite_storage(_test: &str) -> SqliteStorage {
+        SqliteStorage::new("sqlite::memory:")
             .await
-            .expect("Failed to create storage");
+            .expect("Failed to create storage")
+    }
+
+    async fn memory_storage(_test: &str) -> InMemoryStorage {
+        InMemoryStorage::new()
+    }
+
+    /// One test per name, run on the storage `$storage(name)` returns.
+    macro_rules! backend_tests {
+        ($storage:path; $($test:ident),* $(,)?) => {
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    super::$test($storage(stringify!($test)).await).await;
+                }
+            )*
+        };
+    }
 
+    /// Run each generic test against a fresh database of every backend. Tests
+    /// that set up data with SQL skip the in-memory storage.
+    macro_rules! storage_tests {
+        (port: [$($port:ident),* $(,)?], sql: [$($sql:ident),* $(,)?] $(,)?) => {
+            mod sqlite {
+                backend_tests!(super::sqlite_storage; $($port,)* $($sql,)*);
+            }
+
+            #[cfg(feature = "postgres")]
+            mod postgres {
+                backend_tests!(crate::postgres::tests::storage; $($port,)* $($sql,)*);
+            }
+
+            mod memory {
+                backend_tests!(super::memory_storage; $($port,)*);
+            }
+        };
+    }
+
+    storage_tests!(
+        port: [
+            test_article_crud,
+            test_vocabulary_crud,
+            test_duplicate_article_ignored,
+            test_query_all_vocab,
+            test_lexical_fields_roundtrip,
+            test_search_vocab,
+            test_full_text_search,
+            test_get_random_vocab,
+            test_known_words,
+            test_same_word_from_multiple_articles_is_merged,
+            test_edited_fields_survive_reprocessing,
+            test_merge_vocab,
+            test_rename_to_saved_lemma_is_rejected,
+            test_suspended_words_are_not_reviewed,
+            test_query_vocab_pages_and_sorts,
+            test_get_mastered_words,
+            test_pipeline_runs,
+            test_work_queue,
+        ],
+        sql: [
+            test_save_article_with_vocab,
+            test_full_text_index_follows_changes,
+            test_query_vocab_filters,
+            test_tags_and_decks,
+            test_record_review_and_due_vocab,
+            test_fsrs_algorithm_is_used,
+        ],
+    );
+
+    async fn test_article_crud<S: StoragePort>(storage: S) {
         let article = Article {
+            id: None,
             url: "https://example.com/test".to_string(),
             title: "Test Article".to_string(),
             content: "This is test content.".to_string(),
             source: SourceType::RSS,
+            feed_url: None,
             published_at: Utc::now(),
             collected_at: Utc::now(),
         };
@@ -233,17 +1883,14 @@ mod tests {
             .expect("exists check failed"));
     }
 
-    #[tokio::test]
-    async fn test_vocabulary_crud() {
-        let storage = SqliteStorage::new("sqlite::memory:")
-            .await
-            .expect("Failed to create storage");
-
+    async fn test_vocabulary_crud<S: StoragePort>(storage: S) {
         let article = Article {
+            id: None,
             url: "https://example.com/vocab-test".to_string(),
             title: "Vocab Test Article".to_string(),
             content: "Content with vocabulary.".to_string(),
             source: SourceType::Manual,
+            feed_url: None,
             published_at: Utc::now(),
             collected_at: Utc::now(),
         };
@@ -258,22 +1905,106 @@ mod tests {
             definition: "A collection of words".to_string(),
             context_sentence: "Content with vocabulary.".to_string(),
             source_url: article.url.clone(),
+            ..Default::default()
         };
 
         storage.save_vocab(&vocab).await.expect("save_vocab failed");
+
+        let id = storage
+            .query_vocab(&VocabQuery::new())
+            .await
+            .unwrap()
+            .vocabulary[0]
+            .id
+            .expect("reads return ids");
+        let mut saved = storage
+            .get_vocab_by_id(id)
+            .await
+            .unwrap()
+            .expect("saved word is found by id");
+        assert_eq!(saved.word, "vocabulary");
+
+        saved.definition = "Words known to a person".to_string();
+        saved.synonyms = vec!["lexicon".to_string()];
+        storage
+            .update_vocab(&saved)
+            .await
+            .expect("update_vocab failed");
+        let updated = storage.get_vocab_by_id(id).await.unwrap().unwrap();
+        assert_eq!(updated.definition, "Words known to a person");
+        assert_eq!(updated.synonyms, ["lexicon"]);
+        assert_eq!(updated.occurrences.len(), 1);
+        assert_eq!(storage.search_vocab("lexicon").await.unwrap().len(), 0);
+        assert_eq!(storage.search_vocab("person").await.unwrap().len(), 1);
+
+        let unsaved = Vocabulary {
+            id: Some(id + 100),
+            ..updated.clone()
+        };
+        assert!(matches!(
+            storage.update_vocab(&unsaved).await,
+            Err(CoreError::NotFound(_))
+        ));
+
+        storage.record_review(id, Grade::Good).await.unwrap();
+        assert!(storage.delete_vocab(id).await.unwrap());
+        assert!(!storage.delete_vocab(id).await.unwrap());
+        assert!(storage.get_vocab_by_id(id).await.unwrap().is_none());
+        assert!(storage.search_vocab("person").await.unwrap().is_empty());
+        assert!(storage
+            .get_due_vocab(10, &TagFilter::new())
+            .await
+            .unwrap()
+            .is_empty());
     }
 
-    #[tokio::test]
-    async fn test_duplicate_article_ignored() {
-        let storage = SqliteStorage::new("sqlite::memory:")
+    async fn test_save_article_with_vocab<S: TestStorage>(storage: S) {
+        let article = Article {
+            id: None,
+            url: "https://example.com/atomic".to_string(),
+            title: "Atomic".to_string(),
+            content: "Fame is ephemeral. Ties are tenuous.".to_string(),
+            source: SourceType::RSS,
+            feed_url: None,
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        let vocab = |word: &str| Vocabulary {
+            word: word.to_string(),
+            definition: "Definition".to_string(),
+            context_sentence: "Sentence.".to_string(),
+            ..Default::default()
+        };
+
+        // A failing word rolls back the article and the words before it
+        storage.reject_word("tenuous", true).await;
+        let words = [vocab("ephemeral"), vocab("tenuous")];
+        assert!(storage
+            .save_article_with_vocab(&article, &words)
+            .await
+            .is_err());
+        assert!(!storage.exists(&article.url).await.unwrap());
+        assert!(storage.search_vocab("ephemeral").await.unwrap().is_empty());
+
+        storage.reject_word("tenuous", false).await;
+        storage
+            .save_article_with_vocab(&article, &words)
             .await
-            .expect("Failed to create storage");
+            .expect("save_article_with_vocab failed");
+        assert!(storage.exists(&article.url).await.unwrap());
+        let saved = storage.query_vocab(&VocabQuery::new()).await.unwrap();
+        assert_eq!(saved.vocabulary.len(), 2);
+        assert!(saved.vocabulary.iter().all(|v| v.source_url == article.url));
+    }
 
+    async fn test_duplicate_article_ignored<S: StoragePort>(storage: S) {
         let article = Article {
+            id: None,
             url: "https://example.com/duplicate".to_string(),
             title: "Original Title".to_string(),
             content: "Original content.".to_string(),
             source: SourceType::Youtube,
+            feed_url: None,
             published_at: Utc::now(),
             collected_at: Utc::now(),
         };
@@ -284,10 +2015,12 @@ mod tests {
             .expect("first save failed");
 
         let duplicate = Article {
+            id: None,
             url: "https://example.com/duplicate".to_string(),
             title: "Different Title".to_string(),
             content: "Different content.".to_string(),
             source: SourceType::RSS,
+            feed_url: None,
             published_at: Utc::now(),
             collected_at: Utc::now(),
         };
@@ -298,17 +2031,14 @@ mod tests {
             .expect("duplicate save should not error");
     }
 
-    #[tokio::test]
-    async fn test_get_all_vocab() {
-        let storage = SqliteStorage::new("sqlite::memory:")
-            .await
-            .expect("Failed to create storage");
-
+    async fn test_query_all_vocab<S: StoragePort>(storage: S) {
         let article = Article {
+            id: None,
             url: "https://example.com/test".to_string(),
             title: "Test".to_string(),
             content: "Test content.".to_string(),
             source: SourceType::RSS,
+            feed_url: None,
             published_at: Utc::now(),
             collected_at: Utc::now(),
         };
@@ -319,32 +2049,85 @@ mod tests {
             definition: "A trial".to_string(),
             context_sentence: "This is a test.".to_string(),
             source_url: article.url.clone(),
+            ..Default::default()
         };
         let vocab2 = Vocabulary {
             word: "example".to_string(),
             definition: "A sample".to_string(),
             context_sentence: "This is an example.".to_string(),
             source_url: article.url.clone(),
+            ..Default::default()
         };
 
         storage.save_vocab(&vocab1).await.unwrap();
         storage.save_vocab(&vocab2).await.unwrap();
 
-        let all = storage.get_all_vocab().await.unwrap();
+        let all = storage
+            .query_vocab(&VocabQuery::new())
+            .await
+            .unwrap()
+            .vocabulary;
         assert_eq!(all.len(), 2);
     }
 
-    #[tokio::test]
-    async fn test_search_vocab() {
-        let storage = SqliteStorage::new("sqlite::memory:")
-            .await
-            .expect("Failed to create storage");
+    async fn test_lexical_fields_roundtrip<S: StoragePort>(storage: S) {
+        let article = Article {
+            id: None,
+            url: "https://example.com/lexical".to_string(),
+            title: "Lexical".to_string(),
+            content: "Content.".to_string(),
+            source: SourceType::RSS,
+            feed_url: None,
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        storage.save_article(&article).await.unwrap();
+
+        let rich = Vocabulary {
+            word: "mitigate".to_string(),
+            definition: "To make less severe".to_string(),
+            context_sentence: "Patches mitigate the risk.".to_string(),
+            source_url: article.url.clone(),
+            part_of_speech: Some("verb".to_string()),
+            ipa: Some("/ˈmɪtɪɡeɪt/".to_string()),
+            cefr_level: Some("C1".to_string()),
+            synonyms: vec!["alleviate".to_string(), "lessen".to_string()],
+            antonyms: vec!["aggravate".to_string()],
+            collocations: vec!["mitigate the risk".to_string()],
+            example_sentence: Some("Trees mitigate urban heat.".to_string()),
+            ..Default::default()
+        };
+        let plain = Vocabulary {
+            word: "plain".to_string(),
+            definition: "Simple".to_string(),
+            context_sentence: "A plain word.".to_string(),
+            source_url: article.url.clone(),
+            ..Default::default()
+        };
+        storage.save_vocab(&rich).await.unwrap();
+        storage.save_vocab(&plain).await.unwrap();
+
+        let loaded = storage.search_vocab("mitigate").await.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].part_of_speech.as_deref(), Some("verb"));
+        assert_eq!(loaded[0].cefr_level.as_deref(), Some("C1"));
+        assert_eq!(loaded[0].synonyms, vec!["alleviate", "lessen"]);
+        assert_eq!(loaded[0].antonyms, vec!["aggravate"]);
+        assert_eq!(loaded[0].collocations, vec!["mitigate the risk"]);
 
+        let loaded = storage.search_vocab("plain").await.unwrap();
+        assert!(loaded[0].part_of_speech.is_none());
+        assert!(loaded[0].synonyms.is_empty());
+    }
+
+    async fn test_search_vocab<S: StoragePort>(storage: S) {
         let article = Article {
+            id: None,
             url: "https://example.com/search".to_string(),
             title: "Search Test".to_string(),
             content: "Content.".to_string(),
             source: SourceType::RSS,
+            feed_url: None,
             published_at: Utc::now(),
             collected_at: Utc::now(),
         };
@@ -355,6 +2138,7 @@ mod tests {
             definition: "Finding good things by chance".to_string(),
             context_sentence: "It was serendipity.".to_string(),
             source_url: article.url.clone(),
+            ..Default::default()
         };
         storage.save_vocab(&vocab).await.unwrap();
 
@@ -366,21 +2150,135 @@ mod tests {
         assert!(no_results.is_empty());
     }
 
-    #[tokio::test]
-    async fn test_get_random_vocab() {
-        let storage = SqliteStorage::new("sqlite::memory:")
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("serendip").as_deref(), Some(r#""serendip"*"#));
+        assert_eq!(
+            fts_query(r#"risk "mitigate the" ubiq*"#).as_deref(),
+            Some(r#""risk"* "mitigate the" "ubiq"*"#)
+        );
+        // Unclosed quotes run to the end; punctuation alone is ignored
+        assert_eq!(fts_query(r#"a "b c"#).as_deref(), Some(r#""a"* "b c""#));
+        assert_eq!(fts_query(" - ? "), None);
+    }
+
+    async fn test_full_text_search<S: StoragePort>(storage: S) {
+        let article = Article {
+            id: None,
+            url: "https://example.com/fts".to_string(),
+            title: "Heat islands".to_string(),
+            content: "Cities mitigate urban heat with trees and reflective roofs.".to_string(),
+            source: SourceType::RSS,
+            feed_url: None,
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        storage.save_article(&article).await.unwrap();
+
+        for (word, definition, sentence) in [
+            (
+                "mitigate",
+                "To make less severe",
+                "Cities mitigate urban heat.",
+            ),
+            (
+                "reflective",
+                "Sending back light",
+                "Reflective roofs mitigate heat.",
+            ),
+        ] {
+            storage
+                .save_vocab(&Vocabulary {
+                    word: word.to_string(),
+                    definition: definition.to_string(),
+                    context_sentence: sentence.to_string(),
+                    source_url: article.url.clone(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        // A match on the word outranks one in a context sentence
+        let found = storage.search_vocab("mitigate").await.unwrap();
+        let words: Vec<&str> = found.iter().map(|v| v.word.as_str()).collect();
+        assert_eq!(words, ["mitigate", "reflective"]);
+
+        // Stemming and definitions
+        assert_eq!(storage.search_vocab("mitigating").await.unwrap().len(), 2);
+        assert_eq!(storage.search_vocab("severe").await.unwrap().len(), 1);
+        assert_eq!(storage.search_vocab("roof").await.unwrap().len(), 1);
+
+        // Phrases must match in order
+        assert_eq!(
+            storage.search_vocab(r#""urban heat""#).await.unwrap().len(),
+            1
+        );
+        assert!(storage
+            .search_vocab(r#""heat urban""#)
             .await
-            .expect("Failed to create storage");
+            .unwrap()
+            .is_empty());
+
+        let hits = storage.search("reflective roofs", 10).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        match &hits[0] {
+            SearchHit::Word {
+                vocabulary,
+                snippet,
+                ..
+            } => {
+                assert_eq!(vocabulary.word, "reflective");
+                assert!(snippet.contains("**Reflective** **roofs**"));
+            }
+            other => panic!("expected a word hit, got {:?}", other),
+        }
+        match &hits[1] {
+            SearchHit::Article { url, snippet, .. } => {
+                assert_eq!(url, &article.url);
+                assert!(snippet.contains("**reflective** **roofs**"));
+            }
+            other => panic!("expected an article hit, got {:?}", other),
+        }
+    }
+
+    async fn test_full_text_index_follows_changes<S: TestStorage>(storage: S) {
+        let id = save_word(&storage, "tenuous").await;
+
+        storage
+            .execute(&format!(
+                "UPDATE words SET definition = 'Very weak or slight' WHERE id = {}",
+                id
+            ))
+            .await;
+        assert_eq!(storage.search_vocab("slight").await.unwrap().len(), 1);
+
+        storage
+            .execute(&format!("DELETE FROM occurrences WHERE word_id = {}", id))
+            .await;
+        storage
+            .execute(&format!("DELETE FROM words WHERE id = {}", id))
+            .await;
+        assert!(storage.search_vocab("tenuous").await.unwrap().is_empty());
+
+        storage
+            .execute("UPDATE articles SET content = '' WHERE url = 'https://example.com/tenuous'")
+            .await;
+        assert!(storage.search("essay", 10).await.unwrap().is_empty());
+    }
 
+    async fn test_get_random_vocab<S: StoragePort>(storage: S) {
         // Empty DB should return None
         let none = storage.get_random_vocab().await.unwrap();
         assert!(none.is_none());
 
         let article = Article {
+            id: None,
             url: "https://example.com/random".to_string(),
             title: "Random Test".to_string(),
             content: "Content.".to_string(),
             source: SourceType::RSS,
+            feed_url: None,
             published_at: Utc::now(),
             collected_at: Utc::now(),
         };
@@ -391,6 +2289,7 @@ mod tests {
             definition: "By chance".to_string(),
             context_sentence: "Random selection.".to_string(),
             source_url: article.url.clone(),
+            ..Default::default()
         };
         storage.save_vocab(&vocab).await.unwrap();
 
@@ -398,4 +2297,696 @@ mod tests {
         assert!(some.is_some());
         assert_eq!(some.unwrap().word, "random");
     }
+
+    async fn test_known_words<S: StoragePort>(storage: S) {
+        let added = storage
+            .add_known_words(&[
+                "Ubiquitous".to_string(),
+                " ephemeral ".to_string(),
+                "ubiquitous".to_string(),
+                "".to_string(),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(added, 2);
+
+        let mut known = storage.get_known_words().await.unwrap();
+        known.sort();
+        assert_eq!(known, vec!["ephemeral", "ubiquitous"]);
+
+        assert!(storage.remove_known_word("UBIQUITOUS").await.unwrap());
+        assert!(!storage.remove_known_word("ubiquitous").await.unwrap());
+        assert_eq!(storage.get_known_words().await.unwrap(), vec!["ephemeral"]);
+    }
+
+    async fn test_same_word_from_multiple_articles_is_merged<S: StoragePort>(storage: S) {
+        for (i, sentence) in ["Phones are ubiquitous.", "Cameras are Ubiquitous now."]
+            .iter()
+            .enumerate()
+        {
+            let article = Article {
+                id: None,
+                url: format!("https://example.com/{}", i),
+                title: "Post".to_string(),
+                content: sentence.to_string(),
+                source: SourceType::RSS,
+                feed_url: None,
+                published_at: Utc::now(),
+                collected_at: Utc::now(),
+            };
+            storage.save_article(&article).await.unwrap();
+
+            let vocab = Vocabulary {
+                word: if i == 0 { "ubiquitous" } else { "Ubiquitous" }.to_string(),
+                definition: format!("Present everywhere ({})", i),
+                context_sentence: sentence.to_string(),
+                source_url: article.url.clone(),
+                cefr_level: (i == 1).then(|| "C1".to_string()),
+                ..Default::default()
+            };
+            storage.save_vocab(&vocab).await.unwrap();
+            // Saving the same sighting twice is a no-op
+            storage.save_vocab(&vocab).await.unwrap();
+        }
+
+        let all = storage
+            .query_vocab(&VocabQuery::new())
+            .await
+            .unwrap()
+            .vocabulary;
+        assert_eq!(all.len(), 1);
+
+        let vocab = &all[0];
+        assert_eq!(vocab.word, "ubiquitous");
+        assert_eq!(vocab.definition, "Present everywhere (0)");
+        assert_eq!(vocab.context_sentence, "Phones are ubiquitous.");
+        assert_eq!(vocab.source_url, "https://example.com/0");
+        // Missing lexical fields are filled in by later sightings
+        assert_eq!(vocab.cefr_level.as_deref(), Some("C1"));
+        assert_eq!(vocab.occurrences.len(), 2);
+        assert_eq!(vocab.occurrences[1].source_url, "https://example.com/1");
+        assert_eq!(vocab.occurrences[1].definition, "Present everywhere (1)");
+
+        let found = storage.search_vocab("ubiq").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].occurrences.len(), 2);
+    }
+
+    async fn test_edited_fields_survive_reprocessing<S: StoragePort>(storage: S) {
+        let id = save_word(&storage, "lucid").await;
+
+        let mut vocab = storage.get_vocab_by_id(id).await.unwrap().unwrap();
+        vocab.ipa = Some("/ˈluːsɪd/".to_string());
+        vocab.edited_fields = vec!["ipa".to_string(), "synonyms".to_string()];
+        storage.update_vocab(&vocab).await.unwrap();
+
+        // A later sighting fills in missing fields but not edited ones
+        let article = Article {
+            id: None,
+            url: "https://example.com/again".to_string(),
+            title: "Again".to_string(),
+            content: "A lucid essay.".to_string(),
+            source: SourceType::RSS,
+            feed_url: None,
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        let sighting = Vocabulary {
+            word: "lucid".to_string(),
+            definition: "Clear".to_string(),
+            context_sentence: "A lucid essay.".to_string(),
+            ipa: Some("/wrong/".to_string()),
+            cefr_level: Some("C1".to_string()),
+            synonyms: vec!["clear".to_string()],
+            ..Default::default()
+        };
+        storage
+            .save_article_with_vocab(&article, &[sighting])
+            .await
+            .unwrap();
+
+        let vocab = storage.get_vocab_by_id(id).await.unwrap().unwrap();
+        assert_eq!(vocab.ipa.as_deref(), Some("/ˈluːsɪd/"));
+        assert!(vocab.synonyms.is_empty());
+        assert_eq!(vocab.cefr_level.as_deref(), Some("C1"));
+        assert_eq!(vocab.occurrences.len(), 2);
+        assert_eq!(vocab.edited_fields, ["ipa", "synonyms"]);
+    }
+
+    async fn test_rename_to_saved_lemma_is_rejected<S: StoragePort>(storage: S) {
+        let analyse = save_word(&storage, "analyse").await;
+        save_word(&storage, "analyze").await;
+
+        let mut vocab = storage.get_vocab_by_id(analyse).await.unwrap().unwrap();
+        vocab.word = " Analyze".to_string();
+        vocab.definition = "Examine in detail".to_string();
+        match storage.update_vocab(&vocab).await {
+            Err(CoreError::Database(message)) => {
+                assert!(message.contains("already saved as 'analyze'"));
+                assert!(message.contains("spread word merge"));
+            }
+            other => panic!("expected a lemma conflict, got {:?}", other),
+        }
+        // Nothing was changed
+        let unchanged = storage.get_vocab_by_id(analyse).await.unwrap().unwrap();
+        assert_eq!(unchanged.word, "analyse");
+        assert_eq!(unchanged.definition, "Definition of analyse");
+
+        // Changing only the spelling of its own lemma is fine
+        vocab.word = "Analyse".to_string();
+        storage.update_vocab(&vocab).await.unwrap();
+        let renamed = storage.get_vocab_by_id(analyse).await.unwrap().unwrap();
+        assert_eq!(renamed.word, "Analyse");
+    }
+
+    async fn test_merge_vocab<S: StoragePort>(storage: S) {
+        let analyse = save_word(&storage, "analyse").await;
+        let analyze = save_word(&storage, "analyze").await;
+        let mut from = storage.get_vocab_by_id(analyse).await.unwrap().unwrap();
+        from.part_of_speech = Some("verb".to_string());
+        storage.update_vocab(&from).await.unwrap();
+        storage.record_review(analyse, Grade::Good).await.unwrap();
+
+        let merged = storage.merge_vocab(analyse, analyze).await.unwrap();
+        assert_eq!(merged.id, Some(analyze));
+        assert_eq!(merged.word, "analyze");
+        assert_eq!(merged.occurrences.len(), 2);
+        assert_eq!(merged.part_of_speech.as_deref(), Some("verb"));
+        assert!(storage.get_vocab_by_id(analyse).await.unwrap().is_none());
+        // The review schedule moves over
+        assert!(storage
+            .get_new_vocab(10, &TagFilter::new())
+            .await
+            .unwrap()
+            .is_empty());
+
+        // The merged spelling now finds and collects into the kept word
+        let found = storage.get_vocab_by_word("Analyse").await.unwrap().unwrap();
+        assert_eq!(found.id, Some(analyze));
+        let article = Article {
+            id: None,
+            url: "https://example.com/later".to_string(),
+            title: "Later".to_string(),
+            content: "We analyse data.".to_string(),
+            source: SourceType::RSS,
+            feed_url: None,
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        let sighting = Vocabulary {
+            word: "analyse".to_string(),
+            definition: "Examine".to_string(),
+            context_sentence: "We analyse data.".to_string(),
+            ..Default::default()
+        };
+        storage
+            .save_article_with_vocab(&article, &[sighting])
+            .await
+            .unwrap();
+        let all = storage.query_vocab(&VocabQuery::new()).await.unwrap();
+        assert_eq!(all.vocabulary.len(), 1);
+        assert_eq!(all.vocabulary[0].occurrences.len(), 3);
+        assert_eq!(storage.search_vocab("data").await.unwrap().len(), 1);
+
+        assert!(storage.merge_vocab(analyze, analyze).await.is_err());
+        assert!(matches!(
+            storage.merge_vocab(analyse, analyze).await,
+            Err(CoreError::NotFound(_))
+        ));
+    }
+
+    async fn test_suspended_words_are_not_reviewed<S: StoragePort>(storage: S) {
+        let id = save_word(&storage, "paris").await;
+
+        let mut vocab = storage.get_vocab_by_id(id).await.unwrap().unwrap();
+        vocab.suspended = true;
+        storage.update_vocab(&vocab).await.unwrap();
+
+        assert!(storage
+            .get_new_vocab(10, &TagFilter::new())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(
+            storage
+                .get_vocab_by_id(id)
+                .await
+                .unwrap()
+                .unwrap()
+                .suspended
+        );
+    }
+
+    fn words(page: &VocabPage) -> Vec<&str> {
+        page.vocabulary.iter().map(|v| v.word.as_str()).collect()
+    }
+
+    async fn test_query_vocab_pages_and_sorts<S: StoragePort>(storage: S) {
+        for word in ["delta", "alpha", "charlie", "bravo", "echo"] {
+            save_word(&storage, word).await;
+        }
+
+        // Cursor paging walks every word exactly once
+        let first = storage
+            .query_vocab(&VocabQuery::new().with_limit(2))
+            .await
+            .unwrap();
+        assert_eq!(words(&first), ["delta", "alpha"]);
+        let cursor = first.next_cursor.clone().unwrap();
+        let second = storage
+            .query_vocab(&VocabQuery::new().with_limit(2).with_cursor(cursor))
+            .await
+            .unwrap();
+        assert_eq!(words(&second), ["charlie", "bravo"]);
+        let third = storage
+            .query_vocab(
+                &VocabQuery::new()
+                    .with_limit(2)
+                    .with_cursor(second.next_cursor.clone().unwrap()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(words(&third), ["echo"]);
+        assert!(third.next_cursor.is_none());
+
+        let page = storage
+            .query_vocab(&VocabQuery::new().with_limit(2).with_offset(1))
+            .await
+            .unwrap();
+        assert_eq!(words(&page), ["alpha", "charlie"]);
+
+        let newest = storage
+            .query_vocab(&VocabQuery::new().with_sort(VocabSort::Newest).with_limit(2))
+            .await
+            .unwrap();
+        assert_eq!(words(&newest), ["echo", "bravo"]);
+
+        let query = VocabQuery::new()
+            .with_sort(VocabSort::Alphabetical)
+            .with_limit(3);
+        let first = storage.query_vocab(&query).await.unwrap();
+        assert_eq!(words(&first), ["alpha", "bravo", "charlie"]);
+        let second = storage
+            .query_vocab(&query.with_cursor(first.next_cursor.unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(words(&second), ["delta", "echo"]);
+
+        assert!(storage
+            .query_vocab(&VocabQuery::new().with_cursor("not-a-cursor"))
+            .await
+            .is_err());
+    }
+
+    async fn test_query_vocab_filters<S: TestStorage>(storage: S) {
+        let day = |d: u32| Utc.with_ymd_and_hms(2026, 3, d, 12, 0, 0).unwrap();
+        let feed_a = "https://a.example/feed.xml";
+        let feed_b = "https://b.example/feed.xml";
+
+        for (word, feed_url, source, collected_at) in [
+            ("ephemeral", feed_a, SourceType::RSS, day(1)),
+            ("tenuous", feed_a, SourceType::RSS, day(2)),
+            ("lucid", feed_b, SourceType::Manual, day(2)),
+        ] {
+            Fixture::word(word)
+                .with_feed(feed_url)
+                .with_source(source)
+                .with_collected_at(collected_at)
+                .save(&storage)
+                .await;
+        }
+
+        let on_day_two = VocabQuery::new().with_collected_on(day(2).date_naive(), Tz::UTC);
+        let page = storage.query_vocab(&on_day_two).await.unwrap();
+        assert_eq!(words(&page), ["tenuous", "lucid"]);
+
+        // Noon UTC on the 1st is already the 2nd in Auckland
+        let auckland =
+            VocabQuery::new().with_collected_on(day(2).date_naive(), Tz::Pacific__Auckland);
+        let page = storage.query_vocab(&auckland).await.unwrap();
+        assert_eq!(words(&page), ["ephemeral"]);
+
+        let page = storage
+            .query_vocab(&on_day_two.clone().with_feed(feed_a))
+            .await
+            .unwrap();
+        assert_eq!(words(&page), ["tenuous"]);
+
+        let page = storage
+            .query_vocab(&VocabQuery::new().with_source(SourceType::Manual))
+            .await
+            .unwrap();
+        assert_eq!(words(&page), ["lucid"]);
+
+        let page = storage
+            .query_vocab(&VocabQuery::new().with_collected_to(day(2)))
+            .await
+            .unwrap();
+        assert_eq!(words(&page), ["ephemeral"]);
+
+        storage
+            .execute("INSERT INTO tags (name) VALUES ('toefl')")
+            .await;
+        storage
+            .execute(
+                "INSERT INTO word_tags (word_id, tag_id) SELECT w.id, t.id FROM words w, tags t WHERE w.lemma = 'lucid'",
+            )
+            .await;
+        let page = storage
+            .query_vocab(&VocabQuery::new().with_tag("#TOEFL"))
+            .await
+            .unwrap();
+        assert_eq!(words(&page), ["lucid"]);
+
+        let id = storage
+            .fetch_i64("SELECT id FROM words WHERE lemma = 'tenuous'")
+            .await;
+        storage.record_review(id, Grade::Good).await.unwrap();
+
+        let status = |s| VocabQuery::new().with_review_status(s);
+        let page = storage
+            .query_vocab(&status(ReviewStatus::New))
+            .await
+            .unwrap();
+        assert_eq!(words(&page), ["ephemeral", "lucid"]);
+        let page = storage
+            .query_vocab(&status(ReviewStatus::Learning))
+            .await
+            .unwrap();
+        assert_eq!(words(&page), ["tenuous"]);
+        assert!(storage
+            .query_vocab(&status(ReviewStatus::Due))
+            .await
+            .unwrap()
+            .vocabulary
+            .is_empty());
+        assert!(storage
+            .query_vocab(&status(ReviewStatus::Mastered))
+            .await
+            .unwrap()
+            .vocabulary
+            .is_empty());
+    }
+
+    async fn test_tags_and_decks<S: TestStorage>(storage: S) {
+        let article = Article {
+            id: None,
+            url: "https://example.com/rust".to_string(),
+            title: "Post".to_string(),
+            content: String::new(),
+            source: SourceType::RSS,
+            feed_url: None,
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        let tagged = Vocabulary {
+            word: "borrow".to_string(),
+            definition: "Take temporarily".to_string(),
+            context_sentence: "Borrow the value.".to_string(),
+            source_url: article.url.clone(),
+            tags: vec!["Rust blog".to_string(), "#rust".to_string()],
+            ..Default::default()
+        };
+        storage
+            .save_article_with_vocab(&article, &[tagged])
+            .await
+            .unwrap();
+        let borrow = storage.get_vocab_by_word("borrow").await.unwrap().unwrap();
+        assert_eq!(borrow.tags, ["rust", "rust-blog"]);
+        let lucid = save_word(&storage, "lucid").await;
+        let tenuous = save_word(&storage, "tenuous").await;
+
+        let tags = ["finance".to_string(), "rust".to_string()];
+        assert_eq!(storage.add_tags(lucid, &tags).await.unwrap(), 2);
+        assert_eq!(storage.add_tags(lucid, &tags).await.unwrap(), 0);
+        assert!(matches!(
+            storage.add_tags(999, &tags).await,
+            Err(CoreError::NotFound(_))
+        ));
+        assert_eq!(
+            storage
+                .remove_tags(lucid, &["Rust".to_string(), "toefl".to_string()])
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            storage.get_tags().await.unwrap(),
+            [
+                ("finance".to_string(), 1),
+                ("rust".to_string(), 1),
+                ("rust-blog".to_string(), 1)
+            ]
+        );
+
+        let deck = Deck {
+            name: "Reading".to_string(),
+            tags: vec!["finance".to_string(), "Rust blog".to_string()],
+        };
+        storage.save_deck(&deck).await.unwrap();
+        let page = storage
+            .query_vocab(&VocabQuery::new().with_deck("Reading"))
+            .await
+            .unwrap();
+        assert_eq!(words(&page), ["borrow", "lucid"]);
+
+        let filter = TagFilter::new().with_deck("Reading");
+        let new = storage.get_new_vocab(10, &filter).await.unwrap();
+        assert_eq!(new.len(), 2);
+        storage.record_review(lucid, Grade::Again).await.unwrap();
+        storage.record_review(tenuous, Grade::Again).await.unwrap();
+        storage
+            .execute(&format!(
+                "UPDATE review_states SET due_at = {}",
+                S::days_ago(1)
+            ))
+            .await;
+        let due = storage
+            .get_due_vocab(10, &TagFilter::new().with_tag("finance"))
+            .await
+            .unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].word_id, lucid);
+
+        // Saving replaces the deck's tags
+        storage
+            .save_deck(&Deck {
+                name: "Reading".to_string(),
+                tags: vec!["rust".to_string()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_decks().await.unwrap(),
+            [Deck {
+                name: "Reading".to_string(),
+                tags: vec!["rust".to_string()],
+            }]
+        );
+        assert!(storage.delete_deck("Reading").await.unwrap());
+        assert!(!storage.delete_deck("Reading").await.unwrap());
+        assert!(storage.get_decks().await.unwrap().is_empty());
+        assert_eq!(storage.get_tags().await.unwrap().len(), 3);
+    }
+
+    async fn test_record_review_and_due_vocab<S: TestStorage>(storage: S) {
+        let ephemeral = save_word(&storage, "ephemeral").await;
+        let tenuous = save_word(&storage, "tenuous").await;
+
+        // Unreviewed words are new, not due
+        assert!(storage
+            .get_due_vocab(10, &TagFilter::new())
+            .await
+            .unwrap()
+            .is_empty());
+        let new = storage.get_new_vocab(10, &TagFilter::new()).await.unwrap();
+        assert_eq!(new.len(), 2);
+        assert_eq!(new[0].word_id, ephemeral);
+        assert_eq!(new[0].state.reps, 0);
+        assert_eq!(
+            storage
+                .get_new_vocab(1, &TagFilter::new())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let state = storage.record_review(ephemeral, Grade::Good).await.unwrap();
+        assert_eq!(state.reps, 1);
+        assert_eq!(state.interval_days, 1);
+        storage.record_review(tenuous, Grade::Again).await.unwrap();
+        assert!(storage
+            .get_due_vocab(10, &TagFilter::new())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(storage
+            .get_new_vocab(10, &TagFilter::new())
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Make both overdue, tenuous the most
+        for (id, days) in [(ephemeral, 1), (tenuous, 3)] {
+            storage
+                .execute(&format!(
+                    "UPDATE review_states SET due_at = {} WHERE word_id = {}",
+                    S::days_ago(days),
+                    id
+                ))
+                .await;
+        }
+
+        let due = storage.get_due_vocab(10, &TagFilter::new()).await.unwrap();
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].word_id, tenuous);
+        assert_eq!(due[0].vocabulary.word, "tenuous");
+        assert_eq!(due[0].state.lapses, 1);
+        assert_eq!(
+            storage
+                .get_due_vocab(1, &TagFilter::new())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let state = storage.record_review(ephemeral, Grade::Good).await.unwrap();
+        assert_eq!(state.reps, 2);
+        assert_eq!(state.interval_days, 6);
+
+        assert_eq!(
+            storage.fetch_i64("SELECT COUNT(*) FROM review_log").await,
+            3
+        );
+
+        assert!(storage.record_review(9999, Grade::Good).await.is_err());
+    }
+
+    async fn test_fsrs_algorithm_is_used<S: TestStorage>(storage: S) {
+        let storage = storage.with_algorithm(Algorithm::Fsrs);
+        let id = save_word(&storage, "ephemeral").await;
+
+        let state = storage.record_review(id, Grade::Good).await.unwrap();
+        assert!(state.stability > 0.0);
+
+        assert_eq!(
+            storage
+                .fetch_string("SELECT algorithm FROM review_log")
+                .await,
+            "fsrs"
+        );
+    }
+
+    async fn test_get_mastered_words<S: StoragePort>(storage: S) {
+        let ephemeral = save_word(&storage, "ephemeral").await;
+        let tenuous = save_word(&storage, "tenuous").await;
+
+        // 1, 6, 15, then 38 days with SM-2
+        for _ in 0..4 {
+            storage.record_review(ephemeral, Grade::Good).await.unwrap();
+        }
+        storage.record_review(tenuous, Grade::Good).await.unwrap();
+
+        assert_eq!(
+            storage.get_mastered_words().await.unwrap(),
+            vec!["ephemeral"]
+        );
+    }
+
+    async fn test_pipeline_runs<S: StoragePort>(storage: S) {
+        use spread_core::run::{FeedRun, RunStage, RunTrigger};
+
+        let started_at = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+        let mut first = PipelineRun::new(RunTrigger::Scheduled, started_at);
+        first.id = Some(storage.start_run(&first).await.unwrap());
+
+        let stored = storage.get_run(first.id.unwrap()).await.unwrap().unwrap();
+        assert_eq!(stored.trigger, RunTrigger::Scheduled);
+        assert_eq!(stored.started_at, started_at);
+        assert_eq!(stored.finished_at, None);
+
+        first.finished_at = Some(started_at + chrono::Duration::minutes(3));
+        first.cancelled = true;
+        first.stats.articles_saved = 2;
+        first.stats.feed_errors = 1;
+        first.feeds = vec![
+            FeedRun {
+                feed_url: "https://b.example/rss".to_string(),
+                items: 2,
+                articles_saved: 2,
+                vocabularies_saved: 7,
+                error: None,
+            },
+            FeedRun {
+                feed_url: "https://a.example/rss".to_string(),
+                error: Some("HTTP 404".to_string()),
+                ..Default::default()
+            },
+        ];
+        first.record_error(RunStage::Feed, "https://a.example/rss", "HTTP 404");
+        first.record_error(RunStage::Fetch, "https://b.example/post", "timeout");
+        storage.update_run(&first).await.unwrap();
+        assert_eq!(
+            storage.get_run(first.id.unwrap()).await.unwrap().as_ref(),
+            Some(&first)
+        );
+
+        // Updating again replaces feed results and errors
+        first.errors.pop();
+        storage.update_run(&first).await.unwrap();
+        assert_eq!(
+            storage.get_run(first.id.unwrap()).await.unwrap().as_ref(),
+            Some(&first)
+        );
+
+        let second = PipelineRun::new(RunTrigger::Manual, started_at + chrono::Duration::days(1));
+        let second_id = storage.start_run(&second).await.unwrap();
+        let runs = storage.get_runs(None).await.unwrap();
+        assert_eq!(
+            runs.iter().map(|run| run.id.unwrap()).collect::<Vec<_>>(),
+            vec![second_id, first.id.unwrap()]
+        );
+        assert_eq!(runs[1], first);
+        assert_eq!(storage.get_runs(Some(1)).await.unwrap().len(), 1);
+
+        assert_eq!(storage.get_run(second_id + 100).await.unwrap(), None);
+        let unknown = PipelineRun {
+            id: Some(second_id + 100),
+            ..second
+        };
+        assert!(matches!(
+            storage.update_run(&unknown).await,
+            Err(CoreError::NotFound(_))
+        ));
+    }
+
+    async fn test_work_queue<S: StoragePort>(storage: S) {
+        use spread_core::run::QueuedItem;
+
+        let item = |url: &str| QueuedItem {
+            feed_url: "https://blog.example/rss".to_string(),
+            url: url.to_string(),
+            title: format!("Post {}", url),
+            published_at: Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap(),
+        };
+        assert!(storage.get_queued_items().await.unwrap().is_empty());
+
+        let first = [
+            item("https://blog.example/2"),
+            item("https://blog.example/1"),
+        ];
+        assert_eq!(storage.enqueue_items(&first).await.unwrap(), 2);
+        // Already queued URLs keep their place
+        let again = [
+            item("https://blog.example/3"),
+            item("https://blog.example/2"),
+        ];
+        assert_eq!(storage.enqueue_items(&again).await.unwrap(), 1);
+
+        let queued = storage.get_queued_items().await.unwrap();
+        assert_eq!(
+            queued
+                .iter()
+                .map(|item| item.url.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "https://blog.example/2",
+                "https://blog.example/1",
+                "https://blog.example/3"
+            ]
+        );
+        assert_eq!(queued[0], first[0]);
+
+        assert!(storage
+            .dequeue_item("https://blog.example/1")
+            .await
+            .unwrap());
+        assert!(!storage
+            .dequeue_item("https://blog.example/1")
+            .await
+            .unwrap());
+        assert_eq!(storage.get_queued_items().await.unwrap().len(), 2);
+    }
 }
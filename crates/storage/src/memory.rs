@@ -0,0 +1,975 @@
+//! Storage kept in memory, for tests and dry runs.
+
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::BuildHasher;
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use spread_core::error::CoreError;
+use spread_core::model::{
+    Article, Deck, Occurrence, ReviewCard, SearchHit, Vocabulary, SNIPPET_HIGHLIGHT,
+};
+use spread_core::port::StoragePort;
+use spread_core::query::{ReviewStatus, TagFilter, VocabPage, VocabQuery, VocabSort};
+use spread_core::run::{PipelineRun, QueuedItem};
+use spread_core::srs::{Algorithm, Grade, ReviewState, MASTERED_INTERVAL_DAYS};
+
+use crate::porter;
+use crate::{lemma_taken, normalize_lemma, normalize_tag, page_vocab, search_terms, SearchTerm};
+
+/// Words around the match kept in word and article snippets, as in SQLite
+const WORD_SNIPPET_WORDS: usize = 12;
+const ARTICLE_SNIPPET_WORDS: usize = 16;
+
+/// A [`StoragePort`] that keeps everything in memory and forgets it on drop.
+///
+/// Behaves like [`crate::SqliteStorage`] except that search ranks by weighted
+/// term counts rather than BM25.
+#[derive(Default)]
+pub struct InMemoryStorage {
+    state: Mutex<State>,
+    algorithm: Algorithm,
+}
+
+#[derive(Default)]
+struct State {
+    articles: Vec<Article>,
+    /// Saved words by id; `Vocabulary::id` is always set
+    words: BTreeMap<i64, Vocabulary>,
+    next_word_id: i64,
+    /// Lemmas of merged words, and the word they now belong to
+    aliases: HashMap<String, i64>,
+    /// Known lemmas with the batch they were added in
+    known_words: Vec<(String, u64)>,
+    known_batches: u64,
+    /// Deck names with their tags
+    decks: BTreeMap<String, BTreeSet<String>>,
+    reviews: HashMap<i64, ReviewState>,
+    /// Pipeline runs, oldest first; `PipelineRun::id` is its index plus one
+    runs: Vec<PipelineRun>,
+    queue: Vec<QueuedItem>,
+}
+
+impl InMemoryStorage {
+    /// An empty storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the spaced-repetition algorithm used by `record_review`
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Saved articles, in the order they were saved.
+    pub fn articles(&self) -> Vec<Article> {
+        self.state().articles.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // A panic elsewhere cannot leave the state half-updated for readers
+        // that matter, so keep going with it
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl State {
+    fn word(&self, id: i64) -> Result<&Vocabulary, CoreError> {
+        self.words
+            .get(&id)
+            .ok_or_else(|| CoreError::NotFound(format!("Word {}", id)))
+    }
+
+    fn id_of_lemma(&self, lemma: &str) -> Option<i64> {
+        self.words
+            .iter()
+            .find(|(_, vocab)| normalize_lemma(&vocab.word) == lemma)
+            .map(|(id, _)| *id)
+    }
+
+    /// The word with id `id` as the port returns it.
+    fn vocab(&self, id: i64) -> Option<Vocabulary> {
+        let mut vocab = self.words.get(&id)?.clone();
+        vocab.occurrences.sort_by_key(|o| o.extracted_at);
+        if let Some(first) = vocab.occurrences.first() {
+            vocab.context_sentence = first.context_sentence.clone();
+            vocab.source_url = first.source_url.clone();
+        }
+        Some(vocab)
+    }
+
+    fn insert_article(&mut self, article: &Article) {
+        if self.articles.iter().any(|a| a.url == article.url) {
+            return;
+        }
+        let id = self.articles.len() as i64 + 1;
+        self.articles.push(Article {
+            id: Some(id),
+            ..article.clone()
+        });
+    }
+
+    /// Upsert the word and record this sighting; see `insert_vocab` in the
+    /// SQLite storage.
+    fn insert_vocab(&mut self, vocab: &Vocabulary, extracted_at: DateTime<Utc>) {
+        let lemma = normalize_lemma(&vocab.word);
+        let existing = match self.aliases.get(&lemma) {
+            Some(id) => Some(*id),
+            None => self.id_of_lemma(&lemma),
+        };
+
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                self.next_word_id += 1;
+                let id = self.next_word_id;
+                self.words.insert(
+                    id,
+                    Vocabulary {
+                        id: Some(id),
+                        word: vocab.word.trim().to_string(),
+                        definition: vocab.definition.clone(),
+                        context_sentence: String::new(),
+                        source_url: String::new(),
+                        occurrences: Vec::new(),
+                        tags: Vec::new(),
+                        edited_fields: Vec::new(),
+                        suspended: false,
+                        ..vocab.clone()
+                    },
+                );
+                id
+            }
+        };
+
+        let word = self.words.get_mut(&id).expect("word was just found");
+        if existing.is_some() {
+            fill_missing(word, vocab, true);
+        }
+        let occurrence = Occurrence {
+            source_url: vocab.source_url.clone(),
+            context_sentence: vocab.context_sentence.clone(),
+            definition: vocab.definition.clone(),
+            extracted_at,
+        };
+        if !word
+            .occurrences
+            .iter()
+            .any(|o| same_sighting(o, &occurrence))
+        {
+            word.occurrences.push(occurrence);
+        }
+        add_tags(word, &vocab.tags);
+    }
+
+    fn matches_tags(&self, vocab: &Vocabulary, filter: &TagFilter) -> bool {
+        if let Some(tag) = &filter.tag {
+            if !vocab.tags.contains(&normalize_tag(tag)) {
+                return false;
+            }
+        }
+        if let Some(deck) = &filter.deck {
+            let Some(tags) = self.decks.get(deck.trim()) else {
+                return false;
+            };
+            if !vocab.tags.iter().any(|tag| tags.contains(tag)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn matches_query(&self, vocab: &Vocabulary, query: &VocabQuery, now: DateTime<Utc>) -> bool {
+        if query.collected_from.is_some()
+            || query.collected_to.is_some()
+            || query.feed.is_some()
+            || query.source.is_some()
+        {
+            // Article filters must all hold for the same article
+            let seen_in_match = vocab.occurrences.iter().any(|o| {
+                self.articles.iter().any(|a| {
+                    a.url == o.source_url
+                        && query
+                            .collected_from
+                            .is_none_or(|from| a.collected_at >= from)
+                        && query.collected_to.is_none_or(|to| a.collected_at < to)
+                        && query
+                            .feed
+                            .as_ref()
+                            .is_none_or(|feed| a.feed_url.as_ref() == Some(feed))
+                        && query
+                            .source
+                            .as_ref()
+                            .is_none_or(|source| &a.source == source)
+                })
+            });
+            if !seen_in_match {
+                return false;
+            }
+        }
+
+        let filter = TagFilter {
+            tag: query.tag.clone(),
+            deck: query.deck.clone(),
+        };
+        if !self.matches_tags(vocab, &filter) {
+            return false;
+        }
+
+        let review = vocab.id.and_then(|id| self.reviews.get(&id));
+        match query.review_status {
+            Some(ReviewStatus::New) => review.is_none(),
+            Some(ReviewStatus::Due) => review.is_some_and(|r| r.due_at <= now),
+            Some(ReviewStatus::Learning) => {
+                review.is_some_and(|r| r.interval_days < MASTERED_INTERVAL_DAYS)
+            }
+            Some(ReviewStatus::Mastered) => {
+                review.is_some_and(|r| r.interval_days >= MASTERED_INTERVAL_DAYS)
+            }
+            None => true,
+        }
+    }
+
+    /// Words matching a search, best first, with the snippet of their best field.
+    fn match_words(&self, query: &str) -> Vec<(i64, String, f64)> {
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        // Matches in the word itself outrank the definition, then contexts
+        let mut matches: Vec<(i64, String, f64)> = self
+            .words
+            .iter()
+            .filter_map(|(id, vocab)| {
+                let contexts = vocab
+                    .occurrences
+                    .iter()
+                    .map(|o| o.context_sentence.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let fields = [
+                    (vocab.word.as_str(), 10.0),
+                    (vocab.definition.as_str(), 4.0),
+                    (contexts.as_str(), 1.0),
+                ];
+                let (snippet, rank) = match_fields(&fields, &terms, WORD_SNIPPET_WORDS)?;
+                Some((*id, snippet, rank))
+            })
+            .collect();
+        matches.sort_by(|a, b| a.2.total_cmp(&b.2).then(a.0.cmp(&b.0)));
+        matches
+    }
+
+    fn match_articles(&self, query: &str) -> Vec<SearchHit> {
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<(&Article, String, f64)> = self
+            .articles
+            .iter()
+            .filter_map(|article| {
+                let fields = [
+                    (article.title.as_str(), 3.0),
+                    (article.content.as_str(), 1.0),
+                ];
+                let (snippet, rank) = match_fields(&fields, &terms, ARTICLE_SNIPPET_WORDS)?;
+                Some((article, snippet, rank))
+            })
+            .collect();
+        matches.sort_by(|a, b| a.2.total_cmp(&b.2).then(a.0.id.cmp(&b.0.id)));
+
+        matches
+            .into_iter()
+            .map(|(article, snippet, rank)| SearchHit::Article {
+                article_id: article.id.unwrap_or_default(),
+                url: article.url.clone(),
+                title: article.title.clone(),
+                snippet,
+                rank,
+            })
+            .collect()
+    }
+}
+
+/// Fill lexical fields of `word` missing a value from `from`, leaving hand
+/// edits alone when `keep_edited`.
+fn fill_missing(word: &mut Vocabulary, from: &Vocabulary, keep_edited: bool) {
+    let edited = |field: &str| keep_edited && word.edited_fields.iter().any(|f| f == field);
+    if word.part_of_speech.is_none() && !edited("part_of_speech") {
+        word.part_of_speech = from.part_of_speech.clone();
+    }
+    if word.ipa.is_none() && !edited("ipa") {
+        word.ipa = from.ipa.clone();
+    }
+    if word.cefr_level.is_none() && !edited("cefr_level") {
+        word.cefr_level = from.cefr_level.clone();
+    }
+    if word.synonyms.is_empty() && !edited("synonyms") {
+        word.synonyms = from.synonyms.clone();
+    }
+    if word.antonyms.is_empty() && !edited("antonyms") {
+        word.antonyms = from.antonyms.clone();
+    }
+    if word.collocations.is_empty() && !edited("collocations") {
+        word.collocations = from.collocations.clone();
+    }
+    if word.example_sentence.is_none() && !edited("example_sentence") {
+        word.example_sentence = from.example_sentence.clone();
+    }
+}
+
+/// Occurrences are unique by article and context, like the `occurrences` table.
+fn same_sighting(a: &Occurrence, b: &Occurrence) -> bool {
+    a.source_url == b.source_url && a.context_sentence == b.context_sentence
+}
+
+/// Tag a word, returning how many tags were new to it.
+fn add_tags(word: &mut Vocabulary, tags: &[String]) -> usize {
+    let mut added = 0;
+    for tag in tags {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() || word.tags.contains(&tag) {
+            continue;
+        }
+        word.tags.push(tag);
+        added += 1;
+    }
+    word.tags.sort();
+    added
+}
+
+/// Stemmed lowercase alphanumeric tokens of `text`, like the FTS
+/// `porter unicode61` tokenizer.
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| porter::stem(&token.to_lowercase()))
+        .collect()
+}
+
+/// Matches of `term` in a field, as (first word, number of words), where
+/// `words` holds the tokens of each whitespace-separated word of the field.
+fn term_matches(words: &[Vec<String>], term: &SearchTerm<'_>) -> Vec<(usize, usize)> {
+    let (needle, prefix) = match term {
+        SearchTerm::Prefix(term) => (tokens(term), true),
+        SearchTerm::Phrase(phrase) => (tokens(phrase), false),
+    };
+    // Flatten to tokens, remembering the word each came from
+    let (owners, flat): (Vec<usize>, Vec<&String>) = words
+        .iter()
+        .enumerate()
+        .flat_map(|(i, tokens)| tokens.iter().map(move |token| (i, token)))
+        .unzip();
+    if needle.is_empty() || needle.len() > flat.len() {
+        return Vec::new();
+    }
+
+    (0..=flat.len() - needle.len())
+        .filter(|&start| {
+            needle.iter().enumerate().all(|(i, token)| {
+                let candidate = flat[start + i];
+                if prefix && i == needle.len() - 1 {
+                    candidate.starts_with(token.as_str())
+                } else {
+                    candidate == token
+                }
+            })
+        })
+        .map(|start| {
+            let first = owners[start];
+            (first, owners[start + needle.len() - 1] - first + 1)
+        })
+        .collect()
+}
+
+/// Match every term against some field of a document, returning a snippet
+/// and a rank (lower is better), or `None` if a term is missing.
+fn match_fields(
+    fields: &[(&str, f64)],
+    terms: &[SearchTerm<'_>],
+    snippet_words: usize,
+) -> Option<(String, f64)> {
+    let mut found = vec![false; terms.len()];
+    // The snippet comes from the field matching the most different terms
+    let mut best: Option<(usize, String)> = None;
+    let mut rank = 0.0;
+
+    for (text, weight) in fields {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let word_tokens: Vec<Vec<String>> = words.iter().map(|word| tokens(word)).collect();
+        let mut matches = Vec::new();
+        let mut terms_matched = 0;
+        for (i, term) in terms.iter().enumerate() {
+            let term_matches = term_matches(&word_tokens, term);
+            if !term_matches.is_empty() {
+                found[i] = true;
+                terms_matched += 1;
+            }
+            matches.extend(term_matches);
+        }
+
+        rank -= matches.len() as f64 * weight;
+        if terms_matched > 0 && best.as_ref().is_none_or(|(best, _)| terms_matched > *best) {
+            best = Some((terms_matched, snippet(&words, &matches, snippet_words)));
+        }
+    }
+
+    if !found.iter().all(|found| *found) {
+        return None;
+    }
+    best.map(|(_, snippet)| (snippet, rank))
+}
+
+/// Up to `max_words` words of a field around its first match, with matched
+/// words highlighted.
+fn snippet(words: &[&str], matches: &[(usize, usize)], max_words: usize) -> String {
+    let first = matches.iter().map(|(start, _)| *start).min().unwrap_or(0);
+    let start = first
+        .saturating_sub(max_words / 2)
+        .min(words.len().saturating_sub(max_words));
+    let end = (start + max_words).min(words.len());
+
+    let mut text = String::new();
+    if start > 0 {
+        text.push('…');
+    }
+    for (i, word) in words.iter().enumerate().take(end).skip(start) {
+        if i > start {
+            text.push(' ');
+        }
+        if matches
+            .iter()
+            .any(|(match_start, len)| (*match_start..match_start + len).contains(&i))
+        {
+            text.push_str(&highlight(word));
+        } else {
+            text.push_str(word);
+        }
+    }
+    if end < words.len() {
+        text.push('…');
+    }
+    text
+}
+
+/// `word` with its letters and digits highlighted, leaving punctuation around them out.
+fn highlight(word: &str) -> String {
+    let start = word.find(char::is_alphanumeric).unwrap_or(0);
+    let end = word
+        .char_indices()
+        .rfind(|(_, c)| c.is_alphanumeric())
+        .map_or(word.len(), |(i, c)| i + c.len_utf8());
+    format!(
+        "{}{highlight}{}{highlight}{}",
+        &word[..start],
+        &word[start..end],
+        &word[end..],
+        highlight = SNIPPET_HIGHLIGHT
+    )
+}
+
+#[async_trait]
+impl StoragePort for InMemoryStorage {
+    async fn exists(&self, url: &str) -> Result<bool, CoreError> {
+        Ok(self.state().articles.iter().any(|a| a.url == url))
+    }
+
+    async fn save_article(&self, article: &Article) -> Result<(), CoreError> {
+        self.state().insert_article(article);
+        Ok(())
+    }
+
+    async fn save_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
+        self.state().insert_vocab(vocab, Utc::now());
+        Ok(())
+    }
+
+    async fn save_article_with_vocab(
+        &self,
+        article: &Article,
+        vocabularies: &[Vocabulary],
+    ) -> Result<(), CoreError> {
+        let mut state = self.state();
+        state.insert_article(article);
+        let extracted_at = Utc::now();
+        for vocab in vocabularies {
+            let vocab = Vocabulary {
+                source_url: article.url.clone(),
+                ..vocab.clone()
+            };
+            state.insert_vocab(&vocab, extracted_at);
+        }
+        Ok(())
+    }
+
+    async fn get_vocab_by_id(&self, id: i64) -> Result<Option<Vocabulary>, CoreError> {
+        Ok(self.state().vocab(id))
+    }
+
+    async fn get_vocab_by_word(&self, word: &str) -> Result<Option<Vocabulary>, CoreError> {
+        let state = self.state();
+        let lemma = normalize_lemma(word);
+        let id = state
+            .id_of_lemma(&lemma)
+            .or_else(|| state.aliases.get(&lemma).copied());
+        Ok(id.and_then(|id| state.vocab(id)))
+    }
+
+    async fn update_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
+        let id = vocab
+            .id
+            .ok_or_else(|| CoreError::NotFound(format!("'{}' has not been saved", vocab.word)))?;
+
+        let mut state = self.state();
+        state.word(id)?;
+        let lemma = normalize_lemma(&vocab.word);
+        if state.id_of_lemma(&lemma).is_some_and(|other| other != id) {
+            return Err(lemma_taken(&lemma));
+        }
+
+        let word = state.words.get_mut(&id).expect("word was just found");
+        *word = Vocabulary {
+            id: Some(id),
+            word: vocab.word.trim().to_string(),
+            context_sentence: String::new(),
+            source_url: String::new(),
+            occurrences: std::mem::take(&mut word.occurrences),
+            tags: std::mem::take(&mut word.tags),
+            ..vocab.clone()
+        };
+        Ok(())
+    }
+
+    async fn delete_vocab(&self, id: i64) -> Result<bool, CoreError> {
+        let mut state = self.state();
+        state.aliases.retain(|_, word_id| *word_id != id);
+        state.reviews.remove(&id);
+        Ok(state.words.remove(&id).is_some())
+    }
+
+    async fn merge_vocab(&self, from: i64, into: i64) -> Result<Vocabulary, CoreError> {
+        if from == into {
+            return Err(CoreError::Database(format!(
+                "Cannot merge word {} into itself",
+                from
+            )));
+        }
+
+        let mut state = self.state();
+        state.word(from)?;
+        state.word(into)?;
+
+        let merged = state.words.remove(&from).expect("word was just found");
+        let word = state.words.get_mut(&into).expect("word was just found");
+        fill_missing(word, &merged, false);
+        // Contexts already recorded for `into` stay as they are
+        for occurrence in merged.occurrences {
+            if !word
+                .occurrences
+                .iter()
+                .any(|o| same_sighting(o, &occurrence))
+            {
+                word.occurrences.push(occurrence);
+            }
+        }
+        add_tags(word, &merged.tags);
+
+        // `into` keeps its own schedule if it has one
+        if let Some(review) = state.reviews.remove(&from) {
+            state.reviews.entry(into).or_insert(review);
+        }
+        for word_id in state.aliases.values_mut() {
+            if *word_id == from {
+                *word_id = into;
+            }
+        }
+        state.aliases.insert(normalize_lemma(&merged.word), into);
+
+        state
+            .vocab(into)
+            .ok_or_else(|| CoreError::NotFound(format!("Word {}", into)))
+    }
+
+    async fn query_vocab(&self, query: &VocabQuery) -> Result<VocabPage, CoreError> {
+        let state = self.state();
+        let now = Utc::now();
+
+        let mut ids: Vec<(i64, String)> = state
+            .words
+            .values()
+            .filter(|vocab| state.matches_query(vocab, query, now))
+            .map(|vocab| (vocab.id.unwrap_or_default(), normalize_lemma(&vocab.word)))
+            .collect();
+
+        // The cursor is the id of the last word on the previous page
+        if let Some(cursor) = &query.cursor {
+            let id: i64 = cursor
+                .parse()
+                .map_err(|_| CoreError::Parse(format!("Invalid cursor '{}'", cursor)))?;
+            match query.sort {
+                VocabSort::Oldest => ids.retain(|(other, _)| *other > id),
+                VocabSort::Newest => ids.retain(|(other, _)| *other < id),
+                VocabSort::Alphabetical => {
+                    let lemma = state.words.get(&id).map(|v| normalize_lemma(&v.word));
+                    ids.retain(|(other, other_lemma)| {
+                        lemma
+                            .as_ref()
+                            .is_some_and(|lemma| (other_lemma, *other) > (lemma, id))
+                    });
+                }
+            }
+        }
+
+        match query.sort {
+            VocabSort::Oldest => {}
+            VocabSort::Newest => ids.reverse(),
+            VocabSort::Alphabetical => ids.sort(),
+        }
+        let mut page: Vec<i64> = ids
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.map_or(usize::MAX, |limit| limit + 1))
+            .map(|(id, _)| id)
+            .collect();
+        page.sort();
+
+        let vocabs = page
+            .into_iter()
+            .filter_map(|id| Some((id, state.vocab(id)?)))
+            .collect();
+        Ok(page_vocab(vocabs, query))
+    }
+
+    async fn search_vocab(&self, query: &str) -> Result<Vec<Vocabulary>, CoreError> {
+        let state = self.state();
+        Ok(state
+            .match_words(query)
+            .into_iter()
+            .filter_map(|(id, _, _)| state.vocab(id))
+            .collect())
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>, CoreError> {
+        let state = self.state();
+        let mut hits: Vec<SearchHit> = state
+            .match_words(query)
+            .into_iter()
+            .take(limit)
+            .filter_map(|(word_id, snippet, rank)| {
+                Some(SearchHit::Word {
+                    word_id,
+                    vocabulary: Box::new(state.vocab(word_id)?),
+                    snippet,
+                    rank,
+                })
+            })
+            .collect();
+        hits.extend(state.match_articles(query).into_iter().take(limit));
+        Ok(hits)
+    }
+
+    async fn get_random_vocab(&self) -> Result<Option<Vocabulary>, CoreError> {
+        let state = self.state();
+        if state.words.is_empty() {
+            return Ok(None);
+        }
+        // Hasher keys are random per `RandomState`, which is enough to pick a word
+        let n = RandomState::new().hash_one(0u8) as usize % state.words.len();
+        let id = state.words.keys().nth(n).copied();
+        Ok(id.and_then(|id| state.vocab(id)))
+    }
+
+    async fn add_known_words(&self, words: &[String]) -> Result<usize, CoreError> {
+        let mut state = self.state();
+        state.known_batches += 1;
+        let batch = state.known_batches;
+        let mut added = 0;
+        for word in words {
+            let lemma = word.trim().to_lowercase();
+            if lemma.is_empty() || state.known_words.iter().any(|(known, _)| *known == lemma) {
+                continue;
+            }
+            state.known_words.push((lemma, batch));
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    async fn remove_known_word(&self, word: &str) -> Result<bool, CoreError> {
+        let mut state = self.state();
+        let lemma = word.trim().to_lowercase();
+        let before = state.known_words.len();
+        state.known_words.retain(|(known, _)| *known != lemma);
+        Ok(state.known_words.len() < before)
+    }
+
+    async fn get_known_words(&self) -> Result<Vec<String>, CoreError> {
+        let mut known = self.state().known_words.clone();
+        known.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        Ok(known.into_iter().map(|(lemma, _)| lemma).collect())
+    }
+
+    async fn get_mastered_words(&self) -> Result<Vec<String>, CoreError> {
+        let state = self.state();
+        let mut lemmas: Vec<String> = state
+            .reviews
+            .iter()
+            .filter(|(_, review)| review.interval_days >= MASTERED_INTERVAL_DAYS)
+            .filter_map(|(id, _)| state.words.get(id).map(|v| normalize_lemma(&v.word)))
+            .collect();
+        lemmas.sort();
+        Ok(lemmas)
+    }
+
+    async fn add_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError> {
+        let mut state = self.state();
+        state.word(word_id)?;
+        let word = state.words.get_mut(&word_id).expect("word was just found");
+        Ok(add_tags(word, tags))
+    }
+
+    async fn remove_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError> {
+        let mut state = self.state();
+        let Some(word) = state.words.get_mut(&word_id) else {
+            return Ok(0);
+        };
+        let mut removed = 0;
+        for tag in tags {
+            let tag = normalize_tag(tag);
+            if let Some(i) = word.tags.iter().position(|t| *t == tag) {
+                word.tags.remove(i);
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn get_tags(&self) -> Result<Vec<(String, usize)>, CoreError> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for vocab in self.state().words.values() {
+            for tag in &vocab.tags {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+        Ok(counts.into_iter().collect())
+    }
+
+    async fn save_deck(&self, deck: &Deck) -> Result<(), CoreError> {
+        let name = deck.name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Parse("Deck name must not be empty".to_string()));
+        }
+        let tags = deck
+            .tags
+            .iter()
+            .map(|tag| normalize_tag(tag))
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        self.state().decks.insert(name.to_string(), tags);
+        Ok(())
+    }
+
+    async fn delete_deck(&self, name: &str) -> Result<bool, CoreError> {
+        Ok(self.state().decks.remove(name.trim()).is_some())
+    }
+
+    async fn get_decks(&self) -> Result<Vec<Deck>, CoreError> {
+        Ok(self
+            .state()
+            .decks
+            .iter()
+            .map(|(name, tags)| Deck {
+                name: name.clone(),
+                tags: tags.iter().cloned().collect(),
+            })
+            .collect())
+    }
+
+    async fn get_due_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError> {
+        let state = self.state();
+        let now = Utc::now();
+        let mut due: Vec<(i64, &ReviewState)> = state
+            .reviews
+            .iter()
+            .filter(|(id, review)| {
+                review.due_at <= now
+                    && state
+                        .words
+                        .get(id)
+                        .is_some_and(|v| !v.suspended && state.matches_tags(v, filter))
+            })
+            .map(|(id, review)| (*id, review))
+            .collect();
+        due.sort_by(|a, b| a.1.due_at.cmp(&b.1.due_at).then(a.0.cmp(&b.0)));
+
+        Ok(due
+            .into_iter()
+            .take(limit)
+            .filter_map(|(word_id, review)| {
+                Some(ReviewCard {
+                    word_id,
+                    vocabulary: state.vocab(word_id)?,
+                    state: review.clone(),
+                })
+            })
+            .collect())
+    }
+
+    async fn get_new_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError> {
+        let state = self.state();
+        let now = Utc::now();
+        Ok(state
+            .words
+            .values()
+            .filter(|v| {
+                !v.suspended
+                    && !state.reviews.contains_key(&v.id.unwrap_or_default())
+                    && state.matches_tags(v, filter)
+            })
+            .take(limit)
+            .filter_map(|v| {
+                let word_id = v.id?;
+                Some(ReviewCard {
+                    word_id,
+                    vocabulary: state.vocab(word_id)?,
+                    state: ReviewState::new(now),
+                })
+            })
+            .collect())
+    }
+
+    async fn record_review(&self, word_id: i64, grade: Grade) -> Result<ReviewState, CoreError> {
+        let mut state = self.state();
+        state.word(word_id)?;
+
+        let now = Utc::now();
+        let current = state
+            .reviews
+            .get(&word_id)
+            .cloned()
+            .unwrap_or_else(|| ReviewState::new(now));
+        let next = self.algorithm.scheduler().schedule(&current, grade, now);
+        state.reviews.insert(word_id, next.clone());
+        Ok(next)
+    }
+
+    async fn start_run(&self, run: &PipelineRun) -> Result<i64, CoreError> {
+        let mut state = self.state();
+        let id = state.runs.len() as i64 + 1;
+        state.runs.push(PipelineRun {
+            id: Some(id),
+            finished_at: None,
+            feeds: Vec::new(),
+            errors: Vec::new(),
+            ..run.clone()
+        });
+        Ok(id)
+    }
+
+    async fn update_run(&self, run: &PipelineRun) -> Result<(), CoreError> {
+        let mut state = self.state();
+        let stored = run
+            .id
+            .and_then(|id| state.runs.get_mut((id as usize).checked_sub(1)?))
+            .ok_or_else(|| CoreError::NotFound(format!("Run {:?}", run.id)))?;
+        *stored = PipelineRun {
+            trigger: stored.trigger,
+            started_at: stored.started_at,
+            ..run.clone()
+        };
+        Ok(())
+    }
+
+    async fn get_run(&self, id: i64) -> Result<Option<PipelineRun>, CoreError> {
+        let state = self.state();
+        Ok((id as usize)
+            .checked_sub(1)
+            .and_then(|index| state.runs.get(index))
+            .cloned())
+    }
+
+    async fn get_runs(&self, limit: Option<usize>) -> Result<Vec<PipelineRun>, CoreError> {
+        let state = self.state();
+        Ok(state
+            .runs
+            .iter()
+            .rev()
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect())
+    }
+
+    async fn enqueue_items(&self, items: &[QueuedItem]) -> Result<usize, CoreError> {
+        let mut state = self.state();
+        let mut added = 0;
+        for item in items {
+            if !state.queue.iter().any(|queued| queued.url == item.url) {
+                state.queue.push(item.clone());
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    async fn get_queued_items(&self) -> Result<Vec<QueuedItem>, CoreError> {
+        Ok(self.state().queue.clone())
+    }
+
+    async fn dequeue_item(&self, url: &str) -> Result<bool, CoreError> {
+        let mut state = self.state();
+        let before = state.queue.len();
+        state.queue.retain(|item| item.url != url);
+        Ok(state.queue.len() < before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_highlights_tokens() {
+        let terms = search_terms(r#"roof "urban heat""#);
+        let fields = [("Cities mitigate urban heat with reflective roofs.", 1.0)];
+        // The excerpt starts around the first match
+        let (snippet, rank) = match_fields(&fields, &terms, 4).unwrap();
+        assert_eq!(snippet, "Cities mitigate **urban** **heat**…");
+        assert!(rank < 0.0);
+
+        // Every term must match somewhere
+        assert!(match_fields(&fields, &search_terms("roof trees"), 4).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_articles_are_saved_once() {
+        let storage = InMemoryStorage::new();
+        let article = Article {
+            id: None,
+            url: "https://example.com/a".to_string(),
+            title: "A".to_string(),
+            content: String::new(),
+            source: spread_core::model::SourceType::RSS,
+            feed_url: None,
+            published_at: Utc::now(),
+            collected_at: Utc::now(),
+        };
+        storage.save_article(&article).await.unwrap();
+        storage.save_article(&article).await.unwrap();
+
+        let articles = storage.articles();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].id, Some(1));
+    }
+}
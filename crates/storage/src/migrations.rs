@@ -0,0 +1,1074 @@
+//! Numbered schema migrations tracked in the `schema_version` table.
+//!
+//! Migrations are append-only: never edit or renumber one that has shipped,
+//! add a new one at the end of [`MIGRATIONS`] instead.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+
+use chrono::{DateTime, Utc};
+use spread_core::error::CoreError;
+use spread_core::model::Vocabulary;
+use sqlx::{SqliteConnection, SqlitePool};
+use tracing::{info, warn};
+
+use crate::{decode_list, encode_list, normalize_lemma};
+
+const CREATE_ARTICLES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS articles (
+    url TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    source TEXT NOT NULL,
+    published_at DATETIME NOT NULL,
+    collected_at DATETIME NOT NULL
+)
+"#;
+
+/// One row per distinct word, keyed by its normalized lemma.
+const CREATE_WORDS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS words (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    lemma TEXT NOT NULL UNIQUE,
+    word TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    part_of_speech TEXT,
+    ipa TEXT,
+    cefr_level TEXT,
+    synonyms TEXT,
+    antonyms TEXT,
+    collocations TEXT,
+    example_sentence TEXT,
+    created_at DATETIME NOT NULL
+)
+"#;
+
+/// Every article/sentence a word was extracted from.
+const CREATE_OCCURRENCES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS occurrences (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    word_id INTEGER NOT NULL,
+    article_url TEXT NOT NULL,
+    context_sentence TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    extracted_at DATETIME NOT NULL,
+    UNIQUE (word_id, article_url, context_sentence),
+    FOREIGN KEY (word_id) REFERENCES words(id),
+    FOREIGN KEY (article_url) REFERENCES articles(url)
+)
+"#;
+
+const CREATE_KNOWN_WORDS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS known_words (
+    lemma TEXT PRIMARY KEY,
+    added_at DATETIME NOT NULL
+)
+"#;
+
+/// Spaced-repetition state; words without a row have never been reviewed.
+const CREATE_REVIEW_STATES_TABLE: &str = r#"
+CREATE TABLE review_states (
+    word_id INTEGER PRIMARY KEY,
+    reps INTEGER NOT NULL,
+    lapses INTEGER NOT NULL,
+    ease REAL NOT NULL,
+    interval_days INTEGER NOT NULL,
+    stability REAL NOT NULL,
+    difficulty REAL NOT NULL,
+    due_at DATETIME NOT NULL,
+    last_reviewed_at DATETIME,
+    FOREIGN KEY (word_id) REFERENCES words(id)
+)
+"#;
+
+const CREATE_REVIEW_LOG_TABLE: &str = r#"
+CREATE TABLE review_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    word_id INTEGER NOT NULL,
+    grade INTEGER NOT NULL,
+    algorithm TEXT NOT NULL,
+    reviewed_at DATETIME NOT NULL,
+    interval_days INTEGER NOT NULL,
+    ease REAL NOT NULL,
+    stability REAL NOT NULL,
+    difficulty REAL NOT NULL,
+    due_at DATETIME NOT NULL,
+    FOREIGN KEY (word_id) REFERENCES words(id)
+)
+"#;
+
+/// Full-text index of words, keyed by word id. `contexts` holds every
+/// context sentence of the word.
+const CREATE_WORDS_FTS_TABLE: &str = r#"
+CREATE VIRTUAL TABLE words_fts USING fts5(
+    word, definition, contexts,
+    tokenize = 'porter unicode61 remove_diacritics 2'
+)
+"#;
+
+/// Full-text index of article titles and bodies, keyed by URL.
+const CREATE_ARTICLES_FTS_TABLE: &str = r#"
+CREATE VIRTUAL TABLE articles_fts USING fts5(
+    url UNINDEXED, title, content,
+    tokenize = 'porter unicode61 remove_diacritics 2'
+)
+"#;
+
+const POPULATE_WORDS_FTS: &str = r#"
+INSERT INTO words_fts (rowid, word, definition, contexts)
+SELECT w.id, w.word, w.definition,
+       COALESCE((SELECT group_concat(o.context_sentence, ' ') FROM occurrences o WHERE o.word_id = w.id), '')
+FROM words w
+"#;
+
+const POPULATE_ARTICLES_FTS: &str =
+    "INSERT INTO articles_fts (url, title, content) SELECT url, title, content FROM articles";
+
+/// FTS5 tables, their backfill, and triggers keeping them in sync with the
+/// source tables.
+const FULL_TEXT_SEARCH: &[&str] = &[
+    CREATE_WORDS_FTS_TABLE,
+    CREATE_ARTICLES_FTS_TABLE,
+    POPULATE_WORDS_FTS,
+    POPULATE_ARTICLES_FTS,
+    r#"
+CREATE TRIGGER words_fts_insert AFTER INSERT ON words BEGIN
+    INSERT INTO words_fts (rowid, word, definition, contexts) VALUES (new.id, new.word, new.definition, '');
+END
+"#,
+    r#"
+CREATE TRIGGER words_fts_update AFTER UPDATE OF word, definition ON words BEGIN
+    UPDATE words_fts SET word = new.word, definition = new.definition WHERE rowid = new.id;
+END
+"#,
+    r#"
+CREATE TRIGGER words_fts_delete AFTER DELETE ON words BEGIN
+    DELETE FROM words_fts WHERE rowid = old.id;
+END
+"#,
+    OCCURRENCES_FTS_INSERT_TRIGGER,
+    OCCURRENCES_FTS_UPDATE_TRIGGER,
+    OCCURRENCES_FTS_DELETE_TRIGGER,
+    ARTICLES_FTS_INSERT_TRIGGER,
+    ARTICLES_FTS_UPDATE_TRIGGER,
+    ARTICLES_FTS_DELETE_TRIGGER,
+];
+
+const OCCURRENCES_FTS_INSERT_TRIGGER: &str = r#"
+CREATE TRIGGER occurrences_fts_insert AFTER INSERT ON occurrences BEGIN
+    UPDATE words_fts SET contexts = COALESCE(
+        (SELECT group_concat(context_sentence, ' ') FROM occurrences WHERE word_id = new.word_id), ''
+    ) WHERE rowid = new.word_id;
+END
+"#;
+
+const OCCURRENCES_FTS_UPDATE_TRIGGER: &str = r#"
+CREATE TRIGGER occurrences_fts_update AFTER UPDATE OF word_id, context_sentence ON occurrences BEGIN
+    UPDATE words_fts SET contexts = COALESCE(
+        (SELECT group_concat(context_sentence, ' ') FROM occurrences WHERE word_id = old.word_id), ''
+    ) WHERE rowid = old.word_id;
+    UPDATE words_fts SET contexts = COALESCE(
+        (SELECT group_concat(context_sentence, ' ') FROM occurrences WHERE word_id = new.word_id), ''
+    ) WHERE rowid = new.word_id;
+END
+"#;
+
+const OCCURRENCES_FTS_DELETE_TRIGGER: &str = r#"
+CREATE TRIGGER occurrences_fts_delete AFTER DELETE ON occurrences BEGIN
+    UPDATE words_fts SET contexts = COALESCE(
+        (SELECT group_concat(context_sentence, ' ') FROM occurrences WHERE word_id = old.word_id), ''
+    ) WHERE rowid = old.word_id;
+END
+"#;
+
+const ARTICLES_FTS_INSERT_TRIGGER: &str = r#"
+CREATE TRIGGER articles_fts_insert AFTER INSERT ON articles BEGIN
+    INSERT INTO articles_fts (url, title, content) VALUES (new.url, new.title, new.content);
+END
+"#;
+
+const ARTICLES_FTS_UPDATE_TRIGGER: &str = r#"
+CREATE TRIGGER articles_fts_update AFTER UPDATE OF url, title, content ON articles BEGIN
+    UPDATE articles_fts SET url = new.url, title = new.title, content = new.content WHERE url = old.url;
+END
+"#;
+
+const ARTICLES_FTS_DELETE_TRIGGER: &str = r#"
+CREATE TRIGGER articles_fts_delete AFTER DELETE ON articles BEGIN
+    DELETE FROM articles_fts WHERE url = old.url;
+END
+"#;
+
+/// Articles keyed by a stable integer id; the implicit rowid of the original
+/// table may change on VACUUM.
+const CREATE_ARTICLES_WITH_ID_TABLE: &str = r#"
+CREATE TABLE articles_with_id (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    source TEXT NOT NULL,
+    feed_url TEXT,
+    published_at DATETIME NOT NULL,
+    collected_at DATETIME NOT NULL
+)
+"#;
+
+/// `occurrences` pointing at the rebuilt articles table.
+const CREATE_OCCURRENCES_OF_ARTICLES_WITH_ID_TABLE: &str = r#"
+CREATE TABLE occurrences_of_articles_with_id (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    word_id INTEGER NOT NULL,
+    article_url TEXT NOT NULL,
+    context_sentence TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    extracted_at DATETIME NOT NULL,
+    UNIQUE (word_id, article_url, context_sentence),
+    FOREIGN KEY (word_id) REFERENCES words(id),
+    FOREIGN KEY (article_url) REFERENCES articles_with_id(url)
+)
+"#;
+
+/// Rebuild `articles` with an `id` column.
+///
+/// Foreign keys cannot be switched off inside the migration transaction, so
+/// `occurrences` is rebuilt too and the old tables are dropped child first.
+/// Renaming `articles_with_id` updates the new occurrences' foreign key.
+/// Dropping a table does not fire its triggers, so the FTS indexes are kept
+/// and only the triggers and indexes are recreated.
+const ARTICLE_IDS: &[&str] = &[
+    CREATE_ARTICLES_WITH_ID_TABLE,
+    r#"
+INSERT INTO articles_with_id (url, title, content, source, feed_url, published_at, collected_at)
+SELECT url, title, content, source, feed_url, published_at, collected_at
+FROM articles
+ORDER BY collected_at, rowid
+"#,
+    CREATE_OCCURRENCES_OF_ARTICLES_WITH_ID_TABLE,
+    r#"
+INSERT INTO occurrences_of_articles_with_id (id, word_id, article_url, context_sentence, definition, extracted_at)
+SELECT id, word_id, article_url, context_sentence, definition, extracted_at
+FROM occurrences
+"#,
+    "DROP TABLE occurrences",
+    "DROP TABLE articles",
+    "ALTER TABLE articles_with_id RENAME TO articles",
+    "ALTER TABLE occurrences_of_articles_with_id RENAME TO occurrences",
+    "CREATE INDEX idx_articles_collected ON articles (collected_at)",
+    "CREATE INDEX idx_articles_feed ON articles (feed_url)",
+    ARTICLES_FTS_INSERT_TRIGGER,
+    ARTICLES_FTS_UPDATE_TRIGGER,
+    ARTICLES_FTS_DELETE_TRIGGER,
+    OCCURRENCES_FTS_INSERT_TRIGGER,
+    OCCURRENCES_FTS_UPDATE_TRIGGER,
+    OCCURRENCES_FTS_DELETE_TRIGGER,
+];
+
+/// Spellings merged into another word, e.g. "analyse" into "analyze".
+const CREATE_WORD_ALIASES_TABLE: &str = r#"
+CREATE TABLE word_aliases (
+    lemma TEXT PRIMARY KEY,
+    word_id INTEGER NOT NULL,
+    FOREIGN KEY (word_id) REFERENCES words(id)
+)
+"#;
+
+const CREATE_TAGS_TABLE: &str = r#"
+CREATE TABLE tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE
+)
+"#;
+
+/// Many-to-many link between words and tags.
+const CREATE_WORD_TAGS_TABLE: &str = r#"
+CREATE TABLE word_tags (
+    word_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (word_id, tag_id),
+    FOREIGN KEY (word_id) REFERENCES words(id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+)
+"#;
+
+const CREATE_DECKS_TABLE: &str = r#"
+CREATE TABLE decks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE
+)
+"#;
+
+/// Tags whose words make up a deck.
+const CREATE_DECK_TAGS_TABLE: &str = r#"
+CREATE TABLE deck_tags (
+    deck_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (deck_id, tag_id),
+    FOREIGN KEY (deck_id) REFERENCES decks(id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+)
+"#;
+
+/// SHA-256 of the URLs of pruned articles, so they are not collected again.
+const CREATE_PRUNED_ARTICLES_TABLE: &str = r#"
+CREATE TABLE pruned_articles (
+    url_hash TEXT PRIMARY KEY,
+    pruned_at DATETIME NOT NULL
+)
+"#;
+
+/// One row per pipeline run; `stats` is a JSON object of its counts.
+const CREATE_RUNS_TABLE: &str = r#"
+CREATE TABLE runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    triggered_by TEXT NOT NULL,
+    started_at DATETIME NOT NULL,
+    finished_at DATETIME,
+    stats TEXT NOT NULL DEFAULT '{}'
+)
+"#;
+
+/// How each feed fared in a run; `error` is set when it could not be read.
+const CREATE_RUN_FEEDS_TABLE: &str = r#"
+CREATE TABLE run_feeds (
+    run_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    feed_url TEXT NOT NULL,
+    items INTEGER NOT NULL,
+    articles_saved INTEGER NOT NULL,
+    vocabularies_saved INTEGER NOT NULL,
+    error TEXT,
+    PRIMARY KEY (run_id, position),
+    FOREIGN KEY (run_id) REFERENCES runs(id)
+)
+"#;
+
+const CREATE_RUN_ERRORS_TABLE: &str = r#"
+CREATE TABLE run_errors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    stage TEXT NOT NULL,
+    url TEXT NOT NULL,
+    message TEXT NOT NULL,
+    FOREIGN KEY (run_id) REFERENCES runs(id)
+)
+"#;
+
+/// Feed items found by a run and not processed yet, in the order found.
+const CREATE_QUEUED_ITEMS_TABLE: &str = r#"
+CREATE TABLE queued_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL UNIQUE,
+    feed_url TEXT NOT NULL,
+    title TEXT NOT NULL,
+    published_at DATETIME NOT NULL,
+    queued_at DATETIME NOT NULL
+)
+"#;
+
+/// Lexical columns that may be missing from a legacy `vocabularies` table.
+const VOCABULARY_LEXICAL_COLUMNS: &[&str] = &[
+    "part_of_speech",
+    "ipa",
+    "cefr_level",
+    "synonyms",
+    "antonyms",
+    "collocations",
+    "example_sentence",
+];
+
+const CREATE_SCHEMA_VERSION_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS schema_version (
+    version INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied_at DATETIME NOT NULL
+)
+"#;
+
+type MigrationFuture<'c> = Pin<Box<dyn Future<Output = Result<(), CoreError>> + Send + 'c>>;
+
+enum Step {
+    /// Statements executed in order
+    Sql(&'static [&'static str]),
+    /// Data migrations that need more than plain SQL
+    Rust(for<'c> fn(&'c mut SqliteConnection) -> MigrationFuture<'c>),
+}
+
+/// A single schema change.
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    step: Step,
+}
+
+/// All migrations, in order. Databases created before versioning are brought
+/// up by the idempotent initial schema.
+static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        step: Step::Sql(&[
+            CREATE_ARTICLES_TABLE,
+            CREATE_WORDS_TABLE,
+            CREATE_OCCURRENCES_TABLE,
+            CREATE_KNOWN_WORDS_TABLE,
+        ]),
+    },
+    Migration {
+        version: 2,
+        name: "merge_legacy_vocabularies",
+        step: Step::Rust(|conn| Box::pin(merge_legacy_vocabularies(conn))),
+    },
+    Migration {
+        version: 3,
+        name: "review_scheduling",
+        step: Step::Sql(&[
+            CREATE_REVIEW_STATES_TABLE,
+            CREATE_REVIEW_LOG_TABLE,
+            "CREATE INDEX idx_review_states_due ON review_states (due_at)",
+            "CREATE INDEX idx_review_log_word ON review_log (word_id, reviewed_at)",
+        ]),
+    },
+    Migration {
+        version: 4,
+        name: "full_text_search",
+        step: Step::Sql(FULL_TEXT_SEARCH),
+    },
+    Migration {
+        version: 5,
+        name: "vocabulary_filters",
+        step: Step::Sql(&[
+            "ALTER TABLE articles ADD COLUMN feed_url TEXT",
+            "CREATE INDEX idx_articles_collected ON articles (collected_at)",
+            "CREATE INDEX idx_articles_feed ON articles (feed_url)",
+            CREATE_TAGS_TABLE,
+            CREATE_WORD_TAGS_TABLE,
+            "CREATE INDEX idx_word_tags_tag ON word_tags (tag_id)",
+        ]),
+    },
+    Migration {
+        version: 6,
+        name: "article_ids",
+        step: Step::Sql(ARTICLE_IDS),
+    },
+    Migration {
+        version: 7,
+        name: "word_curation",
+        step: Step::Sql(&[
+            // JSON array of hand-edited field names
+            "ALTER TABLE words ADD COLUMN edited_fields TEXT",
+            "ALTER TABLE words ADD COLUMN suspended INTEGER NOT NULL DEFAULT 0",
+            CREATE_WORD_ALIASES_TABLE,
+            "CREATE INDEX idx_word_aliases_word ON word_aliases (word_id)",
+        ]),
+    },
+    Migration {
+        version: 8,
+        name: "decks",
+        step: Step::Sql(&[
+            CREATE_DECKS_TABLE,
+            CREATE_DECK_TAGS_TABLE,
+            "CREATE INDEX idx_deck_tags_tag ON deck_tags (tag_id)",
+        ]),
+    },
+    Migration {
+        version: 9,
+        name: "article_retention",
+        step: Step::Sql(&[
+            // Set once the body has been cut down to the referenced sentences
+            "ALTER TABLE articles ADD COLUMN compacted_at DATETIME",
+            CREATE_PRUNED_ARTICLES_TABLE,
+        ]),
+    },
+    Migration {
+        version: 10,
+        name: "pipeline_runs",
+        step: Step::Sql(&[
+            CREATE_RUNS_TABLE,
+            CREATE_RUN_FEEDS_TABLE,
+            CREATE_RUN_ERRORS_TABLE,
+            "CREATE INDEX idx_run_feeds_feed ON run_feeds (feed_url)",
+            "CREATE INDEX idx_run_errors_run ON run_errors (run_id)",
+        ]),
+    },
+    Migration {
+        version: 11,
+        name: "work_queue",
+        step: Step::Sql(&[
+            "ALTER TABLE runs ADD COLUMN cancelled INTEGER NOT NULL DEFAULT 0",
+            CREATE_QUEUED_ITEMS_TABLE,
+        ]),
+    },
+];
+
+/// Schema version this build expects.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Outcome of [`migrate`].
+#[derive(Debug)]
+pub struct MigrationReport {
+    pub from_version: i64,
+    pub to_version: i64,
+    /// `(version, name)` of each applied migration
+    pub applied: Vec<(i64, &'static str)>,
+    /// Copy of the database taken before migrating, if any
+    pub backup: Option<PathBuf>,
+}
+
+/// Current schema version, 0 for an unversioned database. Only reads, so it
+/// works on a read-only connection.
+pub(crate) async fn current_version(pool: &SqlitePool) -> Result<i64, CoreError> {
+    let (versioned,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    if versioned == 0 {
+        return Ok(0);
+    }
+
+    let (version,): (Option<i64>,) = sqlx::query_as("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(version.unwrap_or(0))
+}
+
+/// Migrations not yet applied to the database.
+pub(crate) async fn pending(pool: &SqlitePool) -> Result<Vec<&'static Migration>, CoreError> {
+    let current = current_version(pool).await?;
+    if current > latest_version() {
+        return Err(CoreError::Database(format!(
+            "Database schema version {} is newer than this build supports ({})",
+            current,
+            latest_version()
+        )));
+    }
+
+    Ok(MIGRATIONS.iter().filter(|m| m.version > current).collect())
+}
+
+/// Apply pending migrations, each in its own transaction.
+///
+/// A file database that already holds data is copied next to itself first.
+pub(crate) async fn migrate(pool: &SqlitePool) -> Result<MigrationReport, CoreError> {
+    let from_version = current_version(pool).await?;
+    let pending = pending(pool).await?;
+
+    let mut report = MigrationReport {
+        from_version,
+        to_version: from_version,
+        applied: Vec::new(),
+        backup: None,
+    };
+    if pending.is_empty() {
+        return Ok(report);
+    }
+
+    report.backup = backup(pool, from_version).await?;
+    sqlx::query(CREATE_SCHEMA_VERSION_TABLE)
+        .execute(pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    for migration in pending {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        match migration.step {
+            Step::Sql(statements) => {
+                for statement in statements {
+                    sqlx::query(statement)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| migration_error(migration, e))?;
+                }
+            }
+            Step::Rust(run) => run(&mut tx).await?,
+        }
+
+        sqlx::query("INSERT INTO schema_version (version, name, applied_at) VALUES (?, ?, ?)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        info!(
+            version = migration.version,
+            name = migration.name,
+            "Applied migration"
+        );
+        report.to_version = migration.version;
+        report.applied.push((migration.version, migration.name));
+    }
+
+    Ok(report)
+}
+
+fn migration_error(migration: &Migration, e: sqlx::Error) -> CoreError {
+    CoreError::Database(format!(
+        "Migration {} ({}) failed: {}",
+        migration.version, migration.name, e
+    ))
+}
+
+/// Copy a non-empty file database to `<file>.v<version>-<timestamp>.bak`.
+async fn backup(pool: &SqlitePool, version: i64) -> Result<Option<PathBuf>, CoreError> {
+    let Some(path) = crate::backup::database_path(pool).await? else {
+        return Ok(None);
+    };
+    if !crate::backup::has_data(pool).await? {
+        return Ok(None);
+    }
+
+    let backup = PathBuf::from(format!(
+        "{}.v{}-{}.bak",
+        path.display(),
+        version,
+        Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    crate::backup::vacuum_into(pool, &backup).await?;
+
+    info!(path = %backup.display(), "Backed up database before migrating");
+    Ok(Some(backup))
+}
+
+/// Merge a pre-dedup `vocabularies` table (one row per extraction) into
+/// `words`/`occurrences`, then drop it.
+async fn merge_legacy_vocabularies(conn: &mut SqliteConnection) -> Result<(), CoreError> {
+    let (legacy,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'vocabularies'",
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    if legacy == 0 {
+        return Ok(());
+    }
+
+    // Very old databases predate the lexical columns
+    let existing: Vec<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('vocabularies')")
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+    let column = |name: &str| {
+        if existing.iter().any(|(c,)| c == name) {
+            format!("v.{}", name)
+        } else {
+            format!("NULL AS {}", name)
+        }
+    };
+    let lexical = VOCABULARY_LEXICAL_COLUMNS
+        .iter()
+        .map(|c| column(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    #[derive(sqlx::FromRow)]
+    struct LegacyRow {
+        word: String,
+        definition: String,
+        context_sentence: String,
+        source_url: String,
+        part_of_speech: Option<String>,
+        ipa: Option<String>,
+        cefr_level: Option<String>,
+        synonyms: Option<String>,
+        antonyms: Option<String>,
+        collocations: Option<String>,
+        example_sentence: Option<String>,
+        collected_at: Option<DateTime<Utc>>,
+    }
+
+    let rows: Vec<LegacyRow> = sqlx::query_as(&format!(
+        r#"
+        SELECT v.word, v.definition, v.context_sentence, v.source_url, {}, a.collected_at
+        FROM vocabularies v
+        LEFT JOIN articles a ON a.url = v.source_url
+        ORDER BY v.id
+        "#,
+        lexical
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    // Rows whose article is gone get a placeholder article so the occurrences
+    // foreign key holds, stamped with the migration time
+    let migrated_at = Utc::now();
+    let mut orphans = 0;
+    for row in rows {
+        let collected_at = match row.collected_at {
+            Some(collected_at) => collected_at,
+            None => {
+                orphans += 1;
+                sqlx::query(
+                    r#"
+                    INSERT OR IGNORE INTO articles (url, title, content, source, published_at, collected_at)
+                    VALUES (?, ?, '', 'RSS', ?, ?)
+                    "#,
+                )
+                .bind(&row.source_url)
+                .bind(&row.source_url)
+                .bind(migrated_at)
+                .bind(migrated_at)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+                migrated_at
+            }
+        };
+        let vocab = Vocabulary {
+            word: row.word,
+            definition: row.definition,
+            context_sentence: row.context_sentence,
+            source_url: row.source_url,
+            part_of_speech: row.part_of_speech,
+            ipa: row.ipa,
+            cefr_level: row.cefr_level,
+            synonyms: decode_list(row.synonyms),
+            antonyms: decode_list(row.antonyms),
+            collocations: decode_list(row.collocations),
+            example_sentence: row.example_sentence,
+            ..Default::default()
+        };
+        insert_merged_vocab(conn, &vocab, collected_at).await?;
+    }
+    if orphans > 0 {
+        warn!(
+            rows = orphans,
+            "Legacy words without an article were kept under placeholder articles"
+        );
+    }
+
+    sqlx::query("DROP TABLE vocabularies")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Upsert the word and record this sighting as an occurrence, as the schema of
+/// migration 2 allows. Frozen: later migrations change `words`, so this must not
+/// follow [`crate::insert_vocab`].
+async fn insert_merged_vocab(
+    conn: &mut SqliteConnection,
+    vocab: &Vocabulary,
+    extracted_at: DateTime<Utc>,
+) -> Result<(), CoreError> {
+    let lemma = normalize_lemma(&vocab.word);
+
+    sqlx::query(
+        r#"
+        INSERT INTO words (
+            lemma, word, definition,
+            part_of_speech, ipa, cefr_level, synonyms, antonyms, collocations, example_sentence,
+            created_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(lemma) DO UPDATE SET
+            part_of_speech = COALESCE(words.part_of_speech, excluded.part_of_speech),
+            ipa = COALESCE(words.ipa, excluded.ipa),
+            cefr_level = COALESCE(words.cefr_level, excluded.cefr_level),
+            synonyms = COALESCE(words.synonyms, excluded.synonyms),
+            antonyms = COALESCE(words.antonyms, excluded.antonyms),
+            collocations = COALESCE(words.collocations, excluded.collocations),
+            example_sentence = COALESCE(words.example_sentence, excluded.example_sentence)
+        "#,
+    )
+    .bind(&lemma)
+    .bind(vocab.word.trim())
+    .bind(&vocab.definition)
+    .bind(&vocab.part_of_speech)
+    .bind(&vocab.ipa)
+    .bind(&vocab.cefr_level)
+    .bind(encode_list(&vocab.synonyms))
+    .bind(encode_list(&vocab.antonyms))
+    .bind(encode_list(&vocab.collocations))
+    .bind(&vocab.example_sentence)
+    .bind(extracted_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let (word_id,): (i64,) = sqlx::query_as("SELECT id FROM words WHERE lemma = ?")
+        .bind(&lemma)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO occurrences (word_id, article_url, context_sentence, definition, extracted_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(word_id)
+    .bind(&vocab.source_url)
+    .bind(&vocab.context_sentence)
+    .bind(&vocab.definition)
+    .bind(extracted_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SqliteStorage;
+    use spread_core::port::StoragePort;
+    use spread_core::query::VocabQuery;
+
+    #[tokio::test]
+    async fn test_fresh_database_is_migrated() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        assert_eq!(storage.schema_version().await.unwrap(), latest_version());
+        assert!(storage.pending_migrations().await.unwrap().is_empty());
+
+        // Running again is a no-op
+        let report = storage.migrate().await.unwrap();
+        assert!(report.applied.is_empty());
+        assert!(report.backup.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_open_leaves_database_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spread.db");
+        let db_url = format!("sqlite:{}?mode=rwc", path.display());
+
+        let missing = SqliteStorage::open_read_only(&db_url).await;
+        assert!(matches!(missing, Err(CoreError::NotFound(_))));
+        assert!(!path.exists());
+
+        SqlitePool::connect(&db_url).await.unwrap().close().await;
+        let storage = SqliteStorage::open_read_only(&db_url).await.unwrap();
+        assert_eq!(storage.schema_version().await.unwrap(), 0);
+        assert_eq!(
+            storage.pending_migrations().await.unwrap().len(),
+            MIGRATIONS.len()
+        );
+        let (tables,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM sqlite_master")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        assert_eq!(tables, 0);
+        assert!(storage.migrate().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_empty_file_database_is_not_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_url = format!("sqlite:{}?mode=rwc", dir.path().join("new.db").display());
+
+        let storage = SqliteStorage::connect(&db_url).await.unwrap();
+        let report = storage.migrate().await.unwrap();
+        assert_eq!(report.applied.len(), MIGRATIONS.len());
+        assert!(report.backup.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_newer_schema_is_rejected() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "INSERT INTO schema_version (version, name, applied_at) VALUES (?, 'future', ?)",
+        )
+        .bind(latest_version() + 1)
+        .bind(Utc::now())
+        .execute(&storage.pool)
+        .await
+        .unwrap();
+
+        assert!(storage.pending_migrations().await.is_err());
+        assert!(storage.migrate().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_vocabularies_are_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_url = format!("sqlite:{}?mode=rwc", dir.path().join("legacy.db").display());
+
+        // Schema and data as written by the original single-table storage
+        let pool = SqlitePool::connect(&db_url).await.unwrap();
+        sqlx::query(CREATE_ARTICLES_TABLE)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE vocabularies (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                word TEXT NOT NULL,
+                definition TEXT NOT NULL,
+                context_sentence TEXT NOT NULL,
+                source_url TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for url in ["https://a.example", "https://b.example"] {
+            sqlx::query(
+                r#"
+                INSERT INTO articles (url, title, content, source, published_at, collected_at)
+                VALUES (?, 'Title', 'Content', 'RSS', ?, ?)
+                "#,
+            )
+            .bind(url)
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO vocabularies (word, definition, context_sentence, source_url) VALUES
+                ('ephemeral', 'Short-lived', 'Fame is ephemeral.', 'https://a.example'),
+                ('Ephemeral', 'Brief', 'Trends are ephemeral.', 'https://b.example'),
+                ('ephemeral', 'Short-lived', 'Fame is ephemeral.', 'https://a.example'),
+                ('tenuous', 'Weak', 'A tenuous link.', 'https://a.example')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let storage = SqliteStorage::connect(&db_url).await.unwrap();
+        assert_eq!(storage.schema_version().await.unwrap(), 0);
+        assert_eq!(
+            storage.pending_migrations().await.unwrap().len(),
+            MIGRATIONS.len()
+        );
+
+        let report = storage.migrate().await.unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, latest_version());
+        let backup = report.backup.expect("existing database is backed up");
+        assert!(backup.exists());
+
+        let all = storage
+            .query_vocab(&VocabQuery::new())
+            .await
+            .unwrap()
+            .vocabulary;
+
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].word, "ephemeral");
+        assert_eq!(all[0].occurrences.len(), 2);
+        assert_eq!(all[1].word, "tenuous");
+        // Existing rows are indexed for full-text search
+        assert_eq!(storage.search_vocab("tenuous").await.unwrap().len(), 1);
+        // Rebuilt articles keep their index and get ids
+        let article_ids: Vec<i64> = storage
+            .search("content", 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|hit| match hit {
+                spread_core::SearchHit::Article { article_id, .. } => Some(article_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(article_ids.len(), 2);
+        assert!(all.iter().all(|v| v.id.is_some()));
+
+        let (legacy,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'vocabularies'",
+        )
+        .fetch_one(&storage.pool)
+        .await
+        .unwrap();
+        assert_eq!(legacy, 0);
+
+        // The backup still holds the original table
+        let backup_pool = SqlitePool::connect(&format!("sqlite:{}", backup.display()))
+            .await
+            .unwrap();
+        let (rows,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM vocabularies")
+            .fetch_one(&backup_pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 4);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_words_without_article_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_url = format!("sqlite:{}?mode=rwc", dir.path().join("orphan.db").display());
+
+        let pool = SqlitePool::connect(&db_url).await.unwrap();
+        sqlx::query(CREATE_ARTICLES_TABLE)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE vocabularies (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                word TEXT NOT NULL,
+                definition TEXT NOT NULL,
+                context_sentence TEXT NOT NULL,
+                source_url TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO vocabularies (word, definition, context_sentence, source_url) VALUES
+                ('tenuous', 'Weak', 'A tenuous link.', 'https://gone.example'),
+                ('ephemeral', 'Short-lived', 'Fame is ephemeral.', 'https://gone.example')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let storage = SqliteStorage::connect(&db_url).await.unwrap();
+        storage.migrate().await.unwrap();
+
+        let all = storage
+            .query_vocab(&VocabQuery::new())
+            .await
+            .unwrap()
+            .vocabulary;
+        assert_eq!(all.len(), 2);
+        assert!(all
+            .iter()
+            .all(|v| v.occurrences.len() == 1
+                && v.occurrences[0].source_url == "https://gone.example"));
+
+        // Both words share one placeholder article
+        let (articles,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM articles WHERE url = 'https://gone.example'")
+                .fetch_one(&storage.pool)
+                .await
+                .unwrap();
+        assert_eq!(articles, 1);
+    }
+}
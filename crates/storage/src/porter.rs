@@ -0,0 +1,246 @@
+//! The Porter stemming algorithm, as used by the SQLite FTS `porter` tokenizer.
+
+/// Stem of a lowercase word; words with anything but ASCII letters are kept as they are.
+pub(crate) fn stem(word: &str) -> String {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+    let mut w = Word(word.as_bytes().to_vec());
+    w.step1a();
+    w.step1b();
+    w.step1c();
+    w.step2();
+    w.step3();
+    w.step4();
+    w.step5();
+    String::from_utf8(w.0).unwrap_or_else(|_| word.to_string())
+}
+
+struct Word(Vec<u8>);
+
+impl Word {
+    fn is_consonant(&self, i: usize) -> bool {
+        match self.0[i] {
+            b'a' | b'e' | b'i' | b'o' | b'u' => false,
+            b'y' => i == 0 || !self.is_consonant(i - 1),
+            _ => true,
+        }
+    }
+
+    /// Number of vowel-consonant sequences in the first `len` letters.
+    fn measure(&self, len: usize) -> usize {
+        let mut m = 0;
+        let mut i = 0;
+        while i < len && self.is_consonant(i) {
+            i += 1;
+        }
+        loop {
+            while i < len && !self.is_consonant(i) {
+                i += 1;
+            }
+            if i >= len {
+                return m;
+            }
+            while i < len && self.is_consonant(i) {
+                i += 1;
+            }
+            m += 1;
+        }
+    }
+
+    fn has_vowel(&self, len: usize) -> bool {
+        (0..len).any(|i| !self.is_consonant(i))
+    }
+
+    /// Whether the first `len` letters end in a double consonant.
+    fn ends_double_consonant(&self, len: usize) -> bool {
+        len >= 2 && self.0[len - 1] == self.0[len - 2] && self.is_consonant(len - 1)
+    }
+
+    /// Whether the first `len` letters end consonant-vowel-consonant, the
+    /// last not `w`, `x` or `y`.
+    fn ends_cvc(&self, len: usize) -> bool {
+        len >= 3
+            && self.is_consonant(len - 3)
+            && !self.is_consonant(len - 2)
+            && self.is_consonant(len - 1)
+            && !matches!(self.0[len - 1], b'w' | b'x' | b'y')
+    }
+
+    fn ends_with(&self, suffix: &str) -> bool {
+        self.0.ends_with(suffix.as_bytes())
+    }
+
+    /// Length of the word without `suffix`.
+    fn stem_len(&self, suffix: &str) -> usize {
+        self.0.len() - suffix.len()
+    }
+
+    fn replace_suffix(&mut self, suffix: &str, with: &str) {
+        let len = self.stem_len(suffix);
+        self.0.truncate(len);
+        self.0.extend_from_slice(with.as_bytes());
+    }
+
+    /// Replace the first of `rules` whose suffix the word ends with, if the
+    /// rest of the word measures more than `min_measure`.
+    fn replace_first(&mut self, rules: &[(&str, &str)], min_measure: usize) {
+        if let Some((suffix, with)) = rules.iter().find(|(suffix, _)| self.ends_with(suffix)) {
+            if self.measure(self.stem_len(suffix)) > min_measure {
+                self.replace_suffix(suffix, with);
+            }
+        }
+    }
+
+    fn step1a(&mut self) {
+        if self.ends_with("sses") {
+            self.replace_suffix("sses", "ss");
+        } else if self.ends_with("ies") {
+            self.replace_suffix("ies", "i");
+        } else if !self.ends_with("ss") && self.ends_with("s") {
+            self.replace_suffix("s", "");
+        }
+    }
+
+    fn step1b(&mut self) {
+        if self.ends_with("eed") {
+            if self.measure(self.stem_len("eed")) > 0 {
+                self.replace_suffix("eed", "ee");
+            }
+            return;
+        }
+        let suffix = if self.ends_with("ed") {
+            "ed"
+        } else if self.ends_with("ing") {
+            "ing"
+        } else {
+            return;
+        };
+        if !self.has_vowel(self.stem_len(suffix)) {
+            return;
+        }
+        self.replace_suffix(suffix, "");
+
+        if self.ends_with("at") || self.ends_with("bl") || self.ends_with("iz") {
+            self.0.push(b'e');
+        } else if self.ends_double_consonant(self.0.len())
+            && !matches!(self.0.last(), Some(b'l' | b's' | b'z'))
+        {
+            self.0.pop();
+        } else if self.measure(self.0.len()) == 1 && self.ends_cvc(self.0.len()) {
+            self.0.push(b'e');
+        }
+    }
+
+    fn step1c(&mut self) {
+        if self.ends_with("y") && self.has_vowel(self.stem_len("y")) {
+            self.replace_suffix("y", "i");
+        }
+    }
+
+    fn step2(&mut self) {
+        self.replace_first(
+            &[
+                ("ational", "ate"),
+                ("tional", "tion"),
+                ("enci", "ence"),
+                ("anci", "ance"),
+                ("izer", "ize"),
+                ("abli", "able"),
+                ("alli", "al"),
+                ("entli", "ent"),
+                ("eli", "e"),
+                ("ousli", "ous"),
+                ("ization", "ize"),
+                ("ation", "ate"),
+                ("ator", "ate"),
+                ("alism", "al"),
+                ("iveness", "ive"),
+                ("fulness", "ful"),
+                ("ousness", "ous"),
+                ("aliti", "al"),
+                ("iviti", "ive"),
+                ("biliti", "ble"),
+            ],
+            0,
+        );
+    }
+
+    fn step3(&mut self) {
+        self.replace_first(
+            &[
+                ("icate", "ic"),
+                ("ative", ""),
+                ("alize", "al"),
+                ("iciti", "ic"),
+                ("ical", "ic"),
+                ("ful", ""),
+                ("ness", ""),
+            ],
+            0,
+        );
+    }
+
+    fn step4(&mut self) {
+        const SUFFIXES: &[&str] = &[
+            "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion",
+            "ou", "ism", "ate", "iti", "ous", "ive", "ize",
+        ];
+        // The longest matching suffix decides, so check longer ones first
+        let Some(suffix) = SUFFIXES
+            .iter()
+            .filter(|suffix| self.ends_with(suffix))
+            .max_by_key(|suffix| suffix.len())
+        else {
+            return;
+        };
+        let len = self.stem_len(suffix);
+        if *suffix == "ion" && !matches!(len.checked_sub(1).map(|i| self.0[i]), Some(b's' | b't')) {
+            return;
+        }
+        if self.measure(len) > 1 {
+            self.0.truncate(len);
+        }
+    }
+
+    fn step5(&mut self) {
+        if self.ends_with("e") {
+            let len = self.stem_len("e");
+            let m = self.measure(len);
+            if m > 1 || (m == 1 && !self.ends_cvc(len)) {
+                self.0.truncate(len);
+            }
+        }
+        let len = self.0.len();
+        if self.measure(len) > 1 && self.ends_double_consonant(len) && self.ends_with("l") {
+            self.0.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        for (word, expected) in [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("agreed", "agre"),
+            ("hopping", "hop"),
+            ("filing", "file"),
+            ("happy", "happi"),
+            ("relational", "relat"),
+            ("generalizations", "gener"),
+            ("mitigate", "mitig"),
+            ("mitigating", "mitig"),
+            ("roofs", "roof"),
+            ("controlling", "control"),
+            ("adoption", "adopt"),
+            ("café", "café"),
+        ] {
+            assert_eq!(stem(word), expected, "stem of {}", word);
+        }
+    }
+}
@@ -0,0 +1,1707 @@
+//! PostgreSQL storage, built with the `postgres` feature.
+//!
+//! Implements the same [`StoragePort`] as [`crate::SqliteStorage`] on a schema
+//! of its own. Query builders and row decoding are shared with SQLite; full
+//! text search uses `tsvector` columns in place of FTS5 tables.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use spread_core::error::CoreError;
+use spread_core::model::{Article, Deck, ReviewCard, SearchHit, Vocabulary, SNIPPET_HIGHLIGHT};
+use spread_core::port::StoragePort;
+use spread_core::query::{TagFilter, VocabPage, VocabQuery};
+use spread_core::run::{PipelineRun, QueuedItem};
+use spread_core::srs::{Algorithm, Grade, ReviewState, MASTERED_INTERVAL_DAYS};
+use sqlx::postgres::PgConnectOptions;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use tracing::info;
+
+use crate::{
+    encode_list, group_rows, lemma_taken, normalize_lemma, normalize_tag, page_vocab,
+    push_due_states, push_new_word_ids, push_word_ids, retention, runs, search_terms,
+    vocabulary_select, ArticleMatchRow, MigrationReport, ReviewStateRow, SearchTerm, VocabularyRow,
+    WordMatchRow, REVIEW_STATE_COLUMNS, WORD_LEXICAL_COLUMNS,
+};
+
+const CREATE_ARTICLES_TABLE: &str = r#"
+CREATE TABLE articles (
+    id BIGSERIAL PRIMARY KEY,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    source TEXT NOT NULL,
+    feed_url TEXT,
+    published_at TIMESTAMPTZ NOT NULL,
+    collected_at TIMESTAMPTZ NOT NULL,
+    compacted_at TIMESTAMPTZ,
+    search TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', title), 'A')
+        || setweight(to_tsvector('english', content), 'B')
+    ) STORED
+)
+"#;
+
+/// One row per distinct word, keyed by its normalized lemma. `contexts` holds
+/// every context sentence of the word so it can be searched.
+const CREATE_WORDS_TABLE: &str = r#"
+CREATE TABLE words (
+    id BIGSERIAL PRIMARY KEY,
+    lemma TEXT NOT NULL UNIQUE,
+    word TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    part_of_speech TEXT,
+    ipa TEXT,
+    cefr_level TEXT,
+    synonyms TEXT,
+    antonyms TEXT,
+    collocations TEXT,
+    example_sentence TEXT,
+    edited_fields TEXT,
+    suspended BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL,
+    contexts TEXT NOT NULL DEFAULT '',
+    search TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', word), 'A')
+        || setweight(to_tsvector('english', definition), 'B')
+        || setweight(to_tsvector('english', contexts), 'C')
+    ) STORED
+)
+"#;
+
+const CREATE_OCCURRENCES_TABLE: &str = r#"
+CREATE TABLE occurrences (
+    id BIGSERIAL PRIMARY KEY,
+    word_id BIGINT NOT NULL REFERENCES words(id),
+    article_url TEXT NOT NULL REFERENCES articles(url),
+    context_sentence TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    extracted_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (word_id, article_url, context_sentence)
+)
+"#;
+
+/// Keeps `words.contexts` in step with the word's occurrences.
+const CREATE_REFRESH_CONTEXTS_FUNCTION: &str = r#"
+CREATE FUNCTION refresh_word_contexts() RETURNS trigger LANGUAGE plpgsql AS $$
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        UPDATE words SET contexts = COALESCE(
+            (SELECT string_agg(context_sentence, ' ' ORDER BY id) FROM occurrences WHERE word_id = OLD.word_id), ''
+        ) WHERE id = OLD.word_id;
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        UPDATE words SET contexts = COALESCE(
+            (SELECT string_agg(context_sentence, ' ' ORDER BY id) FROM occurrences WHERE word_id = NEW.word_id), ''
+        ) WHERE id = NEW.word_id;
+    END IF;
+    RETURN NULL;
+END
+$$
+"#;
+
+const CREATE_REVIEW_STATES_TABLE: &str = r#"
+CREATE TABLE review_states (
+    word_id BIGINT PRIMARY KEY REFERENCES words(id),
+    reps BIGINT NOT NULL,
+    lapses BIGINT NOT NULL,
+    ease DOUBLE PRECISION NOT NULL,
+    interval_days BIGINT NOT NULL,
+    stability DOUBLE PRECISION NOT NULL,
+    difficulty DOUBLE PRECISION NOT NULL,
+    due_at TIMESTAMPTZ NOT NULL,
+    last_reviewed_at TIMESTAMPTZ
+)
+"#;
+
+const CREATE_REVIEW_LOG_TABLE: &str = r#"
+CREATE TABLE review_log (
+    id BIGSERIAL PRIMARY KEY,
+    word_id BIGINT NOT NULL REFERENCES words(id),
+    grade BIGINT NOT NULL,
+    algorithm TEXT NOT NULL,
+    reviewed_at TIMESTAMPTZ NOT NULL,
+    interval_days BIGINT NOT NULL,
+    ease DOUBLE PRECISION NOT NULL,
+    stability DOUBLE PRECISION NOT NULL,
+    difficulty DOUBLE PRECISION NOT NULL,
+    due_at TIMESTAMPTZ NOT NULL
+)
+"#;
+
+/// The schema of SQLite migration 9 in one step.
+const INITIAL_SCHEMA: &[&str] = &[
+    CREATE_ARTICLES_TABLE,
+    "CREATE INDEX idx_articles_collected ON articles (collected_at)",
+    "CREATE INDEX idx_articles_feed ON articles (feed_url)",
+    "CREATE INDEX idx_articles_search ON articles USING GIN (search)",
+    CREATE_WORDS_TABLE,
+    "CREATE INDEX idx_words_search ON words USING GIN (search)",
+    CREATE_OCCURRENCES_TABLE,
+    CREATE_REFRESH_CONTEXTS_FUNCTION,
+    "CREATE TRIGGER occurrences_contexts AFTER INSERT OR DELETE OR UPDATE OF word_id, context_sentence \
+     ON occurrences FOR EACH ROW EXECUTE FUNCTION refresh_word_contexts()",
+    "CREATE TABLE known_words (lemma TEXT PRIMARY KEY, added_at TIMESTAMPTZ NOT NULL)",
+    CREATE_REVIEW_STATES_TABLE,
+    CREATE_REVIEW_LOG_TABLE,
+    "CREATE INDEX idx_review_states_due ON review_states (due_at)",
+    "CREATE INDEX idx_review_log_word ON review_log (word_id, reviewed_at)",
+    "CREATE TABLE word_aliases (lemma TEXT PRIMARY KEY, word_id BIGINT NOT NULL REFERENCES words(id))",
+    "CREATE INDEX idx_word_aliases_word ON word_aliases (word_id)",
+    "CREATE TABLE tags (id BIGSERIAL PRIMARY KEY, name TEXT NOT NULL UNIQUE)",
+    "CREATE TABLE word_tags (
+        word_id BIGINT NOT NULL REFERENCES words(id),
+        tag_id BIGINT NOT NULL REFERENCES tags(id),
+        PRIMARY KEY (word_id, tag_id)
+    )",
+    "CREATE INDEX idx_word_tags_tag ON word_tags (tag_id)",
+    "CREATE TABLE decks (id BIGSERIAL PRIMARY KEY, name TEXT NOT NULL UNIQUE)",
+    "CREATE TABLE deck_tags (
+        deck_id BIGINT NOT NULL REFERENCES decks(id),
+        tag_id BIGINT NOT NULL REFERENCES tags(id),
+        PRIMARY KEY (deck_id, tag_id)
+    )",
+    "CREATE INDEX idx_deck_tags_tag ON deck_tags (tag_id)",
+    "CREATE TABLE pruned_articles (url_hash TEXT PRIMARY KEY, pruned_at TIMESTAMPTZ NOT NULL)",
+];
+
+/// The tables of SQLite migration 10.
+const PIPELINE_RUNS: &[&str] = &[
+    "CREATE TABLE runs (
+        id BIGSERIAL PRIMARY KEY,
+        triggered_by TEXT NOT NULL,
+        started_at TIMESTAMPTZ NOT NULL,
+        finished_at TIMESTAMPTZ,
+        stats TEXT NOT NULL DEFAULT '{}'
+    )",
+    "CREATE TABLE run_feeds (
+        run_id BIGINT NOT NULL REFERENCES runs(id),
+        position BIGINT NOT NULL,
+        feed_url TEXT NOT NULL,
+        items BIGINT NOT NULL,
+        articles_saved BIGINT NOT NULL,
+        vocabularies_saved BIGINT NOT NULL,
+        error TEXT,
+        PRIMARY KEY (run_id, position)
+    )",
+    "CREATE TABLE run_errors (
+        id BIGSERIAL PRIMARY KEY,
+        run_id BIGINT NOT NULL REFERENCES runs(id),
+        stage TEXT NOT NULL,
+        url TEXT NOT NULL,
+        message TEXT NOT NULL
+    )",
+    "CREATE INDEX idx_run_feeds_feed ON run_feeds (feed_url)",
+    "CREATE INDEX idx_run_errors_run ON run_errors (run_id)",
+];
+
+/// The changes of SQLite migration 11.
+const WORK_QUEUE: &[&str] = &[
+    "ALTER TABLE runs ADD COLUMN cancelled BOOLEAN NOT NULL DEFAULT FALSE",
+    "CREATE TABLE queued_items (
+        id BIGSERIAL PRIMARY KEY,
+        url TEXT NOT NULL UNIQUE,
+        feed_url TEXT NOT NULL,
+        title TEXT NOT NULL,
+        published_at TIMESTAMPTZ NOT NULL,
+        queued_at TIMESTAMPTZ NOT NULL
+    )",
+];
+
+const CREATE_SCHEMA_VERSION_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS schema_version (
+    version BIGINT PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied_at TIMESTAMPTZ NOT NULL
+)
+"#;
+
+/// `(name, statements)` of each migration, numbered from 1. Append-only, like
+/// the SQLite migrations, whose numbers these do not share.
+static MIGRATIONS: &[(&str, &[&str])] = &[
+    ("initial_schema", INITIAL_SCHEMA),
+    ("pipeline_runs", PIPELINE_RUNS),
+    ("work_queue", WORK_QUEUE),
+];
+
+/// Turn user input into a `to_tsquery` query; see [`search_terms`]. Returns
+/// `None` when nothing in the input is searchable.
+fn ts_query(input: &str) -> Option<String> {
+    let parts: Vec<String> = search_terms(input)
+        .into_iter()
+        .map(|term| {
+            let (text, suffix) = match term {
+                SearchTerm::Prefix(term) => (term, ":*"),
+                SearchTerm::Phrase(phrase) => (phrase, ""),
+            };
+            // Only letters and digits, so nothing is read as query syntax
+            let words: Vec<&str> = text
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .collect();
+            format!("({}{})", words.join(" <-> "), suffix)
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" & "))
+}
+
+/// Options for `ts_headline`, matching the FTS5 snippets of SQLite.
+fn headline_options() -> String {
+    format!(
+        "StartSel={0}, StopSel={0}, MaxWords=12, MinWords=6",
+        SNIPPET_HIGHLIGHT
+    )
+}
+
+/// Insert an article unless one with the same URL is already stored.
+async fn insert_article(conn: &mut PgConnection, article: &Article) -> Result<(), CoreError> {
+    sqlx::query(
+        r#"
+        INSERT INTO articles (url, title, content, source, feed_url, published_at, collected_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (url) DO NOTHING
+        "#,
+    )
+    .bind(&article.url)
+    .bind(&article.title)
+    .bind(&article.content)
+    .bind(format!("{:?}", article.source))
+    .bind(&article.feed_url)
+    .bind(article.published_at)
+    .bind(article.collected_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Upsert the word and record this sighting as an occurrence; see
+/// `insert_vocab` of the SQLite storage.
+async fn insert_vocab(
+    conn: &mut PgConnection,
+    vocab: &Vocabulary,
+    extracted_at: DateTime<Utc>,
+) -> Result<(), CoreError> {
+    let lemma = canonical_lemma(conn, &normalize_lemma(&vocab.word)).await?;
+    let fill_missing = WORD_LEXICAL_COLUMNS
+        .iter()
+        .map(|column| {
+            format!(
+                "{0} = CASE WHEN '{0}' IN (SELECT json_array_elements_text(COALESCE(words.edited_fields, '[]')::json)) \
+                 THEN words.{0} ELSE COALESCE(words.{0}, excluded.{0}) END",
+                column
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    let (word_id,): (i64,) = sqlx::query_as(&format!(
+        r#"
+        INSERT INTO words (
+            lemma, word, definition,
+            part_of_speech, ipa, cefr_level, synonyms, antonyms, collocations, example_sentence,
+            created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (lemma) DO UPDATE SET
+        {}
+        RETURNING id
+        "#,
+        fill_missing
+    ))
+    .bind(&lemma)
+    .bind(vocab.word.trim())
+    .bind(&vocab.definition)
+    .bind(&vocab.part_of_speech)
+    .bind(&vocab.ipa)
+    .bind(&vocab.cefr_level)
+    .bind(encode_list(&vocab.synonyms))
+    .bind(encode_list(&vocab.antonyms))
+    .bind(encode_list(&vocab.collocations))
+    .bind(&vocab.example_sentence)
+    .bind(extracted_at)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO occurrences (word_id, article_url, context_sentence, definition, extracted_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(word_id)
+    .bind(&vocab.source_url)
+    .bind(&vocab.context_sentence)
+    .bind(&vocab.definition)
+    .bind(extracted_at)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    insert_tags(conn, word_id, &vocab.tags).await?;
+
+    Ok(())
+}
+
+/// Tag a word, creating missing tags. Returns how many tags were new to the word.
+async fn insert_tags(
+    conn: &mut PgConnection,
+    word_id: i64,
+    tags: &[String],
+) -> Result<usize, CoreError> {
+    let mut added = 0;
+    for tag in tags {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            continue;
+        }
+        let tag_id = insert_tag(conn, &tag).await?;
+        added += sqlx::query(
+            "INSERT INTO word_tags (word_id, tag_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(word_id)
+        .bind(tag_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?
+        .rows_affected() as usize;
+    }
+    Ok(added)
+}
+
+/// Id of the tag with the normalized name `tag`, created if missing.
+async fn insert_tag(conn: &mut PgConnection, tag: &str) -> Result<i64, CoreError> {
+    sqlx::query("INSERT INTO tags (name) VALUES ($1) ON CONFLICT (name) DO NOTHING")
+        .bind(tag)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let (id,): (i64,) = sqlx::query_as("SELECT id FROM tags WHERE name = $1")
+        .bind(tag)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    Ok(id)
+}
+
+/// Lemma of the word `lemma` was merged into, or `lemma` itself.
+async fn canonical_lemma(conn: &mut PgConnection, lemma: &str) -> Result<String, CoreError> {
+    let merged: Option<(String,)> = sqlx::query_as(
+        "SELECT w.lemma FROM word_aliases a JOIN words w ON w.id = a.word_id WHERE a.lemma = $1",
+    )
+    .bind(lemma)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(merged.map_or_else(|| lemma.to_string(), |(lemma,)| lemma))
+}
+
+async fn word_exists(conn: &mut PgConnection, id: i64) -> Result<bool, CoreError> {
+    let (exists,): (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM words WHERE id = $1)")
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    Ok(exists)
+}
+
+pub struct PostgresStorage {
+    pool: PgPool,
+    algorithm: Algorithm,
+}
+
+impl PostgresStorage {
+    /// Connect to the database and apply any pending schema migrations.
+    pub async fn new(db_url: &str) -> Result<Self, CoreError> {
+        let storage = Self::connect(db_url).await?;
+        storage.migrate().await?;
+        Ok(storage)
+    }
+
+    /// Connect to the database without applying migrations.
+    pub async fn connect(db_url: &str) -> Result<Self, CoreError> {
+        let pool = PgPool::connect(db_url)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(Self {
+            pool,
+            algorithm: Algorithm::default(),
+        })
+    }
+
+    /// Connect with every transaction read-only, for looking without changing anything.
+    pub async fn open_read_only(db_url: &str) -> Result<Self, CoreError> {
+        let options = PgConnectOptions::from_str(db_url)
+            .map_err(|e| CoreError::Database(e.to_string()))?
+            .options([("default_transaction_read_only", "on")]);
+        let pool = PgPool::connect_with(options)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(Self {
+            pool,
+            algorithm: Algorithm::default(),
+        })
+    }
+
+    /// Set the spaced-repetition algorithm used by `record_review`
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Schema version recorded in the database (0 if never migrated).
+    pub async fn schema_version(&self) -> Result<i64, CoreError> {
+        let (versioned,): (bool,) =
+            sqlx::query_as("SELECT to_regclass('schema_version') IS NOT NULL")
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        if !versioned {
+            return Ok(0);
+        }
+
+        let (version,): (Option<i64>,) = sqlx::query_as("SELECT MAX(version) FROM schema_version")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(version.unwrap_or(0))
+    }
+
+    /// Versions and names of the migrations that `migrate` would apply.
+    pub async fn pending_migrations(&self) -> Result<Vec<(i64, &'static str)>, CoreError> {
+        let current = self.schema_version().await?;
+        Ok((1..)
+            .zip(MIGRATIONS.iter().map(|(name, _)| *name))
+            .filter(|(version, _)| *version > current)
+            .collect())
+    }
+
+    /// Apply pending migrations, each in its own transaction. Processes
+    /// starting at the same time wait for each other.
+    pub async fn migrate(&self) -> Result<MigrationReport, CoreError> {
+        sqlx::query(CREATE_SCHEMA_VERSION_TABLE)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        let from_version = self.schema_version().await?;
+        let latest = MIGRATIONS.len() as i64;
+        if from_version > latest {
+            return Err(CoreError::Database(format!(
+                "Database schema version {} is newer than this build supports ({})",
+                from_version, latest
+            )));
+        }
+
+        let mut report = MigrationReport {
+            from_version,
+            to_version: from_version,
+            applied: Vec::new(),
+            backup: None,
+        };
+        for (version, (name, statements)) in (1..).zip(MIGRATIONS) {
+            if version <= from_version {
+                continue;
+            }
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+
+            // Held until commit; another process may have migrated meanwhile
+            sqlx::query("SELECT pg_advisory_xact_lock(hashtext('spread_schema_version'))")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+            let (applied,): (bool,) =
+                sqlx::query_as("SELECT EXISTS (SELECT 1 FROM schema_version WHERE version = $1)")
+                    .bind(version)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(|e| CoreError::Database(e.to_string()))?;
+            if applied {
+                continue;
+            }
+
+            for statement in *statements {
+                sqlx::query(statement)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| {
+                        CoreError::Database(format!(
+                            "Migration {} ({}) failed: {}",
+                            version, name, e
+                        ))
+                    })?;
+            }
+            sqlx::query(
+                "INSERT INTO schema_version (version, name, applied_at) VALUES ($1, $2, $3)",
+            )
+            .bind(version)
+            .bind(*name)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+            tx.commit()
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+
+            info!(version, name, "Applied migration");
+            report.to_version = version;
+            report.applied.push((version, *name));
+        }
+
+        Ok(report)
+    }
+
+    /// Words with the given ids, in id order.
+    async fn select_vocab_with_ids(
+        &self,
+        ids: &[i64],
+    ) -> Result<Vec<(i64, Vocabulary)>, CoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = vocabulary_select::<Postgres>().replace("{}", "w.id = ANY($1)");
+        let rows: Vec<VocabularyRow> = sqlx::query_as(&sql)
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(group_rows(rows))
+    }
+
+    /// Runs selected by `clause`, in its order, with their feed results and errors.
+    async fn select_runs(&self, clause: &str) -> Result<Vec<PipelineRun>, CoreError> {
+        let rows: Vec<runs::RunRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM runs {}",
+            runs::RUN_COLUMNS,
+            clause
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i64> = rows.iter().map(|row| row.id).collect();
+        let feeds = sqlx::query_as(&format!(
+            "SELECT {} FROM run_feeds WHERE run_id = ANY($1) ORDER BY run_id, position",
+            runs::RUN_FEED_COLUMNS
+        ))
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+        let errors = sqlx::query_as(&format!(
+            "SELECT {} FROM run_errors WHERE run_id = ANY($1) ORDER BY id",
+            runs::RUN_ERROR_COLUMNS
+        ))
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        runs::assemble(rows, feeds, errors)
+    }
+
+    /// Words with the given ids, keyed by id.
+    async fn vocab_by_ids(&self, ids: &[i64]) -> Result<HashMap<i64, Vocabulary>, CoreError> {
+        Ok(self.select_vocab_with_ids(ids).await?.into_iter().collect())
+    }
+
+    /// The word with id `id`, if there is one.
+    async fn vocab_by_id(&self, id: Option<i64>) -> Result<Option<Vocabulary>, CoreError> {
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        let vocabs = self.select_vocab_with_ids(&[id]).await?;
+        Ok(vocabs.into_iter().next().map(|(_, vocab)| vocab))
+    }
+
+    /// Ranked word matches; `None` means no limit.
+    async fn match_words(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+    ) -> Result<Vec<WordMatchRow>, CoreError> {
+        let Some(query) = ts_query(query) else {
+            return Ok(Vec::new());
+        };
+        // Matches in the word itself outrank the definition, then contexts
+        sqlx::query_as(
+            r#"
+            SELECT w.id AS word_id,
+                   ts_headline('english', w.word || ' ' || w.definition || ' ' || w.contexts, q, $3) AS snippet,
+                   -ts_rank(w.search, q)::float8 AS rank
+            FROM words w, to_tsquery('english', $1) q
+            WHERE w.search @@ q
+            ORDER BY rank, w.id
+            LIMIT $2
+            "#,
+        )
+        .bind(query)
+        .bind(limit)
+        .bind(headline_options())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))
+    }
+
+    async fn match_articles(
+        &self,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<ArticleMatchRow>, CoreError> {
+        let Some(query) = ts_query(query) else {
+            return Ok(Vec::new());
+        };
+        sqlx::query_as(
+            r#"
+            SELECT a.id AS article_id, a.url, a.title,
+                   ts_headline('english', a.content, q, $3) AS snippet,
+                   -ts_rank(a.search, q)::float8 AS rank
+            FROM articles a, to_tsquery('english', $1) q
+            WHERE a.search @@ q
+            ORDER BY rank, a.id
+            LIMIT $2
+            "#,
+        )
+        .bind(query)
+        .bind(limit)
+        .bind(headline_options())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl StoragePort for PostgresStorage {
+    async fn exists(&self, url: &str) -> Result<bool, CoreError> {
+        let (exists,): (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM articles WHERE url = $1)
+                OR EXISTS (SELECT 1 FROM pruned_articles WHERE url_hash = $2)
+            "#,
+        )
+        .bind(url)
+        .bind(retention::url_fingerprint(url))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(exists)
+    }
+
+    async fn save_article(&self, article: &Article) -> Result<(), CoreError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        insert_article(&mut conn, article).await
+    }
+
+    async fn save_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        insert_vocab(&mut tx, vocab, Utc::now()).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn save_article_with_vocab(
+        &self,
+        article: &Article,
+        vocabularies: &[Vocabulary],
+    ) -> Result<(), CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        insert_article(&mut tx, article).await?;
+        let extracted_at = Utc::now();
+        for vocab in vocabularies {
+            let vocab = Vocabulary {
+                source_url: article.url.clone(),
+                ..vocab.clone()
+            };
+            insert_vocab(&mut tx, &vocab, extracted_at).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_vocab_by_id(&self, id: i64) -> Result<Option<Vocabulary>, CoreError> {
+        self.vocab_by_id(Some(id)).await
+    }
+
+    async fn get_vocab_by_word(&self, word: &str) -> Result<Option<Vocabulary>, CoreError> {
+        let id: Option<(i64,)> = sqlx::query_as(
+            "SELECT id FROM words WHERE lemma = $1 UNION SELECT word_id FROM word_aliases WHERE lemma = $1",
+        )
+        .bind(normalize_lemma(word))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        self.vocab_by_id(id.map(|(id,)| id)).await
+    }
+
+    async fn update_vocab(&self, vocab: &Vocabulary) -> Result<(), CoreError> {
+        let id = vocab
+            .id
+            .ok_or_else(|| CoreError::NotFound(format!("'{}' has not been saved", vocab.word)))?;
+        let lemma = normalize_lemma(&vocab.word);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM words WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        if exists.is_none() {
+            return Err(CoreError::NotFound(format!("Word {}", id)));
+        }
+
+        let taken: Option<(i64,)> =
+            sqlx::query_as("SELECT id FROM words WHERE lemma = $1 AND id != $2")
+                .bind(&lemma)
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        if taken.is_some() {
+            return Err(lemma_taken(&lemma));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE words SET
+                lemma = $1, word = $2, definition = $3,
+                part_of_speech = $4, ipa = $5, cefr_level = $6,
+                synonyms = $7, antonyms = $8, collocations = $9, example_sentence = $10,
+                edited_fields = $11, suspended = $12
+            WHERE id = $13
+            "#,
+        )
+        .bind(&lemma)
+        .bind(vocab.word.trim())
+        .bind(&vocab.definition)
+        .bind(&vocab.part_of_speech)
+        .bind(&vocab.ipa)
+        .bind(&vocab.cefr_level)
+        .bind(encode_list(&vocab.synonyms))
+        .bind(encode_list(&vocab.antonyms))
+        .bind(encode_list(&vocab.collocations))
+        .bind(&vocab.example_sentence)
+        .bind(encode_list(&vocab.edited_fields))
+        .bind(vocab.suspended)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))
+    }
+
+    async fn delete_vocab(&self, id: i64) -> Result<bool, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        for table in [
+            "word_tags",
+            "word_aliases",
+            "review_states",
+            "review_log",
+            "occurrences",
+        ] {
+            sqlx::query(&format!("DELETE FROM {} WHERE word_id = $1", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+        let result = sqlx::query("DELETE FROM words WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn merge_vocab(&self, from: i64, into: i64) -> Result<Vocabulary, CoreError> {
+        if from == into {
+            return Err(CoreError::Database(format!(
+                "Cannot merge word {} into itself",
+                from
+            )));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        for id in [from, into] {
+            if !word_exists(&mut tx, id).await? {
+                return Err(CoreError::NotFound(format!("Word {}", id)));
+            }
+        }
+
+        let fill_missing = WORD_LEXICAL_COLUMNS
+            .iter()
+            .map(|column| {
+                format!(
+                    "{0} = COALESCE({0}, (SELECT {0} FROM words WHERE id = $1))",
+                    column
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let statements = [
+            format!("UPDATE words SET {} WHERE id = $2", fill_missing),
+            // Contexts already recorded for `into` stay as they are
+            "UPDATE occurrences o SET word_id = $2 WHERE word_id = $1 AND NOT EXISTS (\
+             SELECT 1 FROM occurrences x WHERE x.word_id = $2 \
+             AND x.article_url = o.article_url AND x.context_sentence = o.context_sentence)"
+                .to_string(),
+            "DELETE FROM occurrences WHERE word_id = $1".to_string(),
+            "INSERT INTO word_tags (word_id, tag_id) SELECT $2, tag_id FROM word_tags WHERE word_id = $1 ON CONFLICT DO NOTHING".to_string(),
+            "DELETE FROM word_tags WHERE word_id = $1".to_string(),
+            "UPDATE review_log SET word_id = $2 WHERE word_id = $1".to_string(),
+            // `into` keeps its own schedule if it has one
+            format!(
+                "INSERT INTO review_states ({0}) SELECT $2, {1} FROM review_states WHERE word_id = $1 ON CONFLICT DO NOTHING",
+                REVIEW_STATE_COLUMNS,
+                REVIEW_STATE_COLUMNS.trim_start_matches("word_id, ")
+            ),
+            "DELETE FROM review_states WHERE word_id = $1".to_string(),
+            "UPDATE word_aliases SET word_id = $2 WHERE word_id = $1".to_string(),
+            "INSERT INTO word_aliases (lemma, word_id) SELECT lemma, $2 FROM words WHERE id = $1 \
+             ON CONFLICT (lemma) DO UPDATE SET word_id = excluded.word_id"
+                .to_string(),
+            "DELETE FROM words WHERE id = $1".to_string(),
+        ];
+        for statement in &statements {
+            // Postgres rejects parameters a statement does not use
+            let mut query = sqlx::query(statement).bind(from);
+            if statement.contains("$2") {
+                query = query.bind(into);
+            }
+            query
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        self.get_vocab_by_id(into)
+            .await?
+            .ok_or_else(|| CoreError::NotFound(format!("Word {}", into)))
+    }
+
+    async fn query_vocab(&self, query: &VocabQuery) -> Result<VocabPage, CoreError> {
+        let select = vocabulary_select::<Postgres>();
+        let (prefix, suffix) = select.split_once("{}").unwrap_or((&select, ""));
+        let mut sql = QueryBuilder::<Postgres>::new(prefix);
+        sql.push("w.id IN (");
+        push_word_ids(&mut sql, query)?;
+        sql.push(")");
+        sql.push(suffix);
+
+        let rows: Vec<VocabularyRow> = sql
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(page_vocab(group_rows(rows), query))
+    }
+
+    async fn search_vocab(&self, query: &str) -> Result<Vec<Vocabulary>, CoreError> {
+        let matches = self.match_words(query, None).await?;
+        let ids: Vec<i64> = matches.iter().map(|m| m.word_id).collect();
+        let mut vocabs = self.vocab_by_ids(&ids).await?;
+
+        Ok(ids.iter().filter_map(|id| vocabs.remove(id)).collect())
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>, CoreError> {
+        let words = self.match_words(query, Some(limit as i64)).await?;
+        let ids: Vec<i64> = words.iter().map(|m| m.word_id).collect();
+        let mut vocabs = self.vocab_by_ids(&ids).await?;
+
+        let mut hits: Vec<SearchHit> = words
+            .into_iter()
+            .filter_map(|m| {
+                Some(SearchHit::Word {
+                    word_id: m.word_id,
+                    vocabulary: Box::new(vocabs.remove(&m.word_id)?),
+                    snippet: m.snippet,
+                    rank: m.rank,
+                })
+            })
+            .collect();
+
+        hits.extend(
+            self.match_articles(query, limit as i64)
+                .await?
+                .into_iter()
+                .map(|m| SearchHit::Article {
+                    article_id: m.article_id,
+                    url: m.url,
+                    title: m.title,
+                    snippet: m.snippet,
+                    rank: m.rank,
+                }),
+        );
+        Ok(hits)
+    }
+
+    async fn get_random_vocab(&self) -> Result<Option<Vocabulary>, CoreError> {
+        let id: Option<(i64,)> = sqlx::query_as("SELECT id FROM words ORDER BY random() LIMIT 1")
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        self.vocab_by_id(id.map(|(id,)| id)).await
+    }
+
+    async fn add_known_words(&self, words: &[String]) -> Result<usize, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let now = Utc::now();
+        let mut added = 0;
+        for word in words {
+            let lemma = word.trim().to_lowercase();
+            if lemma.is_empty() {
+                continue;
+            }
+            let result = sqlx::query(
+                "INSERT INTO known_words (lemma, added_at) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            )
+            .bind(&lemma)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+            added += result.rows_affected() as usize;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(added)
+    }
+
+    async fn remove_known_word(&self, word: &str) -> Result<bool, CoreError> {
+        let result = sqlx::query("DELETE FROM known_words WHERE lemma = $1")
+            .bind(word.trim().to_lowercase())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_known_words(&self) -> Result<Vec<String>, CoreError> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT lemma FROM known_words ORDER BY added_at DESC, lemma")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|(lemma,)| lemma).collect())
+    }
+
+    async fn get_mastered_words(&self) -> Result<Vec<String>, CoreError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT w.lemma FROM words w
+            JOIN review_states r ON r.word_id = w.id
+            WHERE r.interval_days >= $1
+            ORDER BY w.lemma
+            "#,
+        )
+        .bind(MASTERED_INTERVAL_DAYS as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|(lemma,)| lemma).collect())
+    }
+
+    async fn add_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        if !word_exists(&mut tx, word_id).await? {
+            return Err(CoreError::NotFound(format!("Word {}", word_id)));
+        }
+        let added = insert_tags(&mut tx, word_id, tags).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(added)
+    }
+
+    async fn remove_tags(&self, word_id: i64, tags: &[String]) -> Result<usize, CoreError> {
+        let mut removed = 0;
+        for tag in tags {
+            removed += sqlx::query(
+                "DELETE FROM word_tags WHERE word_id = $1 AND tag_id = (SELECT id FROM tags WHERE name = $2)",
+            )
+            .bind(word_id)
+            .bind(normalize_tag(tag))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?
+            .rows_affected() as usize;
+        }
+        Ok(removed)
+    }
+
+    async fn get_tags(&self) -> Result<Vec<(String, usize)>, CoreError> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT t.name, COUNT(*) FROM tags t
+            JOIN word_tags wt ON wt.tag_id = t.id
+            GROUP BY t.id
+            ORDER BY t.name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, count)| (name, count as usize))
+            .collect())
+    }
+
+    async fn save_deck(&self, deck: &Deck) -> Result<(), CoreError> {
+        let name = deck.name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Parse("Deck name must not be empty".to_string()));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        sqlx::query("INSERT INTO decks (name) VALUES ($1) ON CONFLICT (name) DO NOTHING")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        let (deck_id,): (i64,) = sqlx::query_as("SELECT id FROM decks WHERE name = $1")
+            .bind(name)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        sqlx::query("DELETE FROM deck_tags WHERE deck_id = $1")
+            .bind(deck_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        for tag in &deck.tags {
+            let tag = normalize_tag(tag);
+            if tag.is_empty() {
+                continue;
+            }
+            let tag_id = insert_tag(&mut tx, &tag).await?;
+            sqlx::query(
+                "INSERT INTO deck_tags (deck_id, tag_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            )
+            .bind(deck_id)
+            .bind(tag_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete_deck(&self, name: &str) -> Result<bool, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        sqlx::query(
+            "DELETE FROM deck_tags WHERE deck_id IN (SELECT id FROM decks WHERE name = $1)",
+        )
+        .bind(name.trim())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+        let result = sqlx::query("DELETE FROM decks WHERE name = $1")
+            .bind(name.trim())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_decks(&self) -> Result<Vec<Deck>, CoreError> {
+        let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT d.name, t.name FROM decks d
+            LEFT JOIN deck_tags dt ON dt.deck_id = d.id
+            LEFT JOIN tags t ON t.id = dt.tag_id
+            ORDER BY d.name, t.name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let mut decks: Vec<Deck> = Vec::new();
+        for (name, tag) in rows {
+            if decks.last().map(|d| &d.name) != Some(&name) {
+                decks.push(Deck {
+                    name,
+                    tags: Vec::new(),
+                });
+            }
+            if let (Some(deck), Some(tag)) = (decks.last_mut(), tag) {
+                deck.tags.push(tag);
+            }
+        }
+        Ok(decks)
+    }
+
+    async fn get_due_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError> {
+        let mut sql = QueryBuilder::<Postgres>::new("");
+        push_due_states(&mut sql, limit, filter);
+        let states: Vec<ReviewStateRow> = sql
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let ids: Vec<i64> = states.iter().map(|s| s.word_id).collect();
+        let mut vocabs = self.vocab_by_ids(&ids).await?;
+
+        Ok(states
+            .into_iter()
+            .filter_map(|row| {
+                let vocabulary = vocabs.remove(&row.word_id)?;
+                Some(ReviewCard {
+                    word_id: row.word_id,
+                    vocabulary,
+                    state: row.into(),
+                })
+            })
+            .collect())
+    }
+
+    async fn get_new_vocab(
+        &self,
+        limit: usize,
+        filter: &TagFilter,
+    ) -> Result<Vec<ReviewCard>, CoreError> {
+        let mut sql = QueryBuilder::<Postgres>::new("");
+        push_new_word_ids(&mut sql, limit, filter);
+        let ids: Vec<i64> = sql
+            .build_query_scalar()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let now = Utc::now();
+        Ok(self
+            .select_vocab_with_ids(&ids)
+            .await?
+            .into_iter()
+            .map(|(word_id, vocabulary)| ReviewCard {
+                word_id,
+                vocabulary,
+                state: ReviewState::new(now),
+            })
+            .collect())
+    }
+
+    async fn record_review(&self, word_id: i64, grade: Grade) -> Result<ReviewState, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        if !word_exists(&mut tx, word_id).await? {
+            return Err(CoreError::NotFound(format!("Word {}", word_id)));
+        }
+
+        let now = Utc::now();
+        let current: Option<ReviewStateRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM review_states WHERE word_id = $1 FOR UPDATE",
+            REVIEW_STATE_COLUMNS
+        ))
+        .bind(word_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+        let current = current.map_or_else(|| ReviewState::new(now), ReviewState::from);
+
+        let next = self.algorithm.scheduler().schedule(&current, grade, now);
+
+        let update = REVIEW_STATE_COLUMNS
+            .split(", ")
+            .skip(1)
+            .map(|column| format!("{0} = excluded.{0}", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        sqlx::query(&format!(
+            "INSERT INTO review_states ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+             ON CONFLICT (word_id) DO UPDATE SET {}",
+            REVIEW_STATE_COLUMNS, update
+        ))
+        .bind(word_id)
+        .bind(next.reps as i64)
+        .bind(next.lapses as i64)
+        .bind(next.ease)
+        .bind(next.interval_days as i64)
+        .bind(next.stability)
+        .bind(next.difficulty)
+        .bind(next.due_at)
+        .bind(next.last_reviewed_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO review_log (
+                word_id, grade, algorithm, reviewed_at,
+                interval_days, ease, stability, difficulty, due_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(word_id)
+        .bind(grade.as_u8() as i64)
+        .bind(self.algorithm.name())
+        .bind(now)
+        .bind(next.interval_days as i64)
+        .bind(next.ease)
+        .bind(next.stability)
+        .bind(next.difficulty)
+        .bind(next.due_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(next)
+    }
+
+    async fn start_run(&self, run: &PipelineRun) -> Result<i64, CoreError> {
+        let (id,): (i64,) = sqlx::query_as(
+            "INSERT INTO runs (triggered_by, started_at, stats) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(run.trigger.to_string())
+        .bind(run.started_at)
+        .bind(runs::encode_stats(&run.stats))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(id)
+    }
+
+    async fn update_run(&self, run: &PipelineRun) -> Result<(), CoreError> {
+        let id = run
+            .id
+            .ok_or_else(|| CoreError::NotFound("Run without an id".to_string()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let result = sqlx::query(
+            "UPDATE runs SET finished_at = $1, cancelled = $2, stats = $3 WHERE id = $4",
+        )
+        .bind(run.finished_at)
+        .bind(run.cancelled)
+        .bind(runs::encode_stats(&run.stats))
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(CoreError::NotFound(format!("Run {}", id)));
+        }
+
+        for table in ["run_feeds", "run_errors"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE run_id = $1", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+        for (position, feed) in run.feeds.iter().enumerate() {
+            sqlx::query(&format!(
+                "INSERT INTO run_feeds (position, {}) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                runs::RUN_FEED_COLUMNS
+            ))
+            .bind(position as i64)
+            .bind(id)
+            .bind(&feed.feed_url)
+            .bind(feed.items as i64)
+            .bind(feed.articles_saved as i64)
+            .bind(feed.vocabularies_saved as i64)
+            .bind(&feed.error)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+        for error in &run.errors {
+            sqlx::query(&format!(
+                "INSERT INTO run_errors ({}) VALUES ($1, $2, $3, $4)",
+                runs::RUN_ERROR_COLUMNS
+            ))
+            .bind(id)
+            .bind(error.stage.to_string())
+            .bind(&error.url)
+            .bind(&error.message)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_run(&self, id: i64) -> Result<Option<PipelineRun>, CoreError> {
+        Ok(self.select_runs(&format!("WHERE id = {}", id)).await?.pop())
+    }
+
+    async fn get_runs(&self, limit: Option<usize>) -> Result<Vec<PipelineRun>, CoreError> {
+        let limit = limit.map_or("ALL".to_string(), |limit| limit.to_string());
+        self.select_runs(&format!("ORDER BY id DESC LIMIT {}", limit))
+            .await
+    }
+
+    async fn enqueue_items(&self, items: &[QueuedItem]) -> Result<usize, CoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        let now = Utc::now();
+        let mut added = 0;
+        for item in items {
+            let result = sqlx::query(&format!(
+                "INSERT INTO queued_items ({}, queued_at) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (url) DO NOTHING",
+                runs::QUEUED_ITEM_COLUMNS
+            ))
+            .bind(&item.feed_url)
+            .bind(&item.url)
+            .bind(&item.title)
+            .bind(item.published_at)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+            added += result.rows_affected() as usize;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(added)
+    }
+
+    async fn get_queued_items(&self) -> Result<Vec<QueuedItem>, CoreError> {
+        let rows: Vec<runs::QueuedItemRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM queued_items ORDER BY id",
+            runs::QUEUED_ITEM_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(rows.into_iter().map(QueuedItem::from).collect())
+    }
+
+    async fn dequeue_item(&self, url: &str) -> Result<bool, CoreError> {
+        let result = sqlx::query("DELETE FROM queued_items WHERE url = $1")
+            .bind(url)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::net::TcpListener;
+    use std::process::{Child, Command, Stdio};
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    use sqlx::{Connection, Executor};
+
+    use super::*;
+    use crate::tests::TestStorage;
+
+    /// URL of a server to test against instead of starting one, without a database name
+    const ENV_TEST_SERVER: &str = "SPREAD_TEST_POSTGRES_URL";
+
+    struct Server {
+        url: String,
+        /// Stops the server when the test process exits and closes its stdin
+        _guard: Option<Child>,
+    }
+
+    static SERVER: OnceLock<Option<Server>> = OnceLock::new();
+
+    /// Start a throwaway server with `initdb` and `postgres` from PATH. `None`
+    /// when they are missing or refuse to run, e.g. as root.
+    fn start_server() -> Option<Server> {
+        if let Ok(url) = std::env::var(ENV_TEST_SERVER) {
+            return Some(Server {
+                url: url.trim_end_matches('/').to_string(),
+                _guard: None,
+            });
+        }
+
+        let dir = std::env::temp_dir().join(format!("spread-postgres-{}", std::process::id()));
+        let initdb = Command::new("initdb")
+            .arg("--pgdata")
+            .arg(&dir)
+            .args(["--username=postgres", "--auth=trust", "--encoding=UTF8"])
+            .args(["--no-sync", "--no-instructions"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .ok()?;
+        if !initdb.success() {
+            let _ = std::fs::remove_dir_all(&dir);
+            return None;
+        }
+
+        let port = TcpListener::bind("127.0.0.1:0")
+            .ok()?
+            .local_addr()
+            .ok()?
+            .port();
+        let guard = Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "postgres -D \"$0\" -k \"$0\" -p {} -c listen_addresses=127.0.0.1 -c fsync=off & \
+                 read _; kill $!; wait; rm -rf \"$0\"",
+                port
+            ))
+            .arg(&dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+
+        Some(Server {
+            url: format!("postgres://postgres@127.0.0.1:{}", port),
+            _guard: Some(guard),
+        })
+    }
+
+    /// Storage on a fresh database named after `test`. Panics without a
+    /// server: building with the `postgres` feature is the opt-in to these tests.
+    pub(crate) async fn storage(test: &str) -> PostgresStorage {
+        let Some(server) = SERVER.get_or_init(start_server) else {
+            panic!(
+                "{}: no PostgreSQL server (set {} or put initdb on PATH)",
+                test, ENV_TEST_SERVER
+            );
+        };
+
+        // A server just started takes a moment to accept connections
+        let admin_url = format!("{}/postgres", server.url);
+        let mut attempts = 0;
+        let mut admin = loop {
+            match sqlx::PgConnection::connect(&admin_url).await {
+                Ok(conn) => break conn,
+                Err(e) if attempts == 100 => panic!("PostgreSQL did not start: {}", e),
+                Err(_) => {
+                    attempts += 1;
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+        };
+
+        let database = format!("spread_{}", test);
+        admin
+            .execute(format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", database).as_str())
+            .await
+            .unwrap();
+        admin
+            .execute(format!("CREATE DATABASE {}", database).as_str())
+            .await
+            .unwrap();
+
+        PostgresStorage::new(&format!("{}/{}", server.url, database))
+            .await
+            .expect("Failed to create storage")
+    }
+
+    #[async_trait]
+    impl TestStorage for PostgresStorage {
+        fn days_ago(days: i64) -> String {
+            format!("now() - interval '{} days'", days)
+        }
+
+        fn with_algorithm(self, algorithm: Algorithm) -> Self {
+            PostgresStorage::with_algorithm(self, algorithm)
+        }
+
+        async fn execute(&self, sql: &str) {
+            sqlx::query(sql).execute(&self.pool).await.unwrap();
+        }
+
+        async fn fetch_i64(&self, sql: &str) -> i64 {
+            sqlx::query_scalar(sql).fetch_one(&self.pool).await.unwrap()
+        }
+
+        async fn fetch_string(&self, sql: &str) -> String {
+            sqlx::query_scalar(sql).fetch_one(&self.pool).await.unwrap()
+        }
+
+        async fn reject_word(&self, lemma: &str, reject: bool) {
+            if reject {
+                self.execute(&format!(
+                    "CREATE FUNCTION reject_word() RETURNS trigger LANGUAGE plpgsql AS $$
+                     BEGIN
+                         IF NEW.lemma = '{}' THEN RAISE EXCEPTION 'rejected'; END IF;
+                         RETURN NEW;
+                     END
+                     $$",
+                    lemma
+                ))
+                .await;
+                self.execute(
+                    "CREATE TRIGGER reject_word BEFORE INSERT ON words \
+                     FOR EACH ROW EXECUTE FUNCTION reject_word()",
+                )
+                .await;
+            } else {
+                self.execute("DROP FUNCTION reject_word CASCADE").await;
+            }
+        }
+    }
+
+    #[test]
+    fn test_ts_query() {
+        assert_eq!(ts_query("serendip").as_deref(), Some("(serendip:*)"));
+        assert_eq!(
+            ts_query(r#"risk "mitigate the" e-mail"#).as_deref(),
+            Some("(risk:*) & (mitigate <-> the) & (e <-> mail:*)")
+        );
+        // Query syntax in the input is dropped
+        assert_eq!(ts_query("a&b|!c:*").as_deref(), Some("(a <-> b <-> c:*)"));
+        assert_eq!(ts_query(" - ? "), None);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_is_idempotent() {
+        let storage = storage("test_migrate_is_idempotent").await;
+        let latest = MIGRATIONS.len() as i64;
+        assert_eq!(storage.schema_version().await.unwrap(), latest);
+        let report = storage.migrate().await.unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(report.to_version, latest);
+    }
+}
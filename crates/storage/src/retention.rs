@@ -0,0 +1,314 @@
+//! Shrinking old articles and reclaiming database space.
+
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use spread_core::error::CoreError;
+use sqlx::SqlitePool;
+
+/// Environment variable with the number of days full article bodies are kept
+pub const ENV_RETENTION_DAYS: &str = "SPREAD_RETENTION_DAYS";
+/// Environment variable that, when `true` or `1`, also prunes old articles without words
+pub const ENV_PRUNE_EMPTY_ARTICLES: &str = "SPREAD_PRUNE_EMPTY_ARTICLES";
+
+/// How long article bodies are kept.
+///
+/// Older articles keep their title, URL and the sentences their words were
+/// seen in. With `prune_empty`, older articles that gave no words are deleted,
+/// leaving only a fingerprint of the URL so they are not collected again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub days: u32,
+    pub prune_empty: bool,
+}
+
+impl RetentionPolicy {
+    pub fn new(days: u32) -> Self {
+        Self {
+            days,
+            prune_empty: false,
+        }
+    }
+
+    pub fn with_prune_empty(mut self, prune_empty: bool) -> Self {
+        self.prune_empty = prune_empty;
+        self
+    }
+
+    /// Read from `SPREAD_RETENTION_DAYS` and `SPREAD_PRUNE_EMPTY_ARTICLES`;
+    /// `None` when no valid retention period is set.
+    pub fn from_env() -> Option<Self> {
+        let days = std::env::var(ENV_RETENTION_DAYS)
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        let prune_empty = std::env::var(ENV_PRUNE_EMPTY_ARTICLES)
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        Some(Self::new(days).with_prune_empty(prune_empty))
+    }
+}
+
+/// Outcome of [`crate::SqliteStorage::compact_articles`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Articles whose body was cut down to the referenced sentences
+    pub compacted: usize,
+    /// Articles without words that were deleted
+    pub pruned: usize,
+    /// Article text removed, in bytes
+    pub content_bytes_removed: u64,
+}
+
+/// Outcome of [`crate::SqliteStorage::vacuum`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct VacuumReport {
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+impl VacuumReport {
+    pub fn reclaimed(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+/// Stored in place of the URL of a pruned article.
+pub(crate) fn url_fingerprint(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub(crate) async fn compact(
+    pool: &SqlitePool,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> Result<CompactionReport, CoreError> {
+    let cutoff = now - Duration::days(i64::from(policy.days));
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let content_bytes = "SELECT COALESCE(SUM(length(CAST(content AS BLOB))), 0) FROM articles";
+    let (bytes_before,): (i64,) = sqlx::query_as(content_bytes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    let mut report = CompactionReport::default();
+    if policy.prune_empty {
+        let urls: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT url FROM articles a
+            WHERE julianday(a.collected_at) < julianday(?)
+              AND NOT EXISTS (SELECT 1 FROM occurrences o WHERE o.article_url = a.url)
+            "#,
+        )
+        .bind(cutoff)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+        for (url,) in &urls {
+            sqlx::query(
+                "INSERT OR IGNORE INTO pruned_articles (url_hash, pruned_at) VALUES (?, ?)",
+            )
+            .bind(url_fingerprint(url))
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CoreError::Database(e.to_string()))?;
+            sqlx::query("DELETE FROM articles WHERE url = ?")
+                .bind(url)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CoreError::Database(e.to_string()))?;
+        }
+        report.pruned = urls.len();
+    }
+
+    // Each referenced sentence once, in the order it was first recorded
+    report.compacted = sqlx::query(
+        r#"
+        UPDATE articles SET
+            content = COALESCE((
+                SELECT group_concat(sentence, char(10)) FROM (
+                    SELECT o.context_sentence AS sentence FROM occurrences o
+                    WHERE o.article_url = articles.url
+                    GROUP BY o.context_sentence
+                    ORDER BY MIN(o.id)
+                )
+            ), ''),
+            compacted_at = ?
+        WHERE compacted_at IS NULL AND julianday(collected_at) < julianday(?)
+        "#,
+    )
+    .bind(now)
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?
+    .rows_affected() as usize;
+
+    let (bytes_after,): (i64,) = sqlx::query_as(content_bytes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    report.content_bytes_removed = (bytes_before - bytes_after).max(0) as u64;
+
+    tx.commit()
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+
+    Ok(report)
+}
+
+/// Rebuild the database file, returning its size before and after.
+pub(crate) async fn vacuum(pool: &SqlitePool) -> Result<VacuumReport, CoreError> {
+    let size_before = database_size(pool).await?;
+    sqlx::query("VACUUM")
+        .execute(pool)
+        .await
+        .map_err(|e| CoreError::Database(e.to_string()))?;
+    let size_after = database_size(pool).await?;
+
+    Ok(VacuumReport {
+        size_before,
+        size_after,
+    })
+}
+
+async fn database_size(pool: &SqlitePool) -> Result<u64, CoreError> {
+    let (size,): (i64,) = sqlx::query_as(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| CoreError::Database(e.to_string()))?;
+    Ok(size as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Fixture;
+    use crate::SqliteStorage;
+    use spread_core::port::StoragePort;
+
+    /// Article at `url` with a long body, collected `age_days` ago
+    fn article(url: &str, age_days: i64, words: &[&str]) -> Fixture {
+        Fixture::new(url, words)
+            .with_body(&"A long body. ".repeat(100))
+            .with_collected_at(Utc::now() - Duration::days(age_days))
+    }
+
+    async fn content(storage: &SqliteStorage, url: &str) -> Option<String> {
+        sqlx::query_as::<_, (String,)>("SELECT content FROM articles WHERE url = ?")
+            .bind(url)
+            .fetch_optional(&storage.pool)
+            .await
+            .unwrap()
+            .map(|(content,)| content)
+    }
+
+    #[tokio::test]
+    async fn test_compact_keeps_referenced_sentences() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        article("https://example.com/old", 40, &["lucid", "terse"])
+            .save(&storage)
+            .await;
+        article("https://example.com/new", 1, &["lucid"])
+            .save(&storage)
+            .await;
+        article("https://example.com/empty", 40, &[])
+            .save(&storage)
+            .await;
+
+        let report = storage
+            .compact_articles(&RetentionPolicy::new(30))
+            .await
+            .unwrap();
+        assert_eq!(report.compacted, 2);
+        assert_eq!(report.pruned, 0);
+        assert!(report.content_bytes_removed > 2000);
+
+        assert_eq!(
+            content(&storage, "https://example.com/old").await.unwrap(),
+            "The lucid essay.\nThe terse essay."
+        );
+        assert_eq!(
+            content(&storage, "https://example.com/empty")
+                .await
+                .unwrap(),
+            ""
+        );
+        assert!(content(&storage, "https://example.com/new")
+            .await
+            .unwrap()
+            .starts_with("A long body."));
+
+        // Words and titles are untouched, and compacting again changes nothing
+        let lucid = storage.get_vocab_by_word("lucid").await.unwrap().unwrap();
+        assert_eq!(lucid.occurrences.len(), 2);
+        let report = storage
+            .compact_articles(&RetentionPolicy::new(30))
+            .await
+            .unwrap();
+        assert_eq!(report, CompactionReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_pruned_articles_are_still_deduplicated() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        article("https://example.com/old", 40, &["lucid"])
+            .save(&storage)
+            .await;
+        article("https://example.com/empty", 40, &[])
+            .save(&storage)
+            .await;
+
+        let policy = RetentionPolicy::new(30).with_prune_empty(true);
+        let report = storage.compact_articles(&policy).await.unwrap();
+        assert_eq!(report.pruned, 1);
+        assert_eq!(report.compacted, 1);
+
+        assert!(content(&storage, "https://example.com/empty")
+            .await
+            .is_none());
+        assert!(storage.exists("https://example.com/empty").await.unwrap());
+        assert!(storage.exists("https://example.com/old").await.unwrap());
+        assert!(!storage.exists("https://example.com/other").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_reports_reclaimed_space() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("spread.db").display());
+        let storage = SqliteStorage::new(&url).await.unwrap();
+        for i in 0..20 {
+            article(&format!("https://example.com/{}", i), 40, &[])
+                .save(&storage)
+                .await;
+        }
+        storage
+            .compact_articles(&RetentionPolicy::new(30).with_prune_empty(true))
+            .await
+            .unwrap();
+
+        let report = storage.vacuum().await.unwrap();
+        assert!(report.reclaimed() > 0);
+        assert_eq!(report.size_before - report.reclaimed(), report.size_after);
+    }
+
+    #[test]
+    fn test_url_fingerprint() {
+        assert_eq!(url_fingerprint("https://example.com").len(), 64);
+        assert_ne!(
+            url_fingerprint("https://example.com/a"),
+            url_fingerprint("https://example.com/b")
+        );
+    }
+}
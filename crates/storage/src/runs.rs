@@ -0,0 +1,108 @@
+//! Rows of the `runs`, `run_feeds`, `run_errors` and `queued_items` tables.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use spread_core::error::CoreError;
+use spread_core::run::{FeedRun, PipelineRun, QueuedItem, RunError, RunStats};
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct RunRow {
+    pub(crate) id: i64,
+    triggered_by: String,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+    cancelled: bool,
+    stats: String,
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct RunFeedRow {
+    run_id: i64,
+    feed_url: String,
+    items: i64,
+    articles_saved: i64,
+    vocabularies_saved: i64,
+    error: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct RunErrorRow {
+    run_id: i64,
+    stage: String,
+    url: String,
+    message: String,
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct QueuedItemRow {
+    feed_url: String,
+    url: String,
+    title: String,
+    published_at: DateTime<Utc>,
+}
+
+impl From<QueuedItemRow> for QueuedItem {
+    fn from(row: QueuedItemRow) -> Self {
+        Self {
+            feed_url: row.feed_url,
+            url: row.url,
+            title: row.title,
+            published_at: row.published_at,
+        }
+    }
+}
+
+pub(crate) const RUN_COLUMNS: &str = "id, triggered_by, started_at, finished_at, cancelled, stats";
+pub(crate) const RUN_FEED_COLUMNS: &str =
+    "run_id, feed_url, items, articles_saved, vocabularies_saved, error";
+pub(crate) const RUN_ERROR_COLUMNS: &str = "run_id, stage, url, message";
+pub(crate) const QUEUED_ITEM_COLUMNS: &str = "feed_url, url, title, published_at";
+
+/// Counts of a run as stored in `runs.stats`, a JSON object.
+pub(crate) fn encode_stats(stats: &RunStats) -> String {
+    serde_json::to_string(stats).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Join runs with their feed results and errors, keeping the order of `runs`.
+/// Feed results and errors keep their order too.
+pub(crate) fn assemble(
+    runs: Vec<RunRow>,
+    feeds: Vec<RunFeedRow>,
+    errors: Vec<RunErrorRow>,
+) -> Result<Vec<PipelineRun>, CoreError> {
+    let mut feeds_by_run: HashMap<i64, Vec<FeedRun>> = HashMap::new();
+    for row in feeds {
+        feeds_by_run.entry(row.run_id).or_default().push(FeedRun {
+            feed_url: row.feed_url,
+            items: row.items as usize,
+            articles_saved: row.articles_saved as usize,
+            vocabularies_saved: row.vocabularies_saved as usize,
+            error: row.error,
+        });
+    }
+    let mut errors_by_run: HashMap<i64, Vec<RunError>> = HashMap::new();
+    for row in errors {
+        errors_by_run.entry(row.run_id).or_default().push(RunError {
+            stage: row.stage.parse().map_err(CoreError::Parse)?,
+            url: row.url,
+            message: row.message,
+        });
+    }
+
+    runs.into_iter()
+        .map(|row| {
+            Ok(PipelineRun {
+                id: Some(row.id),
+                trigger: row.triggered_by.parse().map_err(CoreError::Parse)?,
+                started_at: row.started_at,
+                finished_at: row.finished_at,
+                cancelled: row.cancelled,
+                // Counters added later default to zero
+                stats: serde_json::from_str(&row.stats).unwrap_or_default(),
+                feeds: feeds_by_run.remove(&row.id).unwrap_or_default(),
+                errors: errors_by_run.remove(&row.id).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,116 @@
+//! Test fixtures shared by the storage backends and their callers.
+//!
+//! Enabled in this crate's tests and, for other crates, by the `testing` feature.
+
+use chrono::{DateTime, Utc};
+use spread_core::model::{Article, SourceType, Vocabulary};
+use spread_core::port::StoragePort;
+
+/// An article and the words seen in it, saved together.
+///
+/// Each word gets the context sentence `The <word> essay.` and the definition
+/// `Definition of <word>`; the article body is those sentences.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub article: Article,
+    pub vocabulary: Vec<Vocabulary>,
+}
+
+impl Fixture {
+    /// Article at `url` with `words`, collected now
+    pub fn new(url: &str, words: &[&str]) -> Self {
+        let vocabulary: Vec<Vocabulary> = words
+            .iter()
+            .map(|word| Vocabulary {
+                word: word.to_string(),
+                definition: format!("Definition of {}", word),
+                context_sentence: format!("The {} essay.", word),
+                source_url: url.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let content = vocabulary
+            .iter()
+            .map(|vocab| vocab.context_sentence.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let now = Utc::now();
+        Self {
+            article: Article {
+                id: None,
+                url: url.to_string(),
+                title: format!("Title of {}", url),
+                content,
+                source: SourceType::RSS,
+                feed_url: None,
+                published_at: now,
+                collected_at: now,
+            },
+            vocabulary,
+        }
+    }
+
+    /// `word` alone in its own article at `https://example.com/<word>`
+    pub fn word(word: &str) -> Self {
+        Self::new(&format!("https://example.com/{}", word), &[word])
+    }
+
+    /// Text placed before the words' sentences in the article body
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.article.content = format!("{}{}", body, self.article.content);
+        self
+    }
+
+    /// Publication and collection time
+    pub fn with_collected_at(mut self, collected_at: DateTime<Utc>) -> Self {
+        self.article.published_at = collected_at;
+        self.article.collected_at = collected_at;
+        self
+    }
+
+    pub fn with_feed(mut self, feed_url: &str) -> Self {
+        self.article.feed_url = Some(feed_url.to_string());
+        self
+    }
+
+    pub fn with_source(mut self, source: SourceType) -> Self {
+        self.article.source = source;
+        self
+    }
+
+    /// Tags on every word
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        for vocab in &mut self.vocabulary {
+            vocab.tags = tags.iter().map(|t| t.to_string()).collect();
+        }
+        self
+    }
+
+    /// Synonyms of every word
+    pub fn with_synonyms(mut self, synonyms: &[&str]) -> Self {
+        for vocab in &mut self.vocabulary {
+            vocab.synonyms = synonyms.iter().map(|s| s.to_string()).collect();
+        }
+        self
+    }
+
+    /// Save the article with its words and return their ids, in order.
+    pub async fn save<S: StoragePort + ?Sized>(&self, storage: &S) -> Vec<i64> {
+        storage
+            .save_article_with_vocab(&self.article, &self.vocabulary)
+            .await
+            .unwrap();
+
+        let mut ids = Vec::new();
+        for vocab in &self.vocabulary {
+            let saved = storage.get_vocab_by_word(&vocab.word).await.unwrap();
+            ids.push(saved.and_then(|v| v.id).unwrap());
+        }
+        ids
+    }
+}
+
+/// Save `word` from its own article and return its id
+pub async fn save_word<S: StoragePort + ?Sized>(storage: &S, word: &str) -> i64 {
+    Fixture::word(word).save(storage).await[0]
+}